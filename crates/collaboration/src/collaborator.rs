@@ -1,11 +1,13 @@
 use parking_lot::RwLock;
 use pollster::block_on;
-use radiantkit_core::{RadiantDocumentListener, RadiantDocumentNode, RadiantNode};
+use radiantkit_core::{
+    RadiantDocumentListener, RadiantDocumentNode, RadiantNode, RadiantStyle, RadiantSymbol,
+};
 use std::sync::{Arc, Weak};
 use uuid::Uuid;
 use y_sync::awareness::{Awareness, UpdateSubscription as AwarenessUpdateSubscription};
 use yrs::{
-    types::{map::MapEvent, EntryChange},
+    types::{array::ArrayEvent, map::MapEvent, EntryChange},
     *,
 };
 
@@ -19,12 +21,17 @@ type Connection = NativeConnection;
 #[cfg(target_arch = "wasm32")]
 type Connection = WasmConnection;
 
+type EventSubscription<E> = Subscription<Arc<dyn Fn(&TransactionMut, &E)>>;
+
 pub struct Collaborator<N: RadiantNode> {
     id: Uuid,
     _document: Weak<RwLock<RadiantDocumentNode<N>>>,
     connection: Arc<RwLock<Connection>>,
     _awareness_sub: Option<AwarenessUpdateSubscription>,
-    _root_sub: Subscription<Arc<dyn Fn(&TransactionMut, &MapEvent)>>,
+    _root_sub: EventSubscription<MapEvent>,
+    _order_sub: EventSubscription<ArrayEvent>,
+    _symbols_sub: EventSubscription<MapEvent>,
+    _styles_sub: EventSubscription<MapEvent>,
 }
 
 impl<'a, N: 'static + RadiantNode + serde::de::DeserializeOwned> Collaborator<N> {
@@ -50,20 +57,111 @@ impl<'a, N: 'static + RadiantNode + serde::de::DeserializeOwned> Collaborator<N>
                 .iter()
                 .for_each(|(key, change)| match change {
                     EntryChange::Inserted(val) => {
-                        let id = Uuid::parse_str(key).unwrap();
-                        let node: String = val.clone().cast().unwrap();
-                        let mut node: N = serde_json::from_str(&node).unwrap();
+                        let Ok(id) = Uuid::parse_str(key) else {
+                            return;
+                        };
+                        let Ok(data) = val.clone().cast::<String>() else {
+                            return;
+                        };
+                        let registry = document.registry.clone();
+                        let mut node: N = match registry.scope(|| serde_json::from_str(&data)) {
+                            Ok(node) => node,
+                            Err(err) => {
+                                log::error!("Failed to read node {}: {}", id, err);
+                                return;
+                            }
+                        };
                         node.set_needs_tessellation(false);
                         if document.get_node(id).is_none() {
                             document.add_excluding_listener(node, extension_id);
                         }
                     }
-                    EntryChange::Removed(_val) => {}
+                    EntryChange::Removed(_val) => {
+                        if let Ok(id) = Uuid::parse_str(key) {
+                            document.remove_excluding_listener(id, extension_id);
+                        }
+                    }
                     EntryChange::Updated(_old, new) => {
-                        let id = Uuid::parse_str(key).unwrap();
-                        if let Some(mut node) = document.get_node_mut(id) {
-                            let n: String = new.clone().cast().unwrap();
-                            node.replace(&n);
+                        let Ok(id) = Uuid::parse_str(key) else {
+                            return;
+                        };
+                        let Ok(data) = new.clone().cast::<String>() else {
+                            return;
+                        };
+                        document.replace_node_state(id, &data);
+                    }
+                });
+        });
+
+        // Ids of the nodes in the order auto layouts place them in.
+        let mut order = doc.get_or_insert_array("radiantkit-order");
+        let document_clone = document.clone();
+        let order_sub = order.observe(move |txn, event| {
+            let Some(document) = document_clone.upgrade() else {
+                return;
+            };
+            let Some(mut document) = document.try_write() else {
+                return;
+            };
+            let order = event
+                .target()
+                .iter(txn)
+                .filter_map(|id| Uuid::parse_str(&id.to_string(txn)).ok())
+                .collect::<Vec<_>>();
+            document.set_order(&order);
+        });
+
+        let mut symbols = doc.get_or_insert_map("radiantkit-symbols");
+        let document_clone = document.clone();
+        let symbols_sub = symbols.observe(move |txn, event| {
+            let Some(document) = document_clone.upgrade() else {
+                return;
+            };
+            let Some(mut document) = document.try_write() else {
+                return;
+            };
+            event
+                .keys(txn)
+                .iter()
+                .for_each(|(key, change)| match change {
+                    EntryChange::Inserted(val) | EntryChange::Updated(_, val) => {
+                        let data: String = val.clone().cast().unwrap_or_default();
+                        match serde_json::from_str::<RadiantSymbol>(&data) {
+                            Ok(symbol) => document.insert_symbol(symbol),
+                            Err(err) => log::error!("Failed to read symbol {}: {}", key, err),
+                        }
+                    }
+                    EntryChange::Removed(_val) => {
+                        if let Ok(id) = Uuid::parse_str(key) {
+                            document.symbols.remove(&id);
+                        }
+                    }
+                });
+        });
+
+        let mut styles = doc.get_or_insert_map("radiantkit-styles");
+        let document_clone = document.clone();
+        let styles_sub = styles.observe(move |txn, event| {
+            let Some(document) = document_clone.upgrade() else {
+                return;
+            };
+            let Some(mut document) = document.try_write() else {
+                return;
+            };
+            event
+                .keys(txn)
+                .iter()
+                .for_each(|(key, change)| match change {
+                    EntryChange::Inserted(val) | EntryChange::Updated(_, val) => {
+                        let data: String = val.clone().cast().unwrap_or_default();
+                        match serde_json::from_str::<RadiantStyle>(&data) {
+                            Ok(style) => document.insert_style(style),
+                            Err(err) => log::error!("Failed to read style {}: {}", key, err),
+                        }
+                    }
+                    EntryChange::Removed(_val) => {
+                        if let Ok(id) = Uuid::parse_str(key) {
+                            document.styles.remove(&id);
                         }
                     }
                 });
@@ -101,6 +199,9 @@ impl<'a, N: 'static + RadiantNode + serde::de::DeserializeOwned> Collaborator<N>
             connection,
             _awareness_sub: awareness_sub,
             _root_sub: root_sub,
+            _order_sub: order_sub,
+            _symbols_sub: symbols_sub,
+            _styles_sub: styles_sub,
         })
     }
 }
@@ -133,6 +234,9 @@ impl<N: RadiantNode> RadiantDocumentListener<N> for Collaborator<N> {
                         serde_json::to_string(node).unwrap(),
                     );
                 }
+                if let Some(order) = txn.get_array("radiantkit-order") {
+                    order.push_back(&mut txn, id.to_string());
+                }
                 txn.commit();
             }
         });
@@ -151,9 +255,53 @@ impl<N: RadiantNode> RadiantDocumentListener<N> for Collaborator<N> {
         #[cfg(target_arch = "wasm32")]
         handle_node_change(connection_clone, id, data);
     }
+
+    fn on_symbol_changed(&mut self, symbol: &RadiantSymbol) {
+        let Ok(data) = serde_json::to_string(symbol) else {
+            return;
+        };
+        insert_into_map(&self.connection, "radiantkit-symbols", symbol.id, &data);
+    }
+
+    fn on_style_changed(&mut self, style: &RadiantStyle) {
+        let Ok(data) = serde_json::to_string(style) else {
+            return;
+        };
+        insert_into_map(&self.connection, "radiantkit-styles", style.id, &data);
+    }
+
+    fn on_node_removed(&mut self, id: Uuid) {
+        let connection = self.connection.write();
+        let awareness = connection.awareness();
+        #[cfg(not(target_arch = "wasm32"))]
+        let Ok(awareness) = awareness.try_write() else { return };
+        #[cfg(target_arch = "wasm32")]
+        let Some(awareness) = awareness.try_write() else { return };
+        let doc = awareness.doc();
+        let Ok(mut txn) = doc.try_transact_mut() else {
+            log::error!("Failed to transact");
+            return;
+        };
+        if let Some(root) = txn.get_map("radiantkit-root") {
+            root.remove(&mut txn, &id.to_string());
+        }
+        if let Some(order) = txn.get_array("radiantkit-order") {
+            let index = order
+                .iter(&txn)
+                .position(|value| value.to_string(&txn) == id.to_string());
+            if let Some(index) = index {
+                order.remove(&mut txn, index as u32);
+            }
+        }
+        txn.commit();
+    }
 }
 
 fn handle_node_change(connection: Arc<RwLock<Connection>>, id: Uuid, data: &str) {
+    insert_into_map(&connection, "radiantkit-root", id, data);
+}
+
+fn insert_into_map(connection: &RwLock<Connection>, name: &str, id: Uuid, data: &str) {
     let connection = connection.write();
     let awareness = connection.awareness();
     #[cfg(not(target_arch = "wasm32"))]
@@ -165,8 +313,8 @@ fn handle_node_change(connection: Arc<RwLock<Connection>>, id: Uuid, data: &str)
         log::error!("Failed to transact");
         return;
     };
-    if let Some(root) = txn.get_map("radiantkit-root") {
-        root.insert(
+    if let Some(map) = txn.get_map(name) {
+        map.insert(
             &mut txn,
             id.to_string(),
             data,
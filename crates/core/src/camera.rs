@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+pub const MIN_ZOOM: f32 = 0.02;
+pub const MAX_ZOOM: f32 = 256.0;
+/// Zoom levels per doubling of the zoom.
+const ZOOM_LEVELS_PER_OCTAVE: f32 = 2.0;
+
+/// Part of the document shown in the window. Positions on screen are given in points from
+/// the top left corner of the window, positions in the document in document points.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadiantCamera {
+    /// Document position shown at the top left corner of the window.
+    pub offset: [f32; 2],
+    /// Screen points per document point.
+    pub zoom: f32,
+}
+
+impl Default for RadiantCamera {
+    fn default() -> Self {
+        Self {
+            offset: [0.0, 0.0],
+            zoom: 1.0,
+        }
+    }
+}
+
+impl RadiantCamera {
+    pub fn new(offset: [f32; 2], zoom: f32) -> Self {
+        Self {
+            offset,
+            zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+        }
+    }
+
+    pub fn to_document(&self, position: [f32; 2]) -> [f32; 2] {
+        [
+            self.offset[0] + position[0] / self.zoom,
+            self.offset[1] + position[1] / self.zoom,
+        ]
+    }
+
+    pub fn to_screen(&self, position: [f32; 2]) -> [f32; 2] {
+        [
+            (position[0] - self.offset[0]) * self.zoom,
+            (position[1] - self.offset[1]) * self.zoom,
+        ]
+    }
+
+    /// Moves the document by `delta` screen points.
+    pub fn pan(&mut self, delta: [f32; 2]) {
+        self.offset = [
+            self.offset[0] - delta[0] / self.zoom,
+            self.offset[1] - delta[1] / self.zoom,
+        ];
+    }
+
+    /// Scales the zoom by `factor`, keeping the document position `anchor` at the same
+    /// place on screen.
+    pub fn zoom_at(&mut self, factor: f32, anchor: [f32; 2]) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let scale = self.zoom / zoom;
+        self.offset = [
+            anchor[0] - (anchor[0] - self.offset[0]) * scale,
+            anchor[1] - (anchor[1] - self.offset[1]) * scale,
+        ];
+        self.zoom = zoom;
+    }
+
+    /// Level of the zoom nodes are tessellated for. Zooming within a level keeps their
+    /// tessellation, which is only redone once the zoom reaches another level.
+    pub fn zoom_level(&self) -> i32 {
+        (self.zoom.log2() * ZOOM_LEVELS_PER_OCTAVE).round() as i32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RadiantCamera;
+
+    #[test]
+    fn zoom_levels_span_half_octaves() {
+        let level = |zoom| RadiantCamera::new([0.0, 0.0], zoom).zoom_level();
+        assert_eq!(level(1.0), 0);
+        assert_eq!(level(1.1), 0);
+        assert_eq!(level(0.9), 0);
+        assert_eq!(level(1.5), 1);
+        assert_eq!(level(2.0), 2);
+        assert_eq!(level(0.5), -2);
+    }
+}
@@ -0,0 +1,271 @@
+use std::{any::Any, collections::BTreeMap, fmt::Debug};
+
+use crate::{RadiantComponent, RadiantTypeRegistry};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Component stored in the component map of a node. Once registered with
+/// [`RadiantTypeRegistry::register_component_type`], it is serialized under its type tag.
+pub trait RadiantTypedComponent:
+    RadiantComponent + Serialize + DeserializeOwned + Clone + Debug + 'static
+{
+    const TYPE_TAG: &'static str;
+}
+
+/// Object safe part of [`RadiantTypedComponent`].
+pub trait RadiantDynComponent: Debug {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn clone_box(&self) -> Box<dyn RadiantDynComponent>;
+    fn to_value(&self) -> serde_json::Result<serde_json::Value>;
+}
+
+impl<T: RadiantTypedComponent> RadiantDynComponent for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn RadiantDynComponent> {
+        Box::new(self.clone())
+    }
+
+    fn to_value(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(self)
+    }
+}
+
+#[derive(Debug)]
+enum ComponentEntry {
+    Typed(Box<dyn RadiantDynComponent>),
+    /// Component of a type that is not registered, or that failed to deserialize, kept as
+    /// it is so that it round-trips.
+    Unregistered(serde_json::Value),
+}
+
+impl Clone for ComponentEntry {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Typed(component) => Self::Typed(component.clone_box()),
+            Self::Unregistered(value) => Self::Unregistered(value.clone()),
+        }
+    }
+}
+
+/// Components a node carries besides its transform, selection and color, keyed by type tag.
+#[derive(Debug, Clone, Default)]
+pub struct RadiantComponentMap {
+    components: BTreeMap<String, ComponentEntry>,
+}
+
+impl RadiantComponentMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    pub fn type_tags(&self) -> impl Iterator<Item = &str> {
+        self.components.keys().map(|type_tag| type_tag.as_str())
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.components.values().find_map(|entry| match entry {
+            ComponentEntry::Typed(component) => component.as_any().downcast_ref(),
+            ComponentEntry::Unregistered(_) => None,
+        })
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.components.values_mut().find_map(|entry| match entry {
+            ComponentEntry::Typed(component) => component.as_any_mut().downcast_mut(),
+            ComponentEntry::Unregistered(_) => None,
+        })
+    }
+
+    /// Component of type `T`, adding the default one when the map holds none.
+    pub fn get_or_insert_default<T: RadiantTypedComponent + Default>(&mut self) -> &mut T {
+        if self.get::<T>().is_none() {
+            self.insert(T::default());
+        }
+        self.get_mut().expect("the component was just inserted")
+    }
+
+    /// Adds `component`, returning the component of the same type it replaces.
+    pub fn insert<T: RadiantTypedComponent>(&mut self, component: T) -> Option<T> {
+        let previous = self.remove::<T>();
+        self.components.insert(
+            T::TYPE_TAG.to_string(),
+            ComponentEntry::Typed(Box::new(component)),
+        );
+        previous
+    }
+
+    pub fn remove<T: RadiantTypedComponent>(&mut self) -> Option<T> {
+        match self.components.remove(T::TYPE_TAG)? {
+            ComponentEntry::Typed(component) => component.as_any().downcast_ref().cloned(),
+            ComponentEntry::Unregistered(_) => None,
+        }
+    }
+
+    /// Component with the type tag `type_tag` as it is serialized.
+    pub fn value(&self, type_tag: &str) -> Option<serde_json::Value> {
+        match self.components.get(type_tag)? {
+            ComponentEntry::Typed(component) => component.to_value().ok(),
+            ComponentEntry::Unregistered(value) => Some(value.clone()),
+        }
+    }
+
+    /// Sets the component with the type tag `type_tag` from its serialized value, which
+    /// must deserialize to the type registered in `registry` if there is one.
+    pub fn set_value(
+        &mut self,
+        registry: &RadiantTypeRegistry,
+        type_tag: &str,
+        value: serde_json::Value,
+    ) -> serde_json::Result<()> {
+        let entry = match registry.component(type_tag, value.clone()) {
+            Some(component) => ComponentEntry::Typed(component?),
+            None => ComponentEntry::Unregistered(value),
+        };
+        self.components.insert(type_tag.to_string(), entry);
+        Ok(())
+    }
+
+    /// Deserializes the components kept unregistered whose type `registry` now knows, as
+    /// once a type is registered with the document.
+    pub fn upgrade(&mut self, registry: &RadiantTypeRegistry) {
+        for (type_tag, entry) in &mut self.components {
+            let ComponentEntry::Unregistered(value) = entry else {
+                continue;
+            };
+            match registry.component(type_tag, value.clone()) {
+                Some(Ok(component)) => *entry = ComponentEntry::Typed(component),
+                Some(Err(err)) => log::warn!("Invalid {} component: {}", type_tag, err),
+                None => {}
+            }
+        }
+    }
+
+    pub fn remove_value(&mut self, type_tag: &str) -> bool {
+        self.components.remove(type_tag).is_some()
+    }
+}
+
+impl Serialize for RadiantComponentMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut values = BTreeMap::new();
+        for (type_tag, entry) in &self.components {
+            let value = match entry {
+                ComponentEntry::Typed(component) => {
+                    component.to_value().map_err(serde::ser::Error::custom)?
+                }
+                ComponentEntry::Unregistered(value) => value.clone(),
+            };
+            values.insert(type_tag, value);
+        }
+        values.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RadiantComponentMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+        let mut components = Self::new();
+        // Outside of the scope of a document, components of other types than the built-in
+        // ones stay unregistered until a document upgrades them.
+        let registry = RadiantTypeRegistry::scoped().unwrap_or_default();
+        for (type_tag, value) in values {
+            // A malformed component is kept as it is rather than failing the whole node.
+            if let Err(err) = components.set_value(&registry, &type_tag, value.clone()) {
+                log::warn!("Invalid {} component: {}", type_tag, err);
+                components
+                    .components
+                    .insert(type_tag, ComponentEntry::Unregistered(value));
+            }
+        }
+        Ok(components)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        BaseNode, RadiantComponent, RadiantComponentMap, RadiantMetadataComponent,
+        RadiantTypeRegistry, RadiantTypedComponent, StrokeComponent,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    struct Weight(f32);
+
+    impl RadiantComponent for Weight {}
+
+    impl RadiantTypedComponent for Weight {
+        const TYPE_TAG: &'static str = "weight";
+    }
+
+    #[test]
+    fn unknown_and_malformed_components_round_trip() {
+        let data = serde_json::json!({
+            "metadata": { "author": "someone" },
+            "unknown": { "value": 1 },
+            "weight": "heavy",
+        });
+        let mut registry = RadiantTypeRegistry::new();
+        registry.register_component_type::<Weight>();
+
+        let components: RadiantComponentMap = registry
+            .scope(|| serde_json::from_value(data.clone()))
+            .unwrap();
+        assert_eq!(
+            components.get::<RadiantMetadataComponent>().unwrap().0["author"],
+            "someone"
+        );
+        assert!(components.get::<Weight>().is_none());
+        assert_eq!(serde_json::to_value(&components).unwrap(), data);
+    }
+
+    #[test]
+    fn unregistered_components_are_upgraded_once_registered() {
+        let data = serde_json::json!({ "weight": 2.0 });
+        let mut components: RadiantComponentMap = serde_json::from_value(data.clone()).unwrap();
+        assert!(components.get::<Weight>().is_none());
+
+        let mut registry = RadiantTypeRegistry::new();
+        registry.register_component_type::<Weight>();
+        components.upgrade(&registry);
+        assert_eq!(components.get::<Weight>(), Some(&Weight(2.0)));
+        assert_eq!(serde_json::to_value(&components).unwrap(), data);
+    }
+
+    #[test]
+    fn nodes_carry_their_stroke_layer_layout_and_style_in_the_map() {
+        let mut base = BaseNode::default();
+        base.stroke_mut().set_width(3.0);
+        let data = serde_json::to_value(&base).unwrap();
+        let tags = base.components.type_tags().collect::<Vec<_>>();
+        assert_eq!(tags, ["layer", "layout", "stroke", "style"]);
+        assert_eq!(data["components"]["stroke"]["width"], 3.0);
+        assert!(data.get("stroke").is_none());
+
+        // Components missing from the data are added with their defaults.
+        let mut data = data;
+        data["components"] = serde_json::json!({ "stroke": data["components"]["stroke"] });
+        let mut base: BaseNode = serde_json::from_value(data).unwrap();
+        assert_eq!(base.components.type_tags().count(), 4);
+        assert_eq!(base.stroke().width(), 3.0);
+
+        base.components.remove::<StrokeComponent>();
+        assert_eq!(base.stroke(), &StrokeComponent::default());
+        base.stroke_mut().set_width(2.0);
+        assert_eq!(
+            base.get_component::<StrokeComponent>().unwrap().width(),
+            2.0
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use epaint::{Color32, Rect};
+use serde::{Deserialize, Serialize};
+
+/// Visual effect rendered together with a node. Lengths are in points.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RadiantEffect {
+    DropShadow {
+        offset: [f32; 2],
+        blur: f32,
+        spread: f32,
+        color: Color32,
+    },
+    InnerShadow {
+        offset: [f32; 2],
+        blur: f32,
+        spread: f32,
+        color: Color32,
+    },
+    LayerBlur {
+        radius: f32,
+    },
+    BackgroundBlur {
+        radius: f32,
+    },
+}
+
+impl RadiantEffect {
+    /// Area covered by the node once this effect is applied to a node covering `rect`.
+    pub fn expand_bounds(&self, rect: Rect) -> Rect {
+        match self {
+            RadiantEffect::DropShadow {
+                offset,
+                blur,
+                spread,
+                ..
+            } => rect.union(
+                rect.translate((*offset).into())
+                    .expand(spread.max(0.0) + blur.max(0.0)),
+            ),
+            RadiantEffect::LayerBlur { radius } => rect.expand(radius.max(0.0)),
+            RadiantEffect::InnerShadow { .. } | RadiantEffect::BackgroundBlur { .. } => rect,
+        }
+    }
+
+    /// Blur radius of the effect.
+    pub fn blur_radius(&self) -> f32 {
+        match self {
+            RadiantEffect::DropShadow { blur, .. } | RadiantEffect::InnerShadow { blur, .. } => {
+                *blur
+            }
+            RadiantEffect::LayerBlur { radius } | RadiantEffect::BackgroundBlur { radius } => {
+                *radius
+            }
+        }
+        .max(0.0)
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{RadiantComponent, RadiantEffect, RadiantLayerMarker, RadiantTypedComponent};
+use epaint::{ClippedPrimitive, Rect};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantBlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
+#[cfg_attr(not(target_arch = "wasm32"), radiantkit_macros::radiant_wasm_bindgen)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LayerComponent {
+    opacity: f32,
+    blend_mode: RadiantBlendMode,
+    effects: Vec<RadiantEffect>,
+    /// Whether the node is not drawn but clips its siblings to its shape instead.
+    mask: bool,
+}
+
+impl Default for LayerComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LayerComponent {
+    pub fn new() -> Self {
+        Self {
+            opacity: 1.0,
+            blend_mode: RadiantBlendMode::Normal,
+            effects: Vec::new(),
+            mask: false,
+        }
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    pub fn blend_mode(&self) -> RadiantBlendMode {
+        self.blend_mode
+    }
+
+    pub fn effects(&self) -> &[RadiantEffect] {
+        &self.effects
+    }
+
+    pub fn is_mask(&self) -> bool {
+        self.mask
+    }
+
+    /// Whether the node has to be rendered offscreen and composited.
+    pub fn is_isolated(&self) -> bool {
+        self.opacity < 1.0
+            || self.blend_mode != RadiantBlendMode::Normal
+            || !self.effects.is_empty()
+    }
+
+    /// Grows `rect` to include the area affected by the effects.
+    pub fn expand_bounds(&self, rect: Rect) -> Rect {
+        self.effects.iter().fold(rect, |bounds, effect| {
+            bounds.union(effect.expand_bounds(rect))
+        })
+    }
+}
+
+impl LayerComponent {
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: RadiantBlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    pub fn set_effects(&mut self, effects: Vec<RadiantEffect>) {
+        self.effects = effects;
+    }
+
+    pub fn set_mask(&mut self, mask: bool) {
+        self.mask = mask;
+    }
+}
+
+impl LayerComponent {
+    /// Encloses `primitives` in layer markers so the render manager composites them as a
+    /// whole with this opacity, blend mode and effects. `bounds` is the area the layer may
+    /// cover, effects included.
+    pub fn wrap(
+        &self,
+        mut primitives: Vec<ClippedPrimitive>,
+        bounds: Rect,
+    ) -> Vec<ClippedPrimitive> {
+        if !self.is_isolated() || primitives.is_empty() {
+            return primitives;
+        }
+        primitives.insert(0, RadiantLayerMarker::Begin.into());
+        primitives.push(
+            RadiantLayerMarker::End {
+                style: self.clone(),
+                bounds,
+            }
+            .into(),
+        );
+        primitives
+    }
+}
+
+impl RadiantComponent for LayerComponent {}
+
+impl RadiantTypedComponent for LayerComponent {
+    const TYPE_TAG: &'static str = "layer";
+}
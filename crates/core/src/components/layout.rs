@@ -0,0 +1,395 @@
+use epaint::{pos2, Rect, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::{RadiantComponent, RadiantTypedComponent};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantLayoutDirection {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantLayoutAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+}
+
+/// How the size of a node along an axis is determined in an auto layout.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantLayoutSizing {
+    /// Keeps its own size.
+    #[default]
+    Fixed,
+    /// Fits its content.
+    Hug,
+    /// Takes the space left in the parent.
+    Fill,
+}
+
+/// How a node follows its parent group along an axis when the group is resized.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantConstraint {
+    /// Keeps its distance to the left or top edge.
+    #[default]
+    Start,
+    /// Keeps its distance to the right or bottom edge.
+    End,
+    /// Keeps its distance to both edges, stretching with the group.
+    StartAndEnd,
+    /// Keeps its distance to the center.
+    Center,
+    /// Keeps its position and size relative to the group.
+    Scale,
+}
+
+impl RadiantConstraint {
+    /// Start and length along an axis of a child at `child` once its parent is resized from
+    /// `old_frame` to `new_frame`, all given as start and length.
+    pub fn apply(&self, old_frame: [f32; 2], new_frame: [f32; 2], child: [f32; 2]) -> [f32; 2] {
+        let [old_start, old_length] = old_frame;
+        let [new_start, new_length] = new_frame;
+        let [start, length] = child;
+        match self {
+            RadiantConstraint::Start => [new_start + start - old_start, length],
+            RadiantConstraint::End => [
+                new_start + new_length - (old_start + old_length - start),
+                length,
+            ],
+            RadiantConstraint::StartAndEnd => [
+                new_start + start - old_start,
+                (length + new_length - old_length).max(0.0),
+            ],
+            RadiantConstraint::Center => [
+                new_start + new_length / 2.0 - (old_start + old_length / 2.0 - start),
+                length,
+            ],
+            RadiantConstraint::Scale => {
+                if old_length <= 0.0 {
+                    return [new_start + start - old_start, length];
+                }
+                let ratio = new_length / old_length;
+                [new_start + (start - old_start) * ratio, length * ratio]
+            }
+        }
+    }
+}
+
+/// Size and sizing of a child laid out by an auto layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RadiantLayoutItem {
+    pub size: Vec2,
+    pub sizing: [RadiantLayoutSizing; 2],
+}
+
+/// Lays out the children of a group in a row or a column.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct AutoLayoutComponent {
+    direction: RadiantLayoutDirection,
+    gap: f32,
+    /// Padding at the top, right, bottom and left.
+    padding: [f32; 4],
+    /// Alignment of the children along the direction.
+    main_alignment: RadiantLayoutAlignment,
+    /// Alignment of the children across the direction.
+    cross_alignment: RadiantLayoutAlignment,
+    /// Sizing of the group itself, horizontally and vertically.
+    sizing: [RadiantLayoutSizing; 2],
+}
+
+impl Default for AutoLayoutComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AutoLayoutComponent {
+    pub fn new() -> Self {
+        Self {
+            direction: RadiantLayoutDirection::Horizontal,
+            gap: 0.0,
+            padding: [0.0; 4],
+            main_alignment: RadiantLayoutAlignment::Start,
+            cross_alignment: RadiantLayoutAlignment::Start,
+            sizing: [RadiantLayoutSizing::Hug; 2],
+        }
+    }
+
+    pub fn direction(&self) -> RadiantLayoutDirection {
+        self.direction
+    }
+
+    pub fn gap(&self) -> f32 {
+        self.gap
+    }
+
+    pub fn padding(&self) -> [f32; 4] {
+        self.padding
+    }
+
+    pub fn main_alignment(&self) -> RadiantLayoutAlignment {
+        self.main_alignment
+    }
+
+    pub fn cross_alignment(&self) -> RadiantLayoutAlignment {
+        self.cross_alignment
+    }
+
+    pub fn sizing(&self) -> [RadiantLayoutSizing; 2] {
+        self.sizing
+    }
+}
+
+impl AutoLayoutComponent {
+    pub fn set_direction(&mut self, direction: RadiantLayoutDirection) {
+        self.direction = direction;
+    }
+
+    pub fn set_gap(&mut self, gap: f32) {
+        self.gap = gap.max(0.0);
+    }
+
+    pub fn set_padding(&mut self, padding: [f32; 4]) {
+        self.padding = padding.map(|padding| padding.max(0.0));
+    }
+
+    pub fn set_main_alignment(&mut self, alignment: RadiantLayoutAlignment) {
+        self.main_alignment = alignment;
+    }
+
+    pub fn set_cross_alignment(&mut self, alignment: RadiantLayoutAlignment) {
+        self.cross_alignment = alignment;
+    }
+
+    pub fn set_sizing(&mut self, sizing: [RadiantLayoutSizing; 2]) {
+        self.sizing = sizing;
+    }
+}
+
+impl AutoLayoutComponent {
+    /// Places `items` inside a frame at `origin`, returning the size of the frame and the
+    /// rect of every item. `size` is the current size of the frame, kept on the axes it is
+    /// not hugging its content.
+    pub fn arrange(
+        &self,
+        origin: Vec2,
+        size: Vec2,
+        items: &[RadiantLayoutItem],
+    ) -> (Vec2, Vec<Rect>) {
+        let (main, cross) = match self.direction {
+            RadiantLayoutDirection::Horizontal => (0, 1),
+            RadiantLayoutDirection::Vertical => (1, 0),
+        };
+        let [top, right, bottom, left] = self.padding;
+        // Padding before and after the children along each axis.
+        let padding = [[left, right], [top, bottom]];
+        let gaps = self.gap * items.len().saturating_sub(1) as f32;
+
+        let is_fill =
+            |item: &RadiantLayoutItem, axis: usize| item.sizing[axis] == RadiantLayoutSizing::Fill;
+        let fixed_main = items
+            .iter()
+            .filter(|item| !is_fill(item, main))
+            .map(|item| item.size[main])
+            .sum::<f32>();
+        let fill_count = items.iter().filter(|item| is_fill(item, main)).count();
+        let content_cross = items
+            .iter()
+            .filter(|item| !is_fill(item, cross))
+            .fold(0.0_f32, |max, item| max.max(item.size[cross]));
+
+        let mut frame_size = size;
+        if self.sizing[main] == RadiantLayoutSizing::Hug {
+            frame_size[main] = fixed_main + gaps + padding[main][0] + padding[main][1];
+        }
+        if self.sizing[cross] == RadiantLayoutSizing::Hug {
+            frame_size[cross] = content_cross + padding[cross][0] + padding[cross][1];
+        }
+
+        let inner_main = (frame_size[main] - padding[main][0] - padding[main][1]).max(0.0);
+        let inner_cross = (frame_size[cross] - padding[cross][0] - padding[cross][1]).max(0.0);
+        let fill_main = if fill_count > 0 {
+            (inner_main - fixed_main - gaps).max(0.0) / fill_count as f32
+        } else {
+            0.0
+        };
+
+        let sizes = items
+            .iter()
+            .map(|item| {
+                let mut item_size = item.size;
+                if is_fill(item, main) {
+                    item_size[main] = fill_main;
+                }
+                if is_fill(item, cross) {
+                    item_size[cross] = inner_cross;
+                }
+                item_size
+            })
+            .collect::<Vec<_>>();
+
+        let total_main = sizes.iter().map(|size| size[main]).sum::<f32>() + gaps;
+        let mut offset =
+            padding[main][0] + Self::align(self.main_alignment, inner_main - total_main);
+        let rects = sizes
+            .iter()
+            .map(|item_size| {
+                let mut position = Vec2::ZERO;
+                position[main] = offset;
+                position[cross] = padding[cross][0]
+                    + Self::align(self.cross_alignment, inner_cross - item_size[cross]);
+                offset += item_size[main] + self.gap;
+                Rect::from_min_size(pos2(origin.x, origin.y) + position, *item_size)
+            })
+            .collect();
+
+        (frame_size, rects)
+    }
+
+    fn align(alignment: RadiantLayoutAlignment, free_space: f32) -> f32 {
+        match alignment {
+            RadiantLayoutAlignment::Start => 0.0,
+            RadiantLayoutAlignment::Center => free_space / 2.0,
+            RadiantLayoutAlignment::End => free_space,
+        }
+    }
+}
+
+impl RadiantComponent for AutoLayoutComponent {}
+
+/// How a node is sized when its parent group has an auto layout, and how it follows the
+/// group when it is resized otherwise.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
+#[cfg_attr(not(target_arch = "wasm32"), radiantkit_macros::radiant_wasm_bindgen)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct LayoutItemComponent {
+    width: RadiantLayoutSizing,
+    height: RadiantLayoutSizing,
+    horizontal: RadiantConstraint,
+    vertical: RadiantConstraint,
+}
+
+impl LayoutItemComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sizing(&self) -> [RadiantLayoutSizing; 2] {
+        [self.width, self.height]
+    }
+
+    pub fn constraints(&self) -> [RadiantConstraint; 2] {
+        [self.horizontal, self.vertical]
+    }
+}
+
+impl LayoutItemComponent {
+    pub fn set_sizing(&mut self, [width, height]: [RadiantLayoutSizing; 2]) {
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn set_constraints(&mut self, [horizontal, vertical]: [RadiantConstraint; 2]) {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+    }
+}
+
+impl RadiantComponent for LayoutItemComponent {}
+
+impl RadiantTypedComponent for LayoutItemComponent {
+    const TYPE_TAG: &'static str = "layout";
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        AutoLayoutComponent, RadiantLayoutAlignment, RadiantLayoutDirection, RadiantLayoutItem,
+        RadiantLayoutSizing,
+    };
+    use epaint::{pos2, vec2, Rect, Vec2};
+
+    fn item(width: f32, height: f32, sizing: [RadiantLayoutSizing; 2]) -> RadiantLayoutItem {
+        RadiantLayoutItem {
+            size: vec2(width, height),
+            sizing,
+        }
+    }
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect::from_min_size(pos2(x, y), vec2(width, height))
+    }
+
+    const FIXED: [RadiantLayoutSizing; 2] = [RadiantLayoutSizing::Fixed; 2];
+
+    #[test]
+    fn hug_with_gap_and_padding() {
+        let mut layout = AutoLayoutComponent::new();
+        layout.set_gap(10.0);
+        layout.set_padding([5.0, 6.0, 7.0, 8.0]);
+        let items = [item(20.0, 10.0, FIXED), item(30.0, 40.0, FIXED)];
+
+        let (size, rects) = layout.arrange(vec2(100.0, 200.0), Vec2::ZERO, &items);
+        assert_eq!(size, vec2(8.0 + 20.0 + 10.0 + 30.0 + 6.0, 5.0 + 40.0 + 7.0));
+        assert_eq!(
+            rects,
+            vec![rect(108.0, 205.0, 20.0, 10.0), rect(138.0, 205.0, 30.0, 40.0)]
+        );
+    }
+
+    #[test]
+    fn fill_takes_remaining_space() {
+        let mut layout = AutoLayoutComponent::new();
+        layout.set_gap(10.0);
+        layout.set_sizing(FIXED);
+        let items = [
+            item(50.0, 20.0, FIXED),
+            item(0.0, 20.0, [RadiantLayoutSizing::Fill; 2]),
+            item(30.0, 20.0, FIXED),
+        ];
+
+        let (size, rects) = layout.arrange(Vec2::ZERO, vec2(200.0, 100.0), &items);
+        assert_eq!(size, vec2(200.0, 100.0));
+        assert_eq!(
+            rects,
+            vec![
+                rect(0.0, 0.0, 50.0, 20.0),
+                rect(60.0, 0.0, 100.0, 100.0),
+                rect(170.0, 0.0, 30.0, 20.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn vertical_with_centered_cross_alignment() {
+        let mut layout = AutoLayoutComponent::new();
+        layout.set_direction(RadiantLayoutDirection::Vertical);
+        layout.set_gap(5.0);
+        layout.set_cross_alignment(RadiantLayoutAlignment::Center);
+        layout.set_sizing([RadiantLayoutSizing::Fixed, RadiantLayoutSizing::Hug]);
+        let items = [item(20.0, 10.0, FIXED), item(40.0, 30.0, FIXED)];
+
+        let (size, rects) = layout.arrange(Vec2::ZERO, vec2(100.0, 0.0), &items);
+        assert_eq!(size, vec2(100.0, 45.0));
+        assert_eq!(
+            rects,
+            vec![rect(40.0, 0.0, 20.0, 10.0), rect(30.0, 15.0, 40.0, 30.0)]
+        );
+    }
+
+    #[test]
+    fn end_alignment_in_fixed_frame() {
+        let mut layout = AutoLayoutComponent::new();
+        layout.set_main_alignment(RadiantLayoutAlignment::End);
+        layout.set_cross_alignment(RadiantLayoutAlignment::End);
+        layout.set_sizing(FIXED);
+
+        let (_, rects) = layout.arrange(Vec2::ZERO, vec2(100.0, 20.0), &[item(20.0, 10.0, FIXED)]);
+        assert_eq!(rects, vec![rect(80.0, 10.0, 20.0, 10.0)]);
+    }
+}
@@ -0,0 +1,15 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{RadiantComponent, RadiantTypedComponent};
+
+/// Key value pairs host apps attach to a node.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RadiantMetadataComponent(pub BTreeMap<String, String>);
+
+impl RadiantComponent for RadiantMetadataComponent {}
+
+impl RadiantTypedComponent for RadiantMetadataComponent {
+    const TYPE_TAG: &'static str = "metadata";
+}
@@ -1,12 +1,30 @@
 pub mod color;
+pub mod component_map;
+pub mod effect;
+pub mod layer;
+pub mod layout;
+pub mod metadata;
+pub mod paint;
 pub mod selection;
+pub mod stroke;
+pub mod style;
+pub mod text;
 pub mod transform;
 
 pub use color::*;
+pub use component_map::*;
+pub use effect::*;
+pub use layer::*;
+pub use layout::*;
+pub use metadata::*;
+pub use paint::*;
 pub use selection::*;
+pub use stroke::*;
+pub use style::*;
+pub use text::*;
 pub use transform::*;
 
-pub trait RadiantComponent {}
+pub trait RadiantComponent: std::any::Any {}
 
 pub trait RadiantSelectable: RadiantComponent {
     fn set_selected(&mut self, selected: bool);
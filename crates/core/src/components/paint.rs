@@ -0,0 +1,312 @@
+use epaint::{ClippedPrimitive, Color32, Mesh, Pos2, Rect, Vertex};
+use serde::{Deserialize, Deserializer, Serialize};
+
+const GRADIENT_STEPS: f32 = 16.0;
+const MIN_GRADIENT_STEP: f32 = 4.0;
+/// Most triangle edges a side of the node bounds is split into, so that a gradient much
+/// smaller than its node does not subdivide the whole node finely.
+const MAX_GRADIENT_STEPS: f32 = 64.0;
+/// Vertices a gradient mesh is subdivided up to.
+const MAX_GRADIENT_VERTICES: usize = 16384;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadiantGradientStop {
+    /// Position along the gradient, from `0.0` to `1.0`.
+    pub offset: f32,
+    pub color: Color32,
+}
+
+impl RadiantGradientStop {
+    pub fn new(offset: f32, color: Color32) -> Self {
+        Self {
+            offset: clamp_offset(offset),
+            color,
+        }
+    }
+}
+
+/// How the inside of a shape is painted. Gradient coordinates are relative to the node bounds,
+/// `[0.0, 0.0]` being the top left and `[1.0, 1.0]` the bottom right corner.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RadiantPaint {
+    Solid(Color32),
+    LinearGradient {
+        start: [f32; 2],
+        end: [f32; 2],
+        #[serde(deserialize_with = "deserialize_stops")]
+        stops: Vec<RadiantGradientStop>,
+    },
+    RadialGradient {
+        center: [f32; 2],
+        radius: f32,
+        #[serde(deserialize_with = "deserialize_stops")]
+        stops: Vec<RadiantGradientStop>,
+    },
+}
+
+impl Default for RadiantPaint {
+    fn default() -> Self {
+        Self::Solid(Color32::TRANSPARENT)
+    }
+}
+
+impl RadiantPaint {
+    /// The paint with its color stops clamped to `0.0..=1.0` and sorted by offset.
+    pub fn normalized(mut self) -> Self {
+        match &mut self {
+            RadiantPaint::Solid(_) => {}
+            RadiantPaint::LinearGradient { stops, .. }
+            | RadiantPaint::RadialGradient { stops, .. } => normalize_stops(stops),
+        }
+        self
+    }
+
+    /// Whether any color of the paint is at least partly opaque.
+    pub fn is_visible(&self) -> bool {
+        match self {
+            RadiantPaint::Solid(color) => color.a() > 0,
+            RadiantPaint::LinearGradient { stops, .. }
+            | RadiantPaint::RadialGradient { stops, .. } => {
+                stops.iter().any(|stop| stop.color.a() > 0)
+            }
+        }
+    }
+
+    /// Color of the paint at `position` for a node occupying `rect`.
+    pub fn color_at(&self, position: Pos2, rect: Rect) -> Color32 {
+        match self {
+            RadiantPaint::Solid(color) => *color,
+            RadiantPaint::LinearGradient { start, end, stops } => {
+                let start = relative_to(rect, *start);
+                let end = relative_to(rect, *end);
+                let direction = end - start;
+                let length_sq = direction.length_sq();
+                if length_sq <= f32::EPSILON {
+                    return sample(stops, 0.0);
+                }
+                sample(stops, (position - start).dot(direction) / length_sq)
+            }
+            RadiantPaint::RadialGradient {
+                center,
+                radius,
+                stops,
+            } => {
+                let center = relative_to(rect, *center);
+                let radius = *radius * rect.width().max(rect.height());
+                if radius <= f32::EPSILON {
+                    return sample(stops, 1.0);
+                }
+                sample(stops, (position - center).length() / radius)
+            }
+        }
+    }
+
+    /// Recolors meshes that were tessellated in white so they are filled with this paint.
+    /// Gradients subdivide the triangles so per-vertex colors follow the color stops.
+    pub fn apply(&self, primitives: &mut [ClippedPrimitive], rect: Rect) {
+        let step = self.step(rect);
+        for primitive in primitives {
+            if let epaint::Primitive::Mesh(mesh) = &mut primitive.primitive {
+                if let Some(step) = step {
+                    subdivide(mesh, step);
+                }
+                for vertex in &mut mesh.vertices {
+                    let color = self.color_at(vertex.pos, rect);
+                    vertex.color = color.linear_multiply(vertex.color.a() as f32 / 255.0);
+                }
+            }
+        }
+    }
+
+    fn step(&self, rect: Rect) -> Option<f32> {
+        let length = match self {
+            RadiantPaint::Solid(_) => return None,
+            // A single color does not need per-vertex colors.
+            RadiantPaint::LinearGradient { stops, .. }
+            | RadiantPaint::RadialGradient { stops, .. }
+                if stops.len() < 2 =>
+            {
+                return None
+            }
+            RadiantPaint::LinearGradient { start, end, .. } => {
+                (relative_to(rect, *end) - relative_to(rect, *start)).length()
+            }
+            RadiantPaint::RadialGradient { radius, .. } => {
+                *radius * rect.width().max(rect.height())
+            }
+        };
+        Some(
+            (length / GRADIENT_STEPS)
+                .max(rect.size().max_elem() / MAX_GRADIENT_STEPS)
+                .max(MIN_GRADIENT_STEP),
+        )
+    }
+}
+
+fn clamp_offset(offset: f32) -> f32 {
+    if offset.is_nan() {
+        0.0
+    } else {
+        offset.clamp(0.0, 1.0)
+    }
+}
+
+fn normalize_stops(stops: &mut [RadiantGradientStop]) {
+    for stop in stops.iter_mut() {
+        stop.offset = clamp_offset(stop.offset);
+    }
+    stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+}
+
+fn deserialize_stops<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<RadiantGradientStop>, D::Error> {
+    let mut stops = Vec::<RadiantGradientStop>::deserialize(deserializer)?;
+    normalize_stops(&mut stops);
+    Ok(stops)
+}
+
+fn relative_to(rect: Rect, point: [f32; 2]) -> Pos2 {
+    rect.min + rect.size() * epaint::vec2(point[0], point[1])
+}
+
+/// Color at `offset` along `stops`, which are sorted by offset.
+fn sample(stops: &[RadiantGradientStop], offset: f32) -> Color32 {
+    let first = match stops {
+        [] => return Color32::TRANSPARENT,
+        [stop] => return stop.color,
+        [first, ..] => first,
+    };
+    if offset <= first.offset {
+        return first.color;
+    }
+    for pair in stops.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        if offset <= to.offset {
+            let range = to.offset - from.offset;
+            let t = if range <= f32::EPSILON {
+                1.0
+            } else {
+                (offset - from.offset) / range
+            };
+            return lerp(from.color, to.color, t);
+        }
+    }
+    stops[stops.len() - 1].color
+}
+
+fn lerp(from: Color32, to: Color32, t: f32) -> Color32 {
+    let from = from.to_srgba_unmultiplied();
+    let to = to.to_srgba_unmultiplied();
+    let channel = |i: usize| (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8;
+    Color32::from_rgba_unmultiplied(channel(0), channel(1), channel(2), channel(3))
+}
+
+/// Splits triangles along their longest edge until no edge is longer than `step`, or the
+/// mesh has `MAX_GRADIENT_VERTICES` vertices.
+fn subdivide(mesh: &mut Mesh, step: f32) {
+    let mut triangles: Vec<[u32; 3]> = mesh
+        .indices
+        .chunks_exact(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect();
+    let mut indices = Vec::with_capacity(mesh.indices.len());
+
+    while let Some(triangle) = triangles.pop() {
+        let edges = [(0, 1), (1, 2), (2, 0)];
+        let (a, b) = edges
+            .into_iter()
+            .max_by(|(a0, b0), (a1, b1)| {
+                let l0 = mesh.vertices[triangle[*a0] as usize].pos
+                    - mesh.vertices[triangle[*b0] as usize].pos;
+                let l1 = mesh.vertices[triangle[*a1] as usize].pos
+                    - mesh.vertices[triangle[*b1] as usize].pos;
+                l0.length_sq().total_cmp(&l1.length_sq())
+            })
+            .unwrap();
+        let va = mesh.vertices[triangle[a] as usize];
+        let vb = mesh.vertices[triangle[b] as usize];
+        if (va.pos - vb.pos).length() <= step || mesh.vertices.len() >= MAX_GRADIENT_VERTICES {
+            indices.extend_from_slice(&triangle);
+            continue;
+        }
+
+        let middle = mesh.vertices.len() as u32;
+        mesh.vertices.push(Vertex {
+            pos: va.pos + (vb.pos - va.pos) * 0.5,
+            uv: va.uv + (vb.uv - va.uv) * 0.5,
+            color: lerp(va.color, vb.color, 0.5),
+        });
+        let c = 3 - a - b;
+        triangles.push([triangle[a], middle, triangle[c]]);
+        triangles.push([middle, triangle[b], triangle[c]]);
+    }
+
+    mesh.indices = indices;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{RadiantGradientStop, RadiantPaint};
+    use epaint::{pos2, ClippedPrimitive, Color32, Mesh, Primitive, Rect};
+
+    #[test]
+    fn stops_are_sorted_and_clamped_on_load() {
+        let paint: RadiantPaint = serde_json::from_str(
+            r#"{"LinearGradient":{"start":[0.0,0.0],"end":[1.0,0.0],"stops":[
+                {"offset":1.5,"color":[0,0,0,255]},
+                {"offset":-1.0,"color":[255,255,255,255]}
+            ]}}"#,
+        )
+        .unwrap();
+        let RadiantPaint::LinearGradient { stops, .. } = &paint else {
+            panic!("expected a linear gradient");
+        };
+        assert_eq!(stops[0], RadiantGradientStop::new(0.0, Color32::WHITE));
+        assert_eq!(stops[1], RadiantGradientStop::new(1.0, Color32::BLACK));
+
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(10.0, 10.0));
+        assert_eq!(paint.color_at(pos2(0.0, 5.0), rect), Color32::WHITE);
+        assert_eq!(paint.color_at(pos2(10.0, 5.0), rect), Color32::BLACK);
+    }
+
+    #[test]
+    fn single_and_empty_stops() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(10.0, 10.0));
+        let paint = |stops| RadiantPaint::RadialGradient {
+            center: [0.5, 0.5],
+            radius: 0.5,
+            stops,
+        };
+        let single = paint(vec![RadiantGradientStop::new(0.5, Color32::RED)]);
+        assert_eq!(single.color_at(pos2(0.0, 0.0), rect), Color32::RED);
+        assert_eq!(single.color_at(pos2(5.0, 5.0), rect), Color32::RED);
+        let empty = paint(Vec::new());
+        assert_eq!(empty.color_at(pos2(5.0, 5.0), rect), Color32::TRANSPARENT);
+    }
+
+    #[test]
+    fn subdivision_is_bounded() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100_000.0, 100_000.0));
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(rect, Color32::WHITE);
+        let mut primitives = vec![ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Mesh(mesh),
+        }];
+        let paint = RadiantPaint::RadialGradient {
+            center: [0.5, 0.5],
+            radius: 0.0001,
+            stops: vec![
+                RadiantGradientStop::new(0.0, Color32::WHITE),
+                RadiantGradientStop::new(1.0, Color32::BLACK),
+            ],
+        };
+        paint.apply(&mut primitives, rect);
+        let Primitive::Mesh(mesh) = &primitives[0].primitive else {
+            panic!("expected a mesh");
+        };
+        assert!(mesh.vertices.len() <= super::MAX_GRADIENT_VERTICES);
+        assert!(mesh.indices.len() > 6);
+    }
+}
@@ -0,0 +1,481 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{RadiantComponent, RadiantTypedComponent};
+use epaint::{Color32, Mesh, PathShape, Pos2, Shape, Stroke, Vec2};
+
+const MITER_LIMIT: f32 = 4.0;
+const ARC_STEP: f32 = std::f32::consts::PI / 16.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum StrokeAlignment {
+    Inside,
+    #[default]
+    Center,
+    Outside,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum StrokeCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum StrokeJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen)]
+#[cfg_attr(not(target_arch = "wasm32"), radiantkit_macros::radiant_wasm_bindgen)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct StrokeComponent {
+    width: f32,
+    alignment: StrokeAlignment,
+    /// Alternating dash and gap lengths. The outline is solid when empty.
+    dash_pattern: Vec<f32>,
+    cap: StrokeCap,
+    join: StrokeJoin,
+}
+
+impl Default for StrokeComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StrokeComponent {
+    pub fn new() -> Self {
+        Self {
+            width: 1.0,
+            alignment: StrokeAlignment::Center,
+            dash_pattern: Vec::new(),
+            cap: StrokeCap::Butt,
+            join: StrokeJoin::Miter,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    pub fn alignment(&self) -> StrokeAlignment {
+        self.alignment
+    }
+
+    pub fn dash_pattern(&self) -> &[f32] {
+        &self.dash_pattern
+    }
+
+    pub fn cap(&self) -> StrokeCap {
+        self.cap
+    }
+
+    pub fn join(&self) -> StrokeJoin {
+        self.join
+    }
+}
+
+impl StrokeComponent {
+    /// Farthest the stroke reaches past the outline it is drawn along, which the visual
+    /// bounds of the node grow by.
+    pub fn outer_extent(&self) -> f32 {
+        let half_width = self.width * 0.5;
+        let extent = match self.alignment {
+            StrokeAlignment::Outside => self.width,
+            // Open paths are centered whatever the alignment.
+            StrokeAlignment::Inside | StrokeAlignment::Center => half_width,
+        };
+        match self.cap {
+            StrokeCap::Square => extent.max(half_width * std::f32::consts::SQRT_2),
+            StrokeCap::Butt | StrokeCap::Round => extent,
+        }
+    }
+}
+
+impl StrokeComponent {
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width.max(0.0);
+    }
+
+    pub fn set_alignment(&mut self, alignment: StrokeAlignment) {
+        self.alignment = alignment;
+    }
+
+    pub fn set_dash_pattern(&mut self, dash_pattern: Vec<f32>) {
+        self.dash_pattern = dash_pattern;
+    }
+
+    pub fn set_cap(&mut self, cap: StrokeCap) {
+        self.cap = cap;
+    }
+
+    pub fn set_join(&mut self, join: StrokeJoin) {
+        self.join = join;
+    }
+}
+
+impl RadiantComponent for StrokeComponent {}
+
+impl RadiantTypedComponent for StrokeComponent {
+    const TYPE_TAG: &'static str = "stroke";
+}
+
+impl StrokeComponent {
+    /// Builds the outline of `points` in `color`. Closed paths are offset according to the
+    /// alignment, open paths are always centered.
+    pub fn shapes(&self, points: &[Pos2], closed: bool, color: Color32) -> Vec<Shape> {
+        let points = dedup(points, closed);
+        if self.width <= 0.0 || color == Color32::TRANSPARENT || points.len() < 2 {
+            return Vec::new();
+        }
+
+        let points = match (closed, self.alignment) {
+            (true, StrokeAlignment::Inside) => offset_polygon(&points, -self.width * 0.5),
+            (true, StrokeAlignment::Outside) => offset_polygon(&points, self.width * 0.5),
+            _ => points,
+        };
+
+        let dashes = self.dashes(&points, closed);
+        if dashes.is_none() && self.cap == StrokeCap::Butt && self.join == StrokeJoin::Miter {
+            // epaint produces the same geometry with anti-aliased edges.
+            let stroke = Stroke::new(self.width, color);
+            let shape = if closed {
+                PathShape::closed_line(points, stroke)
+            } else {
+                PathShape::line(points, stroke)
+            };
+            return vec![Shape::Path(shape)];
+        }
+
+        let mut mesh = Mesh::default();
+        match dashes {
+            Some(dashes) => {
+                for dash in dashes {
+                    self.stroke_polyline(&mut mesh, &dedup(&dash, false), false, color);
+                }
+            }
+            None => self.stroke_polyline(&mut mesh, &points, closed, color),
+        }
+        vec![Shape::Mesh(mesh)]
+    }
+
+    fn dashes(&self, points: &[Pos2], closed: bool) -> Option<Vec<Vec<Pos2>>> {
+        let mut pattern = self.dash_pattern.clone();
+        if pattern.is_empty()
+            || pattern.iter().any(|length| *length < 0.0)
+            || pattern.iter().sum::<f32>() <= 0.0
+        {
+            return None;
+        }
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_within(..);
+        }
+
+        let mut path = points.to_vec();
+        if closed {
+            path.push(points[0]);
+        }
+
+        let mut dashes = Vec::new();
+        let mut current = vec![path[0]];
+        let mut index = 0;
+        let mut remaining = pattern[0];
+        for segment in path.windows(2) {
+            let (mut start, end) = (segment[0], segment[1]);
+            let mut length = (end - start).length();
+            while length > remaining {
+                let point = start + (end - start).normalized() * remaining;
+                if index % 2 == 0 {
+                    current.push(point);
+                    dashes.push(std::mem::take(&mut current));
+                } else {
+                    current = vec![point];
+                }
+                length -= remaining;
+                start = point;
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+            remaining -= length;
+            if index % 2 == 0 {
+                current.push(end);
+            }
+        }
+        if index % 2 == 0 && current.len() > 1 {
+            dashes.push(current);
+        }
+
+        Some(dashes)
+    }
+
+    fn stroke_polyline(&self, mesh: &mut Mesh, points: &[Pos2], closed: bool, color: Color32) {
+        let half_width = self.width * 0.5;
+        let count = points.len();
+        if count == 0 {
+            return;
+        }
+        if count == 1 {
+            if self.cap == StrokeCap::Round {
+                add_arc(
+                    mesh,
+                    points[0],
+                    Vec2::X * half_width,
+                    std::f32::consts::TAU,
+                    color,
+                );
+            }
+            return;
+        }
+
+        let segments = if closed { count } else { count - 1 };
+        for i in 0..segments {
+            let (start, end) = (points[i], points[(i + 1) % count]);
+            let normal = (end - start).normalized().rot90() * half_width;
+            add_quad(
+                mesh,
+                [start + normal, end + normal, end - normal, start - normal],
+                color,
+            );
+        }
+
+        let joins = if closed { 0..count } else { 1..count - 1 };
+        for i in joins {
+            let point = points[i];
+            let before = (point - points[(i + count - 1) % count]).normalized();
+            let after = (points[(i + 1) % count] - point).normalized();
+            self.add_join(mesh, point, before, after, half_width, color);
+        }
+
+        if !closed {
+            let start = (points[0] - points[1]).normalized();
+            let end = (points[count - 1] - points[count - 2]).normalized();
+            self.add_cap(mesh, points[0], start, half_width, color);
+            self.add_cap(mesh, points[count - 1], end, half_width, color);
+        }
+    }
+
+    fn add_join(
+        &self,
+        mesh: &mut Mesh,
+        point: Pos2,
+        before: Vec2,
+        after: Vec2,
+        half_width: f32,
+        color: Color32,
+    ) {
+        let cross = before.x * after.y - before.y * after.x;
+        if cross.abs() < f32::EPSILON && before.dot(after) > 0.0 {
+            return;
+        }
+        let side = if cross > 0.0 { 1.0 } else { -1.0 };
+        let from = before.rot90() * half_width * side;
+        let to = after.rot90() * half_width * side;
+
+        match self.join {
+            StrokeJoin::Round => add_arc(mesh, point, from, signed_angle(from, to), color),
+            StrokeJoin::Miter | StrokeJoin::Bevel => {
+                let direction = (from + to).normalized();
+                let cos = direction.dot(from) / half_width;
+                if self.join == StrokeJoin::Miter && cos > 1.0 / MITER_LIMIT {
+                    let miter = point + direction * (half_width / cos);
+                    add_triangle(mesh, [point, point + from, miter], color);
+                    add_triangle(mesh, [point, miter, point + to], color);
+                } else {
+                    add_triangle(mesh, [point, point + from, point + to], color);
+                }
+            }
+        }
+    }
+
+    fn add_cap(
+        &self,
+        mesh: &mut Mesh,
+        point: Pos2,
+        direction: Vec2,
+        half_width: f32,
+        color: Color32,
+    ) {
+        let normal = direction.rot90() * half_width;
+        match self.cap {
+            StrokeCap::Butt => {}
+            StrokeCap::Square => {
+                let extent = direction * half_width;
+                add_quad(
+                    mesh,
+                    [
+                        point + normal,
+                        point + normal + extent,
+                        point - normal + extent,
+                        point - normal,
+                    ],
+                    color,
+                );
+            }
+            StrokeCap::Round => add_arc(
+                mesh,
+                point,
+                normal,
+                2.0 * signed_angle(normal, direction),
+                color,
+            ),
+        }
+    }
+}
+
+fn signed_angle(from: Vec2, to: Vec2) -> f32 {
+    (from.x * to.y - from.y * to.x).atan2(from.dot(to))
+}
+
+fn add_triangle(mesh: &mut Mesh, points: [Pos2; 3], color: Color32) {
+    let index = mesh.vertices.len() as u32;
+    for point in points {
+        mesh.colored_vertex(point, color);
+    }
+    mesh.add_triangle(index, index + 1, index + 2);
+}
+
+fn add_quad(mesh: &mut Mesh, points: [Pos2; 4], color: Color32) {
+    let index = mesh.vertices.len() as u32;
+    for point in points {
+        mesh.colored_vertex(point, color);
+    }
+    mesh.add_triangle(index, index + 1, index + 2);
+    mesh.add_triangle(index, index + 2, index + 3);
+}
+
+/// Adds a triangle fan around `center` starting at offset `from` and sweeping `angle` radians.
+fn add_arc(mesh: &mut Mesh, center: Pos2, from: Vec2, angle: f32, color: Color32) {
+    let steps = (angle.abs() / ARC_STEP).ceil().max(1.0) as u32;
+    let index = mesh.vertices.len() as u32;
+    mesh.colored_vertex(center, color);
+    for step in 0..=steps {
+        let rotation = epaint::emath::Rot2::from_angle(angle * step as f32 / steps as f32);
+        mesh.colored_vertex(center + rotation * from, color);
+    }
+    for step in 0..steps {
+        mesh.add_triangle(index, index + 1 + step, index + 2 + step);
+    }
+}
+
+fn dedup(points: &[Pos2], closed: bool) -> Vec<Pos2> {
+    let mut result: Vec<Pos2> = Vec::with_capacity(points.len());
+    for point in points {
+        if result.last() != Some(point) {
+            result.push(*point);
+        }
+    }
+    if closed && result.len() > 1 && result.first() == result.last() {
+        result.pop();
+    }
+    result
+}
+
+/// Moves every edge of a closed polygon outwards by `distance`, inwards when negative.
+fn offset_polygon(points: &[Pos2], distance: f32) -> Vec<Pos2> {
+    let count = points.len();
+    let area = (0..count).fold(0.0, |area, i| {
+        let (a, b) = (points[i], points[(i + 1) % count]);
+        area + a.x * b.y - b.x * a.y
+    });
+    let distance = if area >= 0.0 { distance } else { -distance };
+
+    (0..count)
+        .map(|i| {
+            let point = points[i];
+            let before = (point - points[(i + count - 1) % count]).normalized().rot90();
+            let after = (points[(i + 1) % count] - point).normalized().rot90();
+            let direction = before + after;
+            if direction.length() < f32::EPSILON {
+                return point + before * distance;
+            }
+            let direction = direction.normalized();
+            point + direction * (distance / direction.dot(before).max(1.0 / MITER_LIMIT))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{StrokeAlignment, StrokeCap, StrokeComponent};
+    use epaint::{pos2, Pos2};
+
+    fn stroke(dash_pattern: Vec<f32>) -> StrokeComponent {
+        let mut stroke = StrokeComponent::new();
+        stroke.set_dash_pattern(dash_pattern);
+        stroke
+    }
+
+    #[test]
+    fn dashes_open_path() {
+        let points = [pos2(0.0, 0.0), pos2(10.0, 0.0)];
+        let dashes = stroke(vec![3.0, 2.0]).dashes(&points, false).unwrap();
+        assert_eq!(
+            dashes,
+            vec![
+                vec![pos2(0.0, 0.0), pos2(3.0, 0.0)],
+                vec![pos2(5.0, 0.0), pos2(8.0, 0.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn dashes_continue_around_corners() {
+        let points = [pos2(0.0, 0.0), pos2(2.0, 0.0), pos2(2.0, 4.0)];
+        let dashes = stroke(vec![4.0, 1.0]).dashes(&points, false).unwrap();
+        assert_eq!(
+            dashes,
+            vec![
+                vec![pos2(0.0, 0.0), pos2(2.0, 0.0), pos2(2.0, 2.0)],
+                vec![pos2(2.0, 3.0), pos2(2.0, 4.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn dashes_closed_path_and_odd_pattern() {
+        let points = [
+            pos2(0.0, 0.0),
+            pos2(4.0, 0.0),
+            pos2(4.0, 4.0),
+            pos2(0.0, 4.0),
+        ];
+        // An odd pattern is repeated, so 4 is both a dash and a gap length.
+        let dashes = stroke(vec![4.0]).dashes(&points, true).unwrap();
+        assert_eq!(
+            dashes
+                .iter()
+                .map(|dash| super::dedup(dash, false))
+                .collect::<Vec<_>>(),
+            vec![
+                vec![pos2(0.0, 0.0), pos2(4.0, 0.0)],
+                vec![pos2(4.0, 4.0), pos2(0.0, 4.0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn dashes_invalid_pattern() {
+        let points: [Pos2; 2] = [pos2(0.0, 0.0), pos2(10.0, 0.0)];
+        assert!(stroke(Vec::new()).dashes(&points, false).is_none());
+        assert!(stroke(vec![0.0, 0.0]).dashes(&points, false).is_none());
+        assert!(stroke(vec![2.0, -1.0]).dashes(&points, false).is_none());
+    }
+
+    #[test]
+    fn outer_extent() {
+        let mut stroke = StrokeComponent::new();
+        stroke.set_width(4.0);
+        assert_eq!(stroke.outer_extent(), 2.0);
+        stroke.set_alignment(StrokeAlignment::Outside);
+        assert_eq!(stroke.outer_extent(), 4.0);
+        stroke.set_alignment(StrokeAlignment::Center);
+        stroke.set_cap(StrokeCap::Square);
+        assert_eq!(stroke.outer_extent(), 2.0 * std::f32::consts::SQRT_2);
+    }
+}
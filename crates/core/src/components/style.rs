@@ -0,0 +1,124 @@
+use epaint::Color32;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{RadiantComponent, RadiantTypedComponent};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantFontFamily {
+    #[default]
+    Proportional,
+    Monospace,
+}
+
+impl From<RadiantFontFamily> for epaint::FontFamily {
+    fn from(family: RadiantFontFamily) -> Self {
+        match family {
+            RadiantFontFamily::Proportional => epaint::FontFamily::Proportional,
+            RadiantFontFamily::Monospace => epaint::FontFamily::Monospace,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct RadiantTextStyle {
+    pub font_size: f32,
+    pub font_family: RadiantFontFamily,
+    pub color: Color32,
+}
+
+impl Default for RadiantTextStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 24.0,
+            font_family: RadiantFontFamily::Proportional,
+            color: Color32::WHITE,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum RadiantStyleValue {
+    Color(Color32),
+    Text(RadiantTextStyle),
+}
+
+/// A named value shared by the nodes of a document.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RadiantStyle {
+    pub id: Uuid,
+    pub name: String,
+    pub value: RadiantStyleValue,
+}
+
+/// Node property a style is applied to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadiantStyleTarget {
+    Fill,
+    Stroke,
+    Text,
+}
+
+impl RadiantStyleTarget {
+    pub fn accepts(&self, value: &RadiantStyleValue) -> bool {
+        matches!(
+            (self, value),
+            (
+                RadiantStyleTarget::Fill | RadiantStyleTarget::Stroke,
+                RadiantStyleValue::Color(_)
+            ) | (RadiantStyleTarget::Text, RadiantStyleValue::Text(_))
+        )
+    }
+}
+
+/// Styles of the document a node is using.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct StyleComponent {
+    fill: Option<Uuid>,
+    stroke: Option<Uuid>,
+    text: Option<Uuid>,
+}
+
+impl StyleComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn style(&self, target: RadiantStyleTarget) -> Option<Uuid> {
+        match target {
+            RadiantStyleTarget::Fill => self.fill,
+            RadiantStyleTarget::Stroke => self.stroke,
+            RadiantStyleTarget::Text => self.text,
+        }
+    }
+
+    /// Targets the style is applied to.
+    pub fn targets(&self, style_id: Uuid) -> Vec<RadiantStyleTarget> {
+        [
+            RadiantStyleTarget::Fill,
+            RadiantStyleTarget::Stroke,
+            RadiantStyleTarget::Text,
+        ]
+        .into_iter()
+        .filter(|target| self.style(*target) == Some(style_id))
+        .collect()
+    }
+}
+
+impl StyleComponent {
+    pub fn set_style(&mut self, target: RadiantStyleTarget, style_id: Option<Uuid>) {
+        match target {
+            RadiantStyleTarget::Fill => self.fill = style_id,
+            RadiantStyleTarget::Stroke => self.stroke = style_id,
+            RadiantStyleTarget::Text => self.text = style_id,
+        }
+    }
+}
+
+impl RadiantComponent for StyleComponent {}
+
+impl RadiantTypedComponent for StyleComponent {
+    const TYPE_TAG: &'static str = "style";
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{RadiantComponent, RadiantTextStyle, RadiantTypedComponent};
+
+/// Text a node displays and the style it is laid out with, carried by nodes that display
+/// text so that instances can override it and the text styles of the document apply to it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TextComponent {
+    text: String,
+    style: RadiantTextStyle,
+}
+
+impl TextComponent {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            style: RadiantTextStyle::default(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn style(&self) -> &RadiantTextStyle {
+        &self.style
+    }
+}
+
+impl TextComponent {
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+    }
+
+    pub fn text_mut(&mut self) -> &mut String {
+        &mut self.text
+    }
+
+    pub fn set_style(&mut self, style: RadiantTextStyle) {
+        self.style = style;
+    }
+}
+
+impl RadiantComponent for TextComponent {}
+
+impl RadiantTypedComponent for TextComponent {
+    const TYPE_TAG: &'static str = "text";
+}
@@ -1,8 +1,11 @@
-use std::{collections::BTreeMap, cell::RefCell, rc::Rc, sync::Arc};
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, cell::RefCell, rc::Rc, sync::Arc};
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use crate::{
-    RadiantGroupNode, RadiantNode, RadiantSelectable, RadiantTessellatable, ScreenDescriptor,
-    SelectionComponent, SubscriptionId,
+    RadiantGroupNode, RadiantInstanceNode, RadiantLayer, RadiantNode, RadiantPaint,
+    RadiantPickingRegistry, RadiantSelectable, RadiantSpatialIndex, RadiantStyle,
+    RadiantStyleTarget, RadiantStyleValue, RadiantSymbol, RadiantTessellatable,
+    RadiantTypeRegistry, RadiantTypedComponent, ScreenDescriptor, SelectionComponent,
+    SubscriptionId, TextComponent, TransformComponent,
 };
 use epaint::ClippedPrimitive;
 use serde::{Deserialize, Serialize};
@@ -14,10 +17,103 @@ pub struct RadiantDocumentNode<N: RadiantNode> {
     pub artboards: BTreeMap<Uuid, RadiantGroupNode<N>>,
     pub active_artboard_id: Uuid,
     pub selected_node_id: Option<Uuid>,
+    #[serde(default)]
+    pub symbols: BTreeMap<Uuid, RadiantSymbol>,
+    #[serde(default)]
+    pub styles: BTreeMap<Uuid, RadiantStyle>,
     #[serde(skip)]
     listeners: Rc<RefCell<Vec<Box<dyn RadiantDocumentListener<N>>>>>,
     #[serde(skip)]
     subscriptions: Vec<SubscriptionId>,
+    /// Nodes changed since instances and the spatial index were last updated.
+    #[serde(skip)]
+    changed_nodes: Rc<RefCell<Vec<Uuid>>>,
+    #[serde(skip)]
+    spatial_index: RadiantSpatialIndex,
+    #[serde(skip)]
+    history: Rc<RefCell<RadiantHistory<N>>>,
+    /// Types of custom nodes and components the document can hold, used whenever it
+    /// deserializes nodes. A deserialized document takes the registry it is deserialized
+    /// in the scope of.
+    #[serde(skip, default = "scoped_registry")]
+    pub registry: RadiantTypeRegistry,
+}
+
+fn scoped_registry() -> RadiantTypeRegistry {
+    RadiantTypeRegistry::scoped().unwrap_or_default()
+}
+
+/// Edit made to the document, holding what undoing it needs.
+enum RadiantEdit<N> {
+    Added(Uuid),
+    /// Id and serialized state of the node before it changed.
+    Changed(Uuid, String),
+    /// Id of the artboard the node was removed from, and the node.
+    Removed(Uuid, N),
+}
+
+/// Node edited by the messages of one gesture, such as a drag, with the kind of edit.
+pub type RadiantEditGesture = (Uuid, &'static str);
+
+/// Edits made to the document, grouped into the steps they are undone in from the last
+/// one.
+struct RadiantHistory<N> {
+    steps: Vec<Vec<RadiantEdit<N>>>,
+    /// Edits made since the current step began, which are not undone yet.
+    step: Vec<RadiantEdit<N>>,
+    /// Gesture the current step was begun for.
+    gesture: Option<RadiantEditGesture>,
+    /// Last serialized state of every node, which changes to it are undone to.
+    states: HashMap<Uuid, String>,
+    /// Whether edits are left out, as while one is undone or the selection changes.
+    paused: bool,
+}
+
+impl<N> Default for RadiantHistory<N> {
+    fn default() -> Self {
+        Self {
+            steps: Vec::new(),
+            step: Vec::new(),
+            gesture: None,
+            states: HashMap::new(),
+            paused: false,
+        }
+    }
+}
+
+impl<N> RadiantHistory<N> {
+    fn record(&mut self, edit: RadiantEdit<N>) {
+        if !self.paused {
+            self.step.push(edit);
+        }
+    }
+
+    /// Notes the new state of a node. Only the first change to a node within a step is
+    /// recorded, so that a drag is undone at once.
+    fn record_change(&mut self, id: Uuid, state: &str) {
+        let Some(previous) = self.states.insert(id, state.to_string()) else {
+            return;
+        };
+        let recorded = self
+            .step
+            .iter()
+            .any(|edit| matches!(edit, RadiantEdit::Changed(changed, _) if *changed == id));
+        if !recorded {
+            self.record(RadiantEdit::Changed(id, previous));
+        }
+    }
+
+    /// Ends the current step, unless `gesture` continues it.
+    fn begin(&mut self, gesture: Option<RadiantEditGesture>) {
+        if gesture.is_some() && gesture == self.gesture {
+            return;
+        }
+        self.gesture = gesture;
+        if !self.step.is_empty() {
+            let step = std::mem::take(&mut self.step);
+            self.steps.push(step);
+        }
+    }
 }
 
 unsafe impl<N: RadiantNode> Send for RadiantDocumentNode<N> {}
@@ -33,8 +129,14 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
             artboards,
             active_artboard_id: artboard_id,
             selected_node_id: None,
+            symbols: BTreeMap::new(),
+            styles: BTreeMap::new(),
             listeners: Rc::new(Vec::new().into()),
             subscriptions: Vec::new(),
+            changed_nodes: Rc::new(Vec::new().into()),
+            spatial_index: RadiantSpatialIndex::new(),
+            history: Default::default(),
+            registry: RadiantTypeRegistry::new(),
         }
     }
 
@@ -53,23 +155,41 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
     }
 
     pub fn add(&mut self, node: N) {
-        self.add_node(node, None);
+        self.add_node(node, self.active_artboard_id, None);
     }
 
     pub fn add_excluding_listener(&mut self, node: N, listener_id: Uuid) {
-        self.add_node(node, Some(listener_id));
+        self.add_node(node, self.active_artboard_id, Some(listener_id));
     }
 
-    fn add_node(&mut self, mut node: N, exclude_listener_id: Option<Uuid>) {
-        if let Some(artboard) = self.artboards.get_mut(&self.active_artboard_id) {
+    fn add_node(&mut self, mut node: N, artboard_id: Uuid, exclude_listener_id: Option<Uuid>) {
+        node.base_mut().components.upgrade(&self.registry);
+        if let Some(artboard) = self.artboards.get_mut(&artboard_id) {
             let id = node.get_id();
             let listeners = self.listeners.clone();
+            let changed_nodes = self.changed_nodes.clone();
+            let history = self.history.clone();
             let subscription = node.observe(move |data| {
+                changed_nodes.borrow_mut().push(id);
+                history.borrow_mut().record_change(id, data);
                 listeners.borrow_mut().iter_mut().for_each(|listener| {
                     listener.on_node_changed(id, data);
                 });
             });
+            let rect = node.get_visual_rect();
+            {
+                let mut history = self.history.borrow_mut();
+                if let Ok(state) = serde_json::to_string(&node) {
+                    history.states.insert(id, state);
+                }
+                // Edits of collaborators are left to them to undo.
+                if exclude_listener_id.is_none() {
+                    history.record(RadiantEdit::Added(id));
+                }
+            }
             artboard.add(node);
+            self.spatial_index.insert(id, artboard_id, rect);
+            self.mark_changed(id);
 
             let listeners = self.listeners.clone();
             listeners.borrow_mut()
@@ -87,6 +207,412 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
         }
     }
 
+    pub fn remove(&mut self, id: Uuid) -> Option<N> {
+        self.remove_node(id, None)
+    }
+
+    pub fn remove_excluding_listener(&mut self, id: Uuid, listener_id: Uuid) -> Option<N> {
+        self.remove_node(id, Some(listener_id))
+    }
+
+    fn remove_node(&mut self, id: Uuid, exclude_listener_id: Option<Uuid>) -> Option<N> {
+        let (artboard_id, node) = self
+            .artboards
+            .iter_mut()
+            .find_map(|(artboard_id, artboard)| Some((*artboard_id, artboard.remove(id)?)))?;
+        self.spatial_index.remove(id);
+        if self.selected_node_id == Some(id) {
+            self.selected_node_id = None;
+        }
+        // Instances of symbols the node belonged to drop it.
+        self.mark_changed(id);
+        {
+            let mut history = self.history.borrow_mut();
+            history.states.remove(&id);
+            if exclude_listener_id.is_none() {
+                history.record(RadiantEdit::Removed(artboard_id, node.clone()));
+            }
+        }
+
+        self.listeners
+            .borrow_mut()
+            .iter_mut()
+            .filter(|l| match exclude_listener_id {
+                Some(id) => l.get_id() != id,
+                None => true,
+            })
+            .for_each(|listener| {
+                listener.on_node_removed(id);
+            });
+
+        self.counter += 1;
+        Some(node)
+    }
+
+    /// Takes the state of a node serialized in `data` without notifying listeners, as when
+    /// a collaborator changed it.
+    pub fn replace_node_state(&mut self, id: Uuid, data: &str) {
+        let registry = self.registry.clone();
+        if let Some(mut node) = self.get_node_mut(id) {
+            registry.scope(|| node.replace(data));
+            node.base_mut().components.upgrade(&registry);
+        }
+        self.history.borrow_mut().states.insert(id, data.to_string());
+        self.reindex(id);
+        // Instances of a symbol follow edits of its master nodes.
+        self.mark_changed(id);
+    }
+
+    /// Registers `T` with the registry of the document, and deserializes the components of
+    /// its type that nodes already carry.
+    pub fn register_component_type<T: RadiantTypedComponent>(&mut self) {
+        self.registry.register_component_type::<T>();
+        self.upgrade_components();
+    }
+
+    /// Replaces the registry of the document, as for a document deserialized outside of
+    /// the scope of a registry, and deserializes the components of the types it registers.
+    pub fn set_registry(&mut self, registry: RadiantTypeRegistry) {
+        self.registry = registry;
+        self.upgrade_components();
+    }
+
+    fn upgrade_components(&mut self) {
+        for artboard in self.artboards.values_mut() {
+            artboard.base.components.upgrade(&self.registry);
+            for node in artboard.nodes.values() {
+                node.write().base_mut().components.upgrade(&self.registry);
+            }
+        }
+    }
+
+    /// Begins the undo step that the edits made from now on are undone in, unless they
+    /// continue the `gesture` of the current step, as the messages of a drag do. The scene
+    /// calls this before handling each message.
+    pub fn begin_edit(&mut self, gesture: Option<RadiantEditGesture>) {
+        self.history.borrow_mut().begin(gesture);
+    }
+
+    /// Undoes the edits of the last step. Returns whether there was one.
+    pub fn undo(&mut self) -> bool {
+        self.begin_edit(None);
+        let Some(step) = self.history.borrow_mut().steps.pop() else {
+            return false;
+        };
+        self.history.borrow_mut().paused = true;
+        for edit in step.into_iter().rev() {
+            match edit {
+                RadiantEdit::Added(id) => {
+                    if let Some(mut node) = self.remove(id) {
+                        node.detach();
+                    }
+                }
+                RadiantEdit::Changed(id, state) => {
+                    let registry = self.registry.clone();
+                    let selected = self.selected_node_id == Some(id);
+                    if let Some(mut node) = self.get_node_mut(id) {
+                        registry.scope(|| node.replace(&state));
+                        // The selection is not part of the history.
+                        if let Some(component) = node.get_component_mut::<SelectionComponent>() {
+                            component.set_selected(selected);
+                        }
+                        node.set_needs_tessellation(true);
+                    }
+                    self.reindex(id);
+                }
+                RadiantEdit::Removed(artboard_id, mut node) => {
+                    let artboard_id = if self.artboards.contains_key(&artboard_id) {
+                        artboard_id
+                    } else {
+                        self.active_artboard_id
+                    };
+                    if let Some(component) = node.get_component_mut::<SelectionComponent>() {
+                        component.set_selected(false);
+                    }
+                    node.set_needs_tessellation(false);
+                    self.add_node(node, artboard_id, None);
+                }
+            }
+        }
+        self.history.borrow_mut().paused = false;
+        true
+    }
+
+    /// Moves the node by `position` and grows it by `scale`, returning its new transform.
+    pub fn transform_node(
+        &mut self,
+        id: Uuid,
+        position: [f32; 2],
+        scale: [f32; 2],
+    ) -> Option<TransformComponent> {
+        let transform = {
+            let mut node = self.get_node_mut(id)?;
+            let component = node.get_component_mut::<TransformComponent>()?;
+            component.transform_xy(&position.into());
+            component.transform_scale(&scale.into());
+            let transform = *component;
+            node.set_needs_tessellation(true);
+            transform
+        };
+        self.reindex(id);
+        Some(transform)
+    }
+
+    /// Moves the selected node by `delta`, returning its id and new transform.
+    pub fn nudge_selected_node(&mut self, delta: [f32; 2]) -> Option<(Uuid, TransformComponent)> {
+        let id = self.selected_node_id?;
+        Some((id, self.transform_node(id, delta, [0.0, 0.0])?))
+    }
+
+    /// Notes that the node changed without notifying its observers, so that instances of
+    /// the symbols it belongs to are synced again.
+    pub fn mark_changed(&self, id: Uuid) {
+        self.changed_nodes.borrow_mut().push(id);
+    }
+
+    pub fn create_symbol(&mut self, id: Uuid, name: String, node_ids: Vec<Uuid>) {
+        let node_ids = node_ids
+            .into_iter()
+            .filter(|id| self.node(*id).is_some())
+            .collect();
+        let symbol = RadiantSymbol { id, name, node_ids };
+        self.listeners.borrow_mut().iter_mut().for_each(|listener| {
+            listener.on_symbol_changed(&symbol);
+        });
+        self.symbols.insert(id, symbol);
+    }
+
+    /// Stores a symbol received from elsewhere, such as a collaborator, without
+    /// notifying listeners.
+    pub fn insert_symbol(&mut self, symbol: RadiantSymbol) {
+        self.symbols.insert(symbol.id, symbol);
+    }
+
+    /// Rebuilds the instances whose master nodes or own properties changed.
+    /// Returns the ids of the instances synced.
+    pub fn sync_instances(&mut self, changed: &[Uuid]) -> Vec<Uuid> {
+        if changed.is_empty() {
+            return Vec::new();
+        }
+        let instances = self
+            .artboards
+            .values()
+            .flat_map(|artboard| artboard.nodes.values())
+            .filter(|node| {
+                let node = node.read();
+                node.downcast_ref::<RadiantInstanceNode<N>>().is_some_and(|instance| {
+                    changed.contains(&instance.base.id)
+                        || instance.symbol.node_ids.iter().any(|id| changed.contains(id))
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut synced = Vec::with_capacity(instances.len());
+        for instance in instances {
+            let (id, node_ids) = {
+                let instance = instance.read();
+                let node_ids = instance
+                    .downcast_ref::<RadiantInstanceNode<N>>()
+                    .map(|instance| instance.symbol.node_ids.clone())
+                    .unwrap_or_default();
+                (instance.get_id(), node_ids)
+            };
+            let masters = node_ids
+                .into_iter()
+                .filter(|node_id| *node_id != id)
+                .filter_map(|node_id| self.get_node(node_id).map(|node| node.clone()))
+                .collect::<Vec<N>>();
+            if let Some(instance) = instance.write().downcast_mut::<RadiantInstanceNode<N>>() {
+                instance.sync(&masters);
+            }
+            synced.push(id);
+        }
+        synced
+    }
+
+    /// Reindexes the bounds of a node right away, so that it can be picked where it moved
+    /// to before the next tessellation.
+    pub fn reindex(&mut self, id: Uuid) {
+        self.update_spatial_index(std::iter::once(id));
+    }
+
+    /// Reindexes the bounds of the given nodes, or of every node when the index is out of
+    /// sync with the document, as after it has been deserialized.
+    fn update_spatial_index(&mut self, ids: impl Iterator<Item = Uuid>) {
+        if !self.is_index_complete() {
+            self.spatial_index.clear();
+            for (artboard_id, artboard) in &self.artboards {
+                for (id, node) in &artboard.nodes {
+                    self.spatial_index
+                        .insert(*id, *artboard_id, node.read().get_visual_rect());
+                }
+            }
+            return;
+        }
+        for id in ids {
+            let entry = self.artboards.iter().find_map(|(artboard_id, artboard)| {
+                artboard
+                    .get_node(id)
+                    .map(|node| (*artboard_id, node.get_visual_rect()))
+            });
+            match entry {
+                Some((artboard_id, rect)) => self.spatial_index.insert(id, artboard_id, rect),
+                None => self.spatial_index.remove(id),
+            }
+        }
+    }
+
+    /// Selection primitives of the nodes, painted with their ids in `picking`.
+    pub fn tessellate_picking(
+        &mut self,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+        picking: &mut RadiantPickingRegistry,
+    ) -> Vec<ClippedPrimitive> {
+        self.tessellate_artboards(true, screen_descriptor, fonts_manager, Some(picking))
+    }
+
+    fn tessellate_artboards(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+        mut picking: Option<&mut RadiantPickingRegistry>,
+    ) -> Vec<ClippedPrimitive> {
+        let mut changed = std::mem::take(&mut *self.changed_nodes.borrow_mut());
+        let mut synced = self.sync_instances(&changed);
+
+        // Off-screen nodes are skipped, except when picking which may happen anywhere.
+        let pending = [changed.as_slice(), synced.as_slice()].concat();
+        let visible = if selection {
+            None
+        } else {
+            self.visible_nodes(screen_descriptor.viewport(), &pending)
+        };
+        let empty = BTreeSet::new();
+        let mut primitives = Vec::new();
+        for (id, artboard) in &mut self.artboards {
+            changed.append(&mut artboard.take_moved());
+            if !artboard.base.visible || (selection && artboard.base.locked) {
+                continue;
+            }
+            let visible = visible
+                .as_ref()
+                .map(|visible| visible.get(id).unwrap_or(&empty));
+            primitives.append(&mut artboard.tessellate_nodes(
+                selection,
+                screen_descriptor,
+                fonts_manager,
+                visible,
+                picking.as_deref_mut(),
+            ));
+        }
+
+        // Some bounds are only known once tessellated, and layouts may have moved nodes
+        // that are left pending for the next sync of instances.
+        changed.append(&mut synced);
+        changed.extend(self.changed_nodes.borrow().iter().copied());
+        changed.sort_unstable();
+        changed.dedup();
+        self.update_spatial_index(changed.into_iter());
+        primitives
+    }
+
+    fn is_index_complete(&self) -> bool {
+        let count = self
+            .artboards
+            .values()
+            .map(|artboard| artboard.nodes.len())
+            .sum::<usize>();
+        count == self.spatial_index.len()
+    }
+
+    /// Nodes of each artboard that may be drawn within `viewport`, along with the `pending`
+    /// ones whose bounds are not known until they are tessellated. Returns `None` when every
+    /// node has to be tessellated.
+    fn visible_nodes(
+        &self,
+        viewport: [f32; 4],
+        pending: &[Uuid],
+    ) -> Option<BTreeMap<Uuid, BTreeSet<Uuid>>> {
+        if !self.is_index_complete() {
+            return None;
+        }
+        let mut visible = BTreeMap::<Uuid, BTreeSet<Uuid>>::new();
+        let pending = pending.iter().filter_map(|id| {
+            self.spatial_index
+                .artboard_id(*id)
+                .map(|artboard_id| (artboard_id, *id))
+        });
+        let on_screen = self.spatial_index.query_rect(viewport);
+        for (artboard_id, id) in on_screen.into_iter().chain(pending) {
+            visible.entry(artboard_id).or_default().insert(id);
+        }
+        Some(visible)
+    }
+
+    pub fn create_style(&mut self, id: Uuid, name: String, value: RadiantStyleValue) {
+        let style = RadiantStyle { id, name, value };
+        self.notify_style_changed(&style);
+        self.styles.insert(id, style);
+    }
+
+    /// Stores a style received from elsewhere, such as a collaborator, without
+    /// notifying listeners. Nodes using it are synced on their own.
+    pub fn insert_style(&mut self, style: RadiantStyle) {
+        self.styles.insert(style.id, style);
+    }
+
+    fn notify_style_changed(&self, style: &RadiantStyle) {
+        self.listeners.borrow_mut().iter_mut().for_each(|listener| {
+            listener.on_style_changed(style);
+        });
+    }
+
+    /// Changes the value of a style and updates every node using it.
+    pub fn update_style(&mut self, id: Uuid, value: RadiantStyleValue) {
+        let Some(style) = self.styles.get_mut(&id) else {
+            return;
+        };
+        style.value = value.clone();
+        let style = style.clone();
+        self.notify_style_changed(&style);
+        for node in self.artboards.values().flat_map(|artboard| artboard.nodes.values()) {
+            let mut node = node.write();
+            let targets = node.base().style().targets(id);
+            if targets.is_empty() {
+                continue;
+            }
+            for target in targets {
+                apply_style_value(&mut *node, target, &value);
+            }
+            node.set_needs_tessellation(true);
+        }
+    }
+
+    pub fn apply_style(&mut self, node_id: Uuid, style_id: Uuid, target: RadiantStyleTarget) {
+        let Some(style) = self.styles.get(&style_id) else {
+            return;
+        };
+        if !target.accepts(&style.value) {
+            return;
+        }
+        let value = style.value.clone();
+        if let Some(mut node) = self.get_node_mut(node_id) {
+            node.base_mut().style_mut().set_style(target, Some(style_id));
+            apply_style_value(&mut *node, target, &value);
+            node.set_needs_tessellation(true);
+        }
+    }
+
+    /// Stops the node from following the style, keeping its current value.
+    pub fn detach_style(&mut self, node_id: Uuid, target: RadiantStyleTarget) {
+        if let Some(mut node) = self.get_node_mut(node_id) {
+            node.base_mut().style_mut().set_style(target, None);
+            node.set_needs_tessellation(true);
+        }
+    }
+
     pub fn set_active_artboard(&mut self, id: Uuid) {
         self.active_artboard_id = id;
     }
@@ -99,6 +625,8 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
         if id == self.selected_node_id {
             return;
         }
+        // The selection is not part of the history.
+        let paused = std::mem::replace(&mut self.history.borrow_mut().paused, true);
         self.artboards.iter_mut().for_each(|artboard| {
             if let Some(prev_selected_node_id) = self.selected_node_id {
                 if let Some(mut node) = artboard.1.get_node_mut(prev_selected_node_id) {
@@ -117,6 +645,7 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
                 }
             }
         });
+        self.history.borrow_mut().paused = paused;
         self.selected_node_id = id
     }
 
@@ -147,16 +676,143 @@ impl<N: RadiantNode> RadiantDocumentNode<N> {
         None
     }
 
+    /// Artboards with the nodes they hold, for host apps to render a layers panel.
+    pub fn layer_tree(&self) -> Vec<RadiantLayer> {
+        self.artboards.values().map(|artboard| artboard.layer()).collect()
+    }
+
+    /// Topmost node under `position`, testing later artboards first.
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.spatial_index
+            .query_point(position, tolerance)
+            .into_iter()
+            .find_map(|(artboard_id, id)| {
+                let artboard = self.artboards.get(&artboard_id)?;
+                if !artboard.base.is_pickable() || !artboard.reveals(position) {
+                    return None;
+                }
+                let node = artboard.get_node(id)?;
+                if node.base().layer().is_mask() || !node.base().is_pickable() {
+                    return None;
+                }
+                node.hit_test(position, tolerance)
+            })
+    }
+
+    /// Pickable nodes whose bounds intersect `rect`, topmost first, as for marquee
+    /// selection.
+    pub fn nodes_in_rect(&self, rect: [f32; 4]) -> Vec<Uuid> {
+        self.pickable_nodes(self.spatial_index.query_rect(rect))
+    }
+
+    /// Pickable nodes whose bounds lie entirely inside `rect`, topmost first.
+    pub fn nodes_inside_rect(&self, rect: [f32; 4]) -> Vec<Uuid> {
+        self.pickable_nodes(self.spatial_index.query_contained(rect))
+    }
+
+    /// Ids of the nodes among `entries` that are neither hidden nor locked, nor held by an
+    /// artboard that is.
+    fn pickable_nodes(&self, entries: Vec<(Uuid, Uuid)>) -> Vec<Uuid> {
+        entries
+            .into_iter()
+            .filter(|(artboard_id, id)| {
+                self.artboards
+                    .get(artboard_id)
+                    .filter(|artboard| artboard.base.is_pickable())
+                    .and_then(|artboard| artboard.get_node(*id))
+                    .is_some_and(|node| node.base().is_pickable())
+            })
+            .map(|(_, id)| id)
+            .collect()
+    }
+
+    /// Whether the node or artboard can be picked, which it can't when it or the artboard
+    /// holding it is hidden or locked.
+    pub fn is_pickable(&self, id: Uuid) -> bool {
+        if let Some(artboard) = self.artboards.get(&id) {
+            return artboard.base.is_pickable();
+        }
+        self.artboards.values().any(|artboard| {
+            artboard.base.is_pickable()
+                && artboard
+                    .get_node(id)
+                    .is_some_and(|node| node.base().is_pickable())
+        })
+    }
+
+    /// Nodes within `distance` of the node that it could snap to, with their bounds.
+    pub fn snap_candidates(&self, id: Uuid, distance: f32) -> Vec<(Uuid, [f32; 4])> {
+        let Some(node) = self.get_node(id) else {
+            return Vec::new();
+        };
+        let [left, top, right, bottom] = node.get_bounding_rect();
+        drop(node);
+        self.spatial_index
+            .query_rect([
+                left - distance,
+                top - distance,
+                right + distance,
+                bottom + distance,
+            ])
+            .into_iter()
+            .filter(|(_, candidate)| *candidate != id)
+            .filter_map(|(_, candidate)| {
+                let node = self.get_node(candidate)?;
+                Some((candidate, node.get_bounding_rect()))
+            })
+            .collect()
+    }
+
+    /// Makes every node tessellate again without notifying listeners, for when the
+    /// pixels per point change.
+    pub fn invalidate_tessellation(&mut self) {
+        for artboard in self.artboards.values() {
+            for node in artboard.nodes.values() {
+                node.write().set_needs_tessellation(false);
+            }
+        }
+    }
+
+    /// Reorders the children of the artboards following `order`, the ids of nodes from
+    /// every artboard, as when syncing the order with collaborators.
+    pub fn set_order(&mut self, order: &[Uuid]) {
+        for artboard in self.artboards.values_mut() {
+            artboard.set_order(order.to_vec());
+        }
+    }
+
     pub fn replace_node(&mut self, id: Uuid, node: N) {
         for artboard in &mut self.artboards {
             if artboard.1.get_node_mut(id).is_some() {
                 artboard.1.replace_node(id, node);
+                self.mark_changed(id);
                 return;
             }
         }
     }
 }
 
+fn apply_style_value<N: RadiantNode>(
+    node: &mut N,
+    target: RadiantStyleTarget,
+    value: &RadiantStyleValue,
+) {
+    match (target, value) {
+        (RadiantStyleTarget::Fill, RadiantStyleValue::Color(color)) => {
+            node.base_mut().set_fill_paint(RadiantPaint::Solid(*color))
+        }
+        (RadiantStyleTarget::Stroke, RadiantStyleValue::Color(color)) => {
+            node.color_mut().set_stroke_color(*color)
+        }
+        (RadiantStyleTarget::Text, RadiantStyleValue::Text(style)) => {
+            if let Some(component) = node.get_component_mut::<TextComponent>() {
+                component.set_style(*style);
+            }
+        }
+        _ => {}
+    }
+}
+
 impl<N: RadiantNode> RadiantTessellatable for RadiantDocumentNode<N> {
     fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
         for artboard in &mut self.artboards {
@@ -178,16 +834,7 @@ impl<N: RadiantNode> RadiantTessellatable for RadiantDocumentNode<N> {
         screen_descriptor: &ScreenDescriptor,
         fonts_manager: &epaint::text::Fonts,
     ) -> Vec<ClippedPrimitive> {
-        self.artboards
-            .iter_mut()
-            .fold(Vec::new(), |mut primitives, artboard| {
-                primitives.append(&mut artboard.1.tessellate(
-                    selection,
-                    screen_descriptor,
-                    fonts_manager,
-                ));
-                primitives
-            })
+        self.tessellate_artboards(selection, screen_descriptor, fonts_manager, None)
     }
 }
 
@@ -195,4 +842,207 @@ pub trait RadiantDocumentListener<N: RadiantNode> {
     fn get_id(&self) -> Uuid;
     fn on_node_added(&mut self, document: &RadiantDocumentNode<N>, node: Uuid);
     fn on_node_changed(&mut self, id: Uuid, data: &str);
+    fn on_node_removed(&mut self, _id: Uuid) {}
+    fn on_symbol_changed(&mut self, _symbol: &RadiantSymbol) {}
+    fn on_style_changed(&mut self, _style: &RadiantStyle) {}
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        RadiantComponent, RadiantDocumentNode, RadiantLineNode, RadiantNode, RadiantStyleTarget,
+        RadiantStyleValue, RadiantTessellatable, RadiantTextStyle, RadiantTypedComponent,
+        TextComponent,
+    };
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+    struct Weight(f32);
+
+    impl RadiantComponent for Weight {}
+
+    impl RadiantTypedComponent for Weight {
+        const TYPE_TAG: &'static str = "weight";
+    }
+
+    #[test]
+    fn index_follows_added_moved_and_removed_nodes() {
+        let mut document = RadiantDocumentNode::<RadiantLineNode>::new();
+        let id = Uuid::new_v4();
+        let mut node = RadiantLineNode::new(id, [0.0, 0.0], [10.0, 0.0]);
+        node.set_needs_tessellation(false);
+        document.add(node);
+        assert_eq!(document.hit_test([5.0, 0.0], 0.0), Some(id));
+
+        if let Some(mut node) = document.get_node_mut(id) {
+            node.transform_mut().transform_xy(&[100.0, 100.0].into());
+            node.set_needs_tessellation(false);
+        }
+        document.reindex(id);
+        assert_eq!(document.hit_test([5.0, 0.0], 0.0), None);
+        assert_eq!(document.hit_test([105.0, 100.0], 0.0), Some(id));
+
+        document.remove(id);
+        assert_eq!(document.hit_test([105.0, 100.0], 0.0), None);
+        assert!(document.nodes_in_rect([0.0, 0.0, 200.0, 200.0]).is_empty());
+    }
+
+    #[test]
+    fn queries_skip_hidden_and_locked_nodes_and_artboards() {
+        let mut document = RadiantDocumentNode::<RadiantLineNode>::new();
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        for id in ids {
+            let mut node = RadiantLineNode::new(id, [0.0, 0.0], [10.0, 0.0]);
+            node.set_needs_tessellation(false);
+            document.add(node);
+        }
+        if let Some(mut node) = document.get_node_mut(ids[1]) {
+            node.base_mut().visible = false;
+        }
+        if let Some(mut node) = document.get_node_mut(ids[2]) {
+            node.base_mut().locked = true;
+        }
+
+        assert_eq!(document.nodes_in_rect([-5.0, -5.0, 5.0, 5.0]), vec![ids[0]]);
+        assert_eq!(
+            document.nodes_inside_rect([-5.0, -5.0, 15.0, 5.0]),
+            vec![ids[0]]
+        );
+        assert!(document.is_pickable(ids[0]));
+
+        let artboard_id = document.active_artboard_id;
+        if let Some(artboard) = document.artboards.get_mut(&artboard_id) {
+            artboard.base.locked = true;
+        }
+        assert!(document.nodes_in_rect([-5.0, -5.0, 5.0, 5.0]).is_empty());
+        assert_eq!(document.hit_test([5.0, 0.0], 1.0), None);
+        assert!(!document.is_pickable(ids[0]));
+        assert!(!document.is_pickable(artboard_id));
+    }
+
+    #[test]
+    fn nudging_moves_only_the_selected_node() {
+        let mut document = RadiantDocumentNode::<RadiantLineNode>::new();
+        let ids = [Uuid::new_v4(), Uuid::new_v4()];
+        for id in ids {
+            let mut node = RadiantLineNode::new(id, [0.0, 0.0], [10.0, 0.0]);
+            node.set_needs_tessellation(false);
+            document.add(node);
+        }
+        let position = |document: &RadiantDocumentNode<RadiantLineNode>, id| {
+            let position: [f32; 2] = document.get_node(id).unwrap().transform().position().into();
+            position
+        };
+        assert!(document.nudge_selected_node([1.0, 0.0]).is_none());
+
+        document.select(Some(ids[1]));
+        let (id, transform) = document.nudge_selected_node([0.0, -10.0]).unwrap();
+        assert_eq!(id, ids[1]);
+        let moved: [f32; 2] = transform.position().into();
+        assert_eq!(moved, [0.0, -10.0]);
+        assert_eq!(position(&document, ids[1]), [0.0, -10.0]);
+        assert_eq!(position(&document, ids[0]), [0.0, 0.0]);
+        assert_eq!(document.hit_test([5.0, -10.0], 1.0), Some(ids[1]));
+    }
+
+    #[test]
+    fn edits_are_undone_by_step() {
+        let mut document = RadiantDocumentNode::<RadiantLineNode>::new();
+        let artboard_id = document.active_artboard_id;
+        let id = Uuid::new_v4();
+        document.add(RadiantLineNode::new(id, [0.0, 0.0], [10.0, 0.0]));
+        let position = |document: &RadiantDocumentNode<RadiantLineNode>| {
+            let position: [f32; 2] = document.get_node(id).unwrap().transform().position().into();
+            position
+        };
+        let drag = |document: &mut RadiantDocumentNode<RadiantLineNode>, moves: usize| {
+            document.begin_edit(None);
+            for _ in 0..moves {
+                document.begin_edit(Some((id, "transform")));
+                if let Some(mut node) = document.get_node_mut(id) {
+                    node.transform_mut().transform_xy(&[10.0, 0.0].into());
+                    node.set_needs_tessellation(true);
+                }
+            }
+        };
+
+        // The moves of one drag make up one step, and selecting records nothing.
+        drag(&mut document, 3);
+        document.begin_edit(None);
+        document.select(Some(id));
+        drag(&mut document, 1);
+        assert_eq!(position(&document), [40.0, 0.0]);
+        document.add_artboard();
+        let other_id = *document.artboards.keys().find(|id| **id != artboard_id).unwrap();
+        document.set_active_artboard(other_id);
+        document.begin_edit(None);
+        document.remove(id);
+
+        assert!(document.undo());
+        assert!(document.artboards[&artboard_id].get_node(id).is_some());
+        assert_eq!(position(&document), [40.0, 0.0]);
+        assert!(document.undo());
+        assert_eq!(position(&document), [30.0, 0.0]);
+        assert!(document.undo());
+        assert_eq!(position(&document), [0.0, 0.0]);
+        assert!(document.undo());
+        assert!(document.get_node(id).is_none());
+        assert!(!document.undo());
+    }
+
+    #[test]
+    fn loaded_documents_deserialize_the_components_of_their_registry() {
+        let mut document = RadiantDocumentNode::<RadiantLineNode>::new();
+        document.register_component_type::<Weight>();
+        let id = Uuid::new_v4();
+        let mut node = RadiantLineNode::new(id, [0.0, 0.0], [10.0, 0.0]);
+        node.base_mut().components.insert(Weight(2.0));
+        document.add(node);
+        let data = serde_json::to_string(&document).unwrap();
+        let weight = |document: &RadiantDocumentNode<RadiantLineNode>| {
+            let node = document.get_node(id).unwrap();
+            node.get_component::<Weight>().cloned()
+        };
+
+        let registry = document.registry.clone();
+        let loaded: RadiantDocumentNode<RadiantLineNode> =
+            registry.scope(|| serde_json::from_str(&data)).unwrap();
+        assert_eq!(loaded.registry.component_types(), registry.component_types());
+        assert_eq!(weight(&loaded), Some(Weight(2.0)));
+
+        let mut loaded: RadiantDocumentNode<RadiantLineNode> = serde_json::from_str(&data).unwrap();
+        assert_eq!(weight(&loaded), None);
+        loaded.set_registry(registry);
+        assert_eq!(weight(&loaded), Some(Weight(2.0)));
+    }
+
+    #[test]
+    fn text_styles_apply_to_nodes_carrying_text() {
+        let mut document = RadiantDocumentNode::<RadiantLineNode>::new();
+        let ids = [Uuid::new_v4(), Uuid::new_v4()];
+        for id in ids {
+            let mut node = RadiantLineNode::new(id, [0.0, 0.0], [10.0, 0.0]);
+            node.set_needs_tessellation(false);
+            document.add(node);
+        }
+        if let Some(mut node) = document.get_node_mut(ids[0]) {
+            node.base_mut().components.insert(TextComponent::new("Title".to_string()));
+        }
+        let style_id = Uuid::new_v4();
+        let style = RadiantTextStyle {
+            font_size: 40.0,
+            ..Default::default()
+        };
+        document.create_style(style_id, "Heading".to_string(), RadiantStyleValue::Text(style));
+        for id in ids {
+            document.apply_style(id, style_id, RadiantStyleTarget::Text);
+        }
+
+        let node = document.get_node(ids[0]).unwrap();
+        let text = node.get_component::<TextComponent>().unwrap();
+        assert_eq!(text.text(), "Title");
+        assert_eq!(text.style().font_size, 40.0);
+        assert!(document.get_node(ids[1]).unwrap().get_component::<TextComponent>().is_none());
+    }
 }
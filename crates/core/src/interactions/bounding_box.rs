@@ -1,6 +1,6 @@
 use crate::{
-    RadiantInteraction, RadiantLineNode, RadiantNode, RadiantRectangleNode, RadiantSceneMessage,
-    RadiantTessellatable, ScreenDescriptor, TransformComponent,
+    RadiantInteraction, RadiantLineNode, RadiantModifiers, RadiantNode, RadiantRectangleNode,
+    RadiantSceneMessage, RadiantTessellatable, ScreenDescriptor, TransformComponent,
 };
 use epaint::ClippedPrimitive;
 use once_cell::sync::Lazy;
@@ -17,6 +17,11 @@ static BOUNDING_BOX_BOTTOM_RIGHT_ID: Lazy<Uuid> = Lazy::new(|| Uuid::new_v4());
 static BOUNDING_BOX_BOTTOM_LEFT_ID: Lazy<Uuid> = Lazy::new(|| Uuid::new_v4());
 static BOUNDING_BOX_TOP_LEFT_ID: Lazy<Uuid> = Lazy::new(|| Uuid::new_v4());
 
+/// Size of the corner handles in screen points.
+const CORNER_SIZE: f32 = 16.0;
+/// Width of the edges in screen points.
+const EDGE_WIDTH: f32 = 1.0;
+
 #[derive(Debug, Clone)]
 pub struct BoundingBoxInteraction {
     pub active_node_id: Option<Uuid>,
@@ -36,10 +41,10 @@ impl BoundingBoxInteraction {
         ];
 
         let mut corner_nodes = vec![
-            RadiantRectangleNode::new(*BOUNDING_BOX_TOP_RIGHT_ID, [0.0, 0.0], [16.0, 16.0]),
-            RadiantRectangleNode::new(*BOUNDING_BOX_BOTTOM_RIGHT_ID, [0.0, 0.0], [16.0, 16.0]),
-            RadiantRectangleNode::new(*BOUNDING_BOX_BOTTOM_LEFT_ID, [0.0, 0.0], [16.0, 16.0]),
-            RadiantRectangleNode::new(*BOUNDING_BOX_TOP_LEFT_ID, [0.0, 0.0], [16.0, 16.0]),
+            RadiantRectangleNode::new(*BOUNDING_BOX_TOP_RIGHT_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantRectangleNode::new(*BOUNDING_BOX_BOTTOM_RIGHT_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantRectangleNode::new(*BOUNDING_BOX_BOTTOM_LEFT_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantRectangleNode::new(*BOUNDING_BOX_TOP_LEFT_ID, [0.0, 0.0], [0.0, 0.0]),
         ];
         for node in &mut corner_nodes {
             node.color_mut().set_fill_color(epaint::Color32::BLUE);
@@ -63,6 +68,10 @@ impl Default for BoundingBoxInteraction {
 
 impl BoundingBoxInteraction {
     pub fn contains(&self, id: Uuid) -> bool {
+        Self::is_handle(id)
+    }
+
+    pub fn is_handle(id: Uuid) -> bool {
         return id == *BOUNDING_BOX_TOP_ID
             || id == *BOUNDING_BOX_RIGHT_ID
             || id == *BOUNDING_BOX_BOTTOM_ID
@@ -73,9 +82,11 @@ impl BoundingBoxInteraction {
             || id == *BOUNDING_BOX_TOP_LEFT_ID;
     }
 
-    pub fn enable(&mut self, node: RwLockWriteGuard<impl RadiantNode>, _screen_descriptor: &ScreenDescriptor) {
+    pub fn enable(&mut self, node: RwLockWriteGuard<impl RadiantNode>, screen_descriptor: &ScreenDescriptor) {
         if let Some(_component) = node.get_component::<TransformComponent>() {
             let rect = node.get_bounding_rect();
+            let corner_size = screen_descriptor.document_length(CORNER_SIZE);
+            let offset = corner_size / 2.0;
 
             self.nodes[0].start = [rect[0], rect[1]].into();
             self.nodes[0].end = [rect[2], rect[1]].into();
@@ -91,21 +102,25 @@ impl BoundingBoxInteraction {
 
             self.corner_nodes[0]
                 .transform_mut()
-                .set_position(&[rect[2] - 8.0, rect[1] - 8.0].into());
+                .set_position(&[rect[2] - offset, rect[1] - offset].into());
             self.corner_nodes[1]
                 .transform_mut()
-                .set_position(&[rect[2] - 8.0, rect[3] - 8.0].into());
+                .set_position(&[rect[2] - offset, rect[3] - offset].into());
             self.corner_nodes[2]
                 .transform_mut()
-                .set_position(&[rect[0] - 8.0, rect[3] - 8.0].into());
+                .set_position(&[rect[0] - offset, rect[3] - offset].into());
             self.corner_nodes[3]
                 .transform_mut()
-                .set_position(&[rect[0] - 8.0, rect[1] - 8.0].into());
+                .set_position(&[rect[0] - offset, rect[1] - offset].into());
 
             for node in &mut self.nodes {
+                node.stroke_mut()
+                    .set_width(screen_descriptor.document_length(EDGE_WIDTH));
                 node.set_needs_tessellation(true);
             }
             for node in &mut self.corner_nodes {
+                node.transform_mut()
+                    .set_scale(&[corner_size, corner_size].into());
                 node.set_needs_tessellation(true);
             }
 
@@ -117,6 +132,20 @@ impl BoundingBoxInteraction {
         self.active_node_id = None;
     }
 
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.active_node_id?;
+        self.corner_nodes
+            .iter()
+            .rev()
+            .find_map(|node| node.hit_test(position, tolerance))
+            .or_else(|| {
+                self.nodes
+                    .iter()
+                    .rev()
+                    .find_map(|node| node.hit_test(position, tolerance))
+            })
+    }
+
     pub fn update(&mut self, node: RwLockWriteGuard<impl RadiantNode>, screen_descriptor: &ScreenDescriptor) {
         self.enable(node, screen_descriptor);
     }
@@ -190,54 +219,61 @@ impl RadiantInteraction for BoundingBoxInteraction {
 }
 
 impl BoundingBoxInteraction {
-    pub fn handle(&mut self, id: Uuid, transform: [f32; 2]) -> Option<RadiantSceneMessage> {
-        let Some(node_id) = self.active_node_id else {
-            return None;
-        };
+    /// Sides of the box moved by the handle, -1 for left or top and 1 for right or bottom.
+    fn handle_sides(id: Uuid) -> Option<[f32; 2]> {
         match id {
-            _id if id == *BOUNDING_BOX_TOP_ID => Some(RadiantSceneMessage::TransformNode {
-                id: node_id,
-                position: [0.0, transform[1]],
-                scale: [0.0, -transform[1]],
-            }),
-            _id if id == *BOUNDING_BOX_RIGHT_ID => Some(RadiantSceneMessage::TransformNode {
-                id: node_id,
-                position: [0.0, 0.0],
-                scale: [transform[0], 0.0],
-            }),
-            _id if id == *BOUNDING_BOX_BOTTOM_ID => Some(RadiantSceneMessage::TransformNode {
-                id: node_id,
-                position: [0.0, 0.0],
-                scale: [0.0, transform[1]],
-            }),
-            _id if id == *BOUNDING_BOX_LEFT_ID => Some(RadiantSceneMessage::TransformNode {
-                id: node_id,
-                position: [transform[0], 0.0],
-                scale: [-transform[0], 0.0],
-            }),
-            _id if id == *BOUNDING_BOX_TOP_RIGHT_ID => Some(RadiantSceneMessage::TransformNode {
-                id: node_id,
-                position: [0.0, transform[1]],
-                scale: [transform[0], -transform[1]],
-            }),
-            _id if id == *BOUNDING_BOX_BOTTOM_RIGHT_ID => {
-                Some(RadiantSceneMessage::TransformNode {
-                    id: node_id,
-                    position: [0.0, 0.0],
-                    scale: [transform[0], transform[1]],
-                })
-            }
-            _id if id == *BOUNDING_BOX_BOTTOM_LEFT_ID => Some(RadiantSceneMessage::TransformNode {
-                id: node_id,
-                position: [transform[0], 0.0],
-                scale: [-transform[0], transform[1]],
-            }),
-            _id if id == *BOUNDING_BOX_TOP_LEFT_ID => Some(RadiantSceneMessage::TransformNode {
-                id: node_id,
-                position: [transform[0], transform[1]],
-                scale: [-transform[0], -transform[1]],
-            }),
+            _id if id == *BOUNDING_BOX_TOP_ID => Some([0.0, -1.0]),
+            _id if id == *BOUNDING_BOX_RIGHT_ID => Some([1.0, 0.0]),
+            _id if id == *BOUNDING_BOX_BOTTOM_ID => Some([0.0, 1.0]),
+            _id if id == *BOUNDING_BOX_LEFT_ID => Some([-1.0, 0.0]),
+            _id if id == *BOUNDING_BOX_TOP_RIGHT_ID => Some([1.0, -1.0]),
+            _id if id == *BOUNDING_BOX_BOTTOM_RIGHT_ID => Some([1.0, 1.0]),
+            _id if id == *BOUNDING_BOX_BOTTOM_LEFT_ID => Some([-1.0, 1.0]),
+            _id if id == *BOUNDING_BOX_TOP_LEFT_ID => Some([-1.0, -1.0]),
             _ => None,
         }
     }
+
+    /// Resizes the node by dragging the handle `id` by `transform`. Shift keeps the aspect
+    /// ratio when dragging a corner and Alt resizes from the center.
+    pub fn handle(
+        &mut self,
+        id: Uuid,
+        transform: [f32; 2],
+        modifiers: RadiantModifiers,
+    ) -> Option<RadiantSceneMessage> {
+        let node_id = self.active_node_id?;
+        let sides = Self::handle_sides(id)?;
+
+        let factor = if modifiers.alt { 2.0 } else { 1.0 };
+        let mut scale = [
+            sides[0] * transform[0] * factor,
+            sides[1] * transform[1] * factor,
+        ];
+        if modifiers.shift && sides[0] != 0.0 && sides[1] != 0.0 {
+            let width = self.nodes[0].end.x - self.nodes[0].start.x;
+            let height = self.nodes[1].end.y - self.nodes[1].start.y;
+            if width > 0.0 && height > 0.0 {
+                if scale[0].abs() / width >= scale[1].abs() / height {
+                    scale[1] = scale[0] * height / width;
+                } else {
+                    scale[0] = scale[1] * width / height;
+                }
+            }
+        }
+
+        let position = if modifiers.alt {
+            [-scale[0] / 2.0, -scale[1] / 2.0]
+        } else {
+            [
+                if sides[0] < 0.0 { -scale[0] } else { 0.0 },
+                if sides[1] < 0.0 { -scale[1] } else { 0.0 },
+            ]
+        };
+        Some(RadiantSceneMessage::TransformNode {
+            id: node_id,
+            position,
+            scale,
+        })
+    }
 }
@@ -0,0 +1,194 @@
+use crate::{
+    RadiantInteraction, RadiantNode, RadiantRectangleNode, RadiantSceneMessage,
+    RadiantTessellatable, ScreenDescriptor,
+};
+use epaint::ClippedPrimitive;
+use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+static CORNER_RADIUS_TOP_LEFT_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+static CORNER_RADIUS_TOP_RIGHT_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+static CORNER_RADIUS_BOTTOM_RIGHT_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+static CORNER_RADIUS_BOTTOM_LEFT_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+
+/// Size of the handles in screen points.
+const HANDLE_SIZE: f32 = 10.0;
+/// Minimum distance of a handle from its corner so it does not overlap the bounding box, in
+/// screen points.
+const HANDLE_INSET: f32 = 16.0;
+/// Direction pointing from each corner towards the inside of the rectangle.
+const INWARD: [[f32; 2]; 4] = [[1.0, 1.0], [-1.0, 1.0], [-1.0, -1.0], [1.0, -1.0]];
+
+/// Round handles inside the corners of a rectangle-like node, dragged to change the radius of
+/// each corner.
+#[derive(Debug, Clone)]
+pub struct CornerRadiusInteraction {
+    pub active_node_id: Option<Uuid>,
+    pub corner_radius: [f32; 4],
+    pub max_radius: f32,
+    pub handle_nodes: Vec<RadiantRectangleNode>,
+    pub primitives: Vec<ClippedPrimitive>,
+    pub selection_primitives: Vec<ClippedPrimitive>,
+}
+
+impl CornerRadiusInteraction {
+    pub fn new() -> Self {
+        let mut handle_nodes = vec![
+            RadiantRectangleNode::new(*CORNER_RADIUS_TOP_LEFT_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantRectangleNode::new(*CORNER_RADIUS_TOP_RIGHT_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantRectangleNode::new(*CORNER_RADIUS_BOTTOM_RIGHT_ID, [0.0, 0.0], [0.0, 0.0]),
+            RadiantRectangleNode::new(*CORNER_RADIUS_BOTTOM_LEFT_ID, [0.0, 0.0], [0.0, 0.0]),
+        ];
+        for node in &mut handle_nodes {
+            node.color_mut().set_fill_color(epaint::Color32::WHITE);
+            node.color_mut().set_stroke_color(epaint::Color32::BLUE);
+        }
+
+        Self {
+            active_node_id: None,
+            corner_radius: [0.0; 4],
+            max_radius: 0.0,
+            handle_nodes,
+            primitives: Vec::new(),
+            selection_primitives: Vec::new(),
+        }
+    }
+}
+
+impl Default for CornerRadiusInteraction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CornerRadiusInteraction {
+    pub fn contains(&self, id: Uuid) -> bool {
+        Self::is_handle(id)
+    }
+
+    pub fn is_handle(id: Uuid) -> bool {
+        id == *CORNER_RADIUS_TOP_LEFT_ID
+            || id == *CORNER_RADIUS_TOP_RIGHT_ID
+            || id == *CORNER_RADIUS_BOTTOM_RIGHT_ID
+            || id == *CORNER_RADIUS_BOTTOM_LEFT_ID
+    }
+
+    pub fn enable(&mut self, node: &impl RadiantNode, screen_descriptor: &ScreenDescriptor) {
+        let Some(corner_radius) = node
+            .downcast_ref::<RadiantRectangleNode>()
+            .map(RadiantRectangleNode::get_corner_radius)
+        else {
+            self.disable();
+            return;
+        };
+
+        let [left, top, right, bottom] = node.get_bounding_rect();
+        let max_radius = (right - left).abs().min((bottom - top).abs()) / 2.0;
+        let corners = [[left, top], [right, top], [right, bottom], [left, bottom]];
+        let handle_size = screen_descriptor.document_length(HANDLE_SIZE);
+        let handle_inset = screen_descriptor.document_length(HANDLE_INSET);
+
+        for (i, handle) in self.handle_nodes.iter_mut().enumerate() {
+            let inset = corner_radius[i].min(max_radius).max(handle_inset.min(max_radius));
+            handle.transform_mut().set_position(
+                &[
+                    corners[i][0] + INWARD[i][0] * inset - handle_size / 2.0,
+                    corners[i][1] + INWARD[i][1] * inset - handle_size / 2.0,
+                ]
+                .into(),
+            );
+            handle
+                .transform_mut()
+                .set_scale(&[handle_size, handle_size].into());
+            handle.set_corner_radius([handle_size / 2.0; 4]);
+            handle
+                .stroke_mut()
+                .set_width(screen_descriptor.document_length(1.0));
+            handle.set_needs_tessellation(true);
+        }
+
+        self.corner_radius = corner_radius.map(|radius| radius.min(max_radius));
+        self.max_radius = max_radius;
+        self.active_node_id = Some(node.get_id());
+    }
+
+    pub fn disable(&mut self) {
+        self.active_node_id = None;
+    }
+
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.active_node_id?;
+        self.handle_nodes
+            .iter()
+            .rev()
+            .find_map(|node| node.hit_test(position, tolerance))
+    }
+
+    pub fn update(&mut self, node: &impl RadiantNode, screen_descriptor: &ScreenDescriptor) {
+        self.enable(node, screen_descriptor);
+    }
+}
+
+impl CornerRadiusInteraction {
+    pub fn tessellate(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+    ) -> Vec<ClippedPrimitive> {
+        if self.active_node_id.is_none() {
+            return Vec::new();
+        }
+
+        self.primitives = self
+            .handle_nodes
+            .iter_mut()
+            .fold(Vec::new(), |mut primitives, node| {
+                primitives.append(&mut node.tessellate(false, screen_descriptor, fonts_manager));
+                primitives
+            });
+        self.selection_primitives =
+            self.handle_nodes
+                .iter_mut()
+                .fold(Vec::new(), |mut primitives, node| {
+                    primitives.append(&mut node.tessellate(true, screen_descriptor, fonts_manager));
+                    primitives
+                });
+
+        self.get_primitives(selection)
+    }
+}
+
+impl RadiantInteraction for CornerRadiusInteraction {
+    fn get_primitives(&self, selection: bool) -> Vec<ClippedPrimitive> {
+        if selection {
+            self.selection_primitives.clone()
+        } else {
+            self.primitives.clone()
+        }
+    }
+}
+
+impl CornerRadiusInteraction {
+    pub fn handle(&mut self, id: Uuid, transform: [f32; 2]) -> Option<RadiantSceneMessage> {
+        let node_id = self.active_node_id?;
+        let index = [
+            *CORNER_RADIUS_TOP_LEFT_ID,
+            *CORNER_RADIUS_TOP_RIGHT_ID,
+            *CORNER_RADIUS_BOTTOM_RIGHT_ID,
+            *CORNER_RADIUS_BOTTOM_LEFT_ID,
+        ]
+        .iter()
+        .position(|handle_id| *handle_id == id)?;
+
+        // Only the movement along the corner diagonal changes the radius.
+        let delta = (transform[0] * INWARD[index][0] + transform[1] * INWARD[index][1]) / 2.0;
+        let mut corner_radius = self.corner_radius;
+        corner_radius[index] = (corner_radius[index] + delta).clamp(0.0, self.max_radius);
+
+        Some(RadiantSceneMessage::SetCornerRadius {
+            id: node_id,
+            corner_radius,
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    RadiantInteraction, RadiantLineNode, RadiantNode, RadiantRectangleNode, RadiantSceneMessage,
+    RadiantTessellatable, ScreenDescriptor,
+};
+use epaint::ClippedPrimitive;
+use once_cell::sync::Lazy;
+use parking_lot::RwLockWriteGuard;
+use uuid::Uuid;
+
+static ENDPOINT_START_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+static ENDPOINT_END_ID: Lazy<Uuid> = Lazy::new(Uuid::new_v4);
+
+/// Size of the handles in screen points.
+const HANDLE_SIZE: f32 = 12.0;
+
+/// Draggable handles placed on the endpoints of a line-like node.
+#[derive(Debug, Clone)]
+pub struct EndpointsInteraction {
+    pub active_node_id: Option<Uuid>,
+    pub endpoints: [[f32; 2]; 2],
+    pub handle_nodes: Vec<RadiantRectangleNode>,
+    pub primitives: Vec<ClippedPrimitive>,
+    pub selection_primitives: Vec<ClippedPrimitive>,
+}
+
+impl EndpointsInteraction {
+    pub fn new() -> Self {
+        let mut handle_nodes = vec![
+            RadiantRectangleNode::new(
+                *ENDPOINT_START_ID,
+                [0.0, 0.0],
+                [HANDLE_SIZE, HANDLE_SIZE],
+            ),
+            RadiantRectangleNode::new(*ENDPOINT_END_ID, [0.0, 0.0], [HANDLE_SIZE, HANDLE_SIZE]),
+        ];
+        for node in &mut handle_nodes {
+            node.color_mut().set_fill_color(epaint::Color32::BLUE);
+        }
+
+        Self {
+            active_node_id: None,
+            endpoints: [[0.0, 0.0], [0.0, 0.0]],
+            handle_nodes,
+            primitives: Vec::new(),
+            selection_primitives: Vec::new(),
+        }
+    }
+}
+
+impl Default for EndpointsInteraction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EndpointsInteraction {
+    pub fn contains(&self, id: Uuid) -> bool {
+        Self::is_handle(id)
+    }
+
+    pub fn is_handle(id: Uuid) -> bool {
+        id == *ENDPOINT_START_ID || id == *ENDPOINT_END_ID
+    }
+
+    pub fn enable(
+        &mut self,
+        node: RwLockWriteGuard<impl RadiantNode>,
+        screen_descriptor: &ScreenDescriptor,
+    ) {
+        let Some(endpoints) = node
+            .downcast_ref::<RadiantLineNode>()
+            .map(RadiantLineNode::get_endpoints)
+        else {
+            self.disable();
+            return;
+        };
+
+        let handle_size = screen_descriptor.document_length(HANDLE_SIZE);
+        for (handle, endpoint) in self.handle_nodes.iter_mut().zip(endpoints.iter()) {
+            handle.transform_mut().set_position(
+                &[
+                    endpoint[0] - handle_size / 2.0,
+                    endpoint[1] - handle_size / 2.0,
+                ]
+                .into(),
+            );
+            handle
+                .transform_mut()
+                .set_scale(&[handle_size, handle_size].into());
+            handle.set_needs_tessellation(true);
+        }
+
+        self.endpoints = endpoints;
+        self.active_node_id = Some(node.get_id());
+    }
+
+    pub fn disable(&mut self) {
+        self.active_node_id = None;
+    }
+
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.active_node_id?;
+        self.handle_nodes
+            .iter()
+            .rev()
+            .find_map(|node| node.hit_test(position, tolerance))
+    }
+
+    pub fn update(
+        &mut self,
+        node: RwLockWriteGuard<impl RadiantNode>,
+        screen_descriptor: &ScreenDescriptor,
+    ) {
+        self.enable(node, screen_descriptor);
+    }
+}
+
+impl EndpointsInteraction {
+    pub fn tessellate(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+    ) -> Vec<ClippedPrimitive> {
+        if self.active_node_id.is_none() {
+            return Vec::new();
+        }
+
+        self.primitives = self
+            .handle_nodes
+            .iter_mut()
+            .fold(Vec::new(), |mut primitives, node| {
+                primitives.append(&mut node.tessellate(false, screen_descriptor, fonts_manager));
+                primitives
+            });
+        self.selection_primitives =
+            self.handle_nodes
+                .iter_mut()
+                .fold(Vec::new(), |mut primitives, node| {
+                    primitives.append(&mut node.tessellate(true, screen_descriptor, fonts_manager));
+                    primitives
+                });
+
+        self.get_primitives(selection)
+    }
+}
+
+impl RadiantInteraction for EndpointsInteraction {
+    fn get_primitives(&self, selection: bool) -> Vec<ClippedPrimitive> {
+        if selection {
+            self.selection_primitives.clone()
+        } else {
+            self.primitives.clone()
+        }
+    }
+}
+
+impl EndpointsInteraction {
+    pub fn handle(&mut self, id: Uuid, transform: [f32; 2]) -> Option<RadiantSceneMessage> {
+        let node_id = self.active_node_id?;
+        let [mut start, mut end] = self.endpoints;
+        match id {
+            _id if id == *ENDPOINT_START_ID => {
+                start = [start[0] + transform[0], start[1] + transform[1]];
+            }
+            _id if id == *ENDPOINT_END_ID => {
+                end = [end[0] + transform[0], end[1] + transform[1]];
+            }
+            _ => return None,
+        }
+        Some(RadiantSceneMessage::SetEndpoints {
+            id: node_id,
+            start,
+            end,
+        })
+    }
+}
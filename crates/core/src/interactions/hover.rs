@@ -0,0 +1,60 @@
+use crate::ScreenDescriptor;
+use epaint::{pos2, ClippedPrimitive, ClippedShape, Color32, Rect, Stroke, TessellationOptions};
+
+const HOVER_STROKE_WIDTH: f32 = 1.5;
+
+/// Outline drawn around the node under the mouse.
+#[derive(Debug, Clone, Default)]
+pub struct HoverInteraction {
+    rect: Option<[f32; 4]>,
+    primitives: Vec<ClippedPrimitive>,
+}
+
+impl HoverInteraction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Outlines `rect`, or hides the outline when it is `None`.
+    pub fn set_rect(&mut self, rect: Option<[f32; 4]>, screen_descriptor: &ScreenDescriptor) {
+        if self.rect == rect {
+            return;
+        }
+        self.rect = rect;
+        self.primitives = match rect {
+            Some([left, top, right, bottom]) => {
+                let rect = Rect::from_min_max(pos2(left, top), pos2(right, bottom));
+                let shape = epaint::Shape::rect_stroke(
+                    rect,
+                    0.0,
+                    Stroke::new(
+                        screen_descriptor.document_length(HOVER_STROKE_WIDTH),
+                        Color32::BLUE,
+                    ),
+                );
+                epaint::tessellator::tessellate_shapes(
+                    screen_descriptor.pixels_per_point,
+                    TessellationOptions::default(),
+                    [1, 1],
+                    vec![],
+                    vec![ClippedShape(Rect::EVERYTHING, shape)],
+                )
+            }
+            None => Vec::new(),
+        };
+    }
+
+    /// Tessellates the outline again on the next `set_rect`, as after the camera changed.
+    pub fn invalidate(&mut self) {
+        self.rect = None;
+        self.primitives.clear();
+    }
+
+    pub fn tessellate(&self, selection: bool) -> Vec<ClippedPrimitive> {
+        if selection {
+            Vec::new()
+        } else {
+            self.primitives.clone()
+        }
+    }
+}
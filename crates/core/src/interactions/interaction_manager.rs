@@ -1,10 +1,23 @@
-use crate::{BoundingBoxInteraction, RadiantNode, RadiantSceneMessage, ScreenDescriptor};
+use crate::{
+    BoundingBoxInteraction, CornerRadiusInteraction, EndpointsInteraction, HoverInteraction,
+    RadiantLineNode, RadiantModifiers, RadiantNode, RadiantSceneMessage, ScreenDescriptor,
+};
 use epaint::ClippedPrimitive;
 use parking_lot::RwLockWriteGuard;
 use uuid::Uuid;
 
+/// Whether `id` is one of the handles of the interactions rather than a node of the document.
+pub fn is_interaction_handle(id: Uuid) -> bool {
+    BoundingBoxInteraction::is_handle(id)
+        || EndpointsInteraction::is_handle(id)
+        || CornerRadiusInteraction::is_handle(id)
+}
+
 pub struct RadiantInteractionManager<M> {
     pub bounding_box_interaction: BoundingBoxInteraction,
+    pub endpoints_interaction: EndpointsInteraction,
+    pub corner_radius_interaction: CornerRadiusInteraction,
+    pub hover_interaction: HoverInteraction,
     _phantom: std::marker::PhantomData<M>,
 }
 
@@ -12,12 +25,17 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
     pub fn new() -> Self {
         Self {
             bounding_box_interaction: BoundingBoxInteraction::new(),
+            endpoints_interaction: EndpointsInteraction::new(),
+            corner_radius_interaction: CornerRadiusInteraction::new(),
+            hover_interaction: HoverInteraction::new(),
             _phantom: std::marker::PhantomData,
         }
     }
 
     pub fn is_interaction(&self, id: Uuid) -> bool {
         self.bounding_box_interaction.contains(id)
+            || self.endpoints_interaction.contains(id)
+            || self.corner_radius_interaction.contains(id)
     }
 
     pub fn enable_interactions(
@@ -25,12 +43,22 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
         node: RwLockWriteGuard<impl RadiantNode>,
         screen_descriptor: &ScreenDescriptor,
     ) {
-        self.bounding_box_interaction
-            .enable(node, screen_descriptor);
+        if node.downcast_ref::<RadiantLineNode>().is_some() {
+            self.bounding_box_interaction.disable();
+            self.endpoints_interaction.enable(node, screen_descriptor);
+        } else {
+            self.endpoints_interaction.disable();
+            self.corner_radius_interaction
+                .enable(&*node, screen_descriptor);
+            self.bounding_box_interaction
+                .enable(node, screen_descriptor);
+        }
     }
 
     pub fn disable_interactions(&mut self) {
         self.bounding_box_interaction.disable();
+        self.endpoints_interaction.disable();
+        self.corner_radius_interaction.disable();
     }
 
     pub fn update_interactions(
@@ -38,20 +66,34 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
         node: RwLockWriteGuard<impl RadiantNode>,
         screen_descriptor: &ScreenDescriptor,
     ) {
-        self.bounding_box_interaction
-            .update(node, screen_descriptor);
+        if node.downcast_ref::<RadiantLineNode>().is_some() {
+            self.endpoints_interaction.update(node, screen_descriptor);
+        } else {
+            self.corner_radius_interaction
+                .update(&*node, screen_descriptor);
+            self.bounding_box_interaction
+                .update(node, screen_descriptor);
+        }
+    }
+
+    /// Handle under `position`, testing the handles drawn last first.
+    pub fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.corner_radius_interaction
+            .hit_test(position, tolerance)
+            .or_else(|| self.endpoints_interaction.hit_test(position, tolerance))
+            .or_else(|| self.bounding_box_interaction.hit_test(position, tolerance))
     }
 
-    pub fn handle_interaction(&mut self, message: M) -> Option<M> {
+    pub fn handle_interaction(&mut self, message: M, modifiers: RadiantModifiers) -> Option<M> {
         match message.try_into() {
             Ok(RadiantSceneMessage::TransformNode { id, position, .. })
                 if self.is_interaction(id) =>
             {
-                if let Some(m) = self.bounding_box_interaction.handle(id, position) {
-                    Some(m.into())
-                } else {
-                    None
-                }
+                self.bounding_box_interaction
+                    .handle(id, position, modifiers)
+                    .or_else(|| self.endpoints_interaction.handle(id, position))
+                    .or_else(|| self.corner_radius_interaction.handle(id, position))
+                    .map(|m| m.into())
             }
             _ => None,
         }
@@ -63,7 +105,22 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>> RadiantInterac
         screen_descriptor: &ScreenDescriptor,
         fonts_manager: &epaint::text::Fonts,
     ) -> Vec<ClippedPrimitive> {
-        self.bounding_box_interaction
-            .tessellate(selection, screen_descriptor, fonts_manager)
+        let mut primitives = self.hover_interaction.tessellate(selection);
+        primitives.append(&mut self.bounding_box_interaction.tessellate(
+            selection,
+            screen_descriptor,
+            fonts_manager,
+        ));
+        primitives.append(&mut self.endpoints_interaction.tessellate(
+            selection,
+            screen_descriptor,
+            fonts_manager,
+        ));
+        primitives.append(&mut self.corner_radius_interaction.tessellate(
+            selection,
+            screen_descriptor,
+            fonts_manager,
+        ));
+        primitives
     }
 }
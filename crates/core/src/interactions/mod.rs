@@ -1,7 +1,13 @@
 pub mod bounding_box;
+pub mod corner_radius;
+pub mod endpoints;
+pub mod hover;
 pub mod interaction_manager;
 
 pub use bounding_box::*;
+pub use corner_radius::*;
+pub use endpoints::*;
+pub use hover::*;
 pub use interaction_manager::*;
 
 use epaint::ClippedPrimitive;
@@ -0,0 +1,295 @@
+use crate::{KeyCode, RadiantModifiers, RadiantSceneMessage};
+use serde::{Deserialize, Serialize};
+
+/// Key pressed together with the modifiers held, which must match exactly.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RadiantKeyChord {
+    pub key: KeyCode,
+    #[serde(default)]
+    pub modifiers: RadiantModifiers,
+}
+
+impl RadiantKeyChord {
+    pub fn new(key: KeyCode, modifiers: RadiantModifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// Chord of a key pressed without modifiers.
+    pub fn key(key: KeyCode) -> Self {
+        Self::new(key, RadiantModifiers::default())
+    }
+
+    /// Chord of a character key pressed without modifiers.
+    pub fn char(c: &str) -> Self {
+        Self::key(KeyCode::Char(c.to_string()))
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.modifiers.shift = true;
+        self
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.modifiers.ctrl = true;
+        self
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.modifiers.alt = true;
+        self
+    }
+
+    pub fn with_meta(mut self) -> Self {
+        self.modifiers.meta = true;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RadiantKeyBinding {
+    pub chord: RadiantKeyChord,
+    pub message: RadiantSceneMessage,
+}
+
+/// Messages sent for the key chords pressed while no node of the selection consumes the
+/// key. Host apps can bind their own messages or replace the keymap altogether.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RadiantKeymap {
+    pub bindings: Vec<RadiantKeyBinding>,
+}
+
+impl RadiantKeymap {
+    /// Keymap without any binding.
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Binds `chord` to `message`, replacing the message it was bound to.
+    pub fn bind(&mut self, chord: RadiantKeyChord, message: RadiantSceneMessage) {
+        match self
+            .bindings
+            .iter_mut()
+            .find(|binding| binding.chord == chord)
+        {
+            Some(binding) => binding.message = message,
+            None => self.bindings.push(RadiantKeyBinding { chord, message }),
+        }
+    }
+
+    pub fn unbind(&mut self, chord: &RadiantKeyChord) -> Option<RadiantSceneMessage> {
+        let index = self
+            .bindings
+            .iter()
+            .position(|binding| &binding.chord == chord)?;
+        Some(self.bindings.remove(index).message)
+    }
+
+    /// Message bound to `chord`. Character keys pressed with Shift alone also match the
+    /// chord without it, since the keys that need Shift to type a character depend on the
+    /// keyboard layout. Chords with other modifiers match Shift exactly, so that Ctrl+Shift+Z
+    /// is not taken for Ctrl+Z.
+    pub fn message(&self, chord: &RadiantKeyChord) -> Option<&RadiantSceneMessage> {
+        let find = |chord: &RadiantKeyChord| {
+            self.bindings
+                .iter()
+                .find(|binding| &binding.chord == chord)
+                .map(|binding| &binding.message)
+        };
+        let modifiers = &chord.modifiers;
+        let shift_only = modifiers.shift && !(modifiers.ctrl || modifiers.alt || modifiers.meta);
+        find(chord).or_else(|| match chord.key {
+            KeyCode::Char(_) if shift_only => {
+                let mut chord = chord.clone();
+                chord.modifiers.shift = false;
+                find(&chord)
+            }
+            _ => None,
+        })
+    }
+
+    /// Binds every chord of `keymap`, overriding the bindings of this keymap.
+    pub fn extend(&mut self, keymap: RadiantKeymap) {
+        for binding in keymap.bindings {
+            self.bind(binding.chord, binding.message);
+        }
+    }
+}
+
+impl Default for RadiantKeymap {
+    fn default() -> Self {
+        let mut keymap = Self::new();
+        keymap.bind(
+            RadiantKeyChord::key(KeyCode::Delete),
+            RadiantSceneMessage::RemoveSelectedNode {},
+        );
+        keymap.bind(
+            RadiantKeyChord::key(KeyCode::Backspace),
+            RadiantSceneMessage::RemoveSelectedNode {},
+        );
+        keymap.bind(
+            RadiantKeyChord::key(KeyCode::Escape),
+            RadiantSceneMessage::SelectNode { id: None },
+        );
+        for (key, delta) in [
+            (KeyCode::ArrowUp, [0.0, -1.0]),
+            (KeyCode::ArrowDown, [0.0, 1.0]),
+            (KeyCode::ArrowLeft, [-1.0, 0.0]),
+            (KeyCode::ArrowRight, [1.0, 0.0]),
+        ] {
+            keymap.bind(
+                RadiantKeyChord::key(key.clone()),
+                RadiantSceneMessage::NudgeSelectedNode { delta },
+            );
+            keymap.bind(
+                RadiantKeyChord::key(key).with_shift(),
+                RadiantSceneMessage::NudgeSelectedNode {
+                    delta: [delta[0] * 10.0, delta[1] * 10.0],
+                },
+            );
+        }
+        // Ctrl on most platforms, Cmd on macOS.
+        for chord in [
+            RadiantKeyChord::char("z").with_ctrl(),
+            RadiantKeyChord::char("z").with_meta(),
+        ] {
+            keymap.bind(chord, RadiantSceneMessage::Undo {});
+        }
+        for chord in [
+            RadiantKeyChord::char("0").with_ctrl(),
+            RadiantKeyChord::char("0").with_meta(),
+        ] {
+            keymap.bind(
+                chord,
+                RadiantSceneMessage::SetCamera {
+                    offset: [0.0, 0.0],
+                    zoom: 1.0,
+                },
+            );
+        }
+        // "+" is typed with Shift on many layouts, on the same key as "=" on some.
+        for (key, factor) in [("=", 2.0), ("+", 2.0), ("-", 0.5)] {
+            let ctrl = RadiantKeyChord::char(key).with_ctrl();
+            let meta = RadiantKeyChord::char(key).with_meta();
+            let mut chords = vec![ctrl.clone(), meta.clone()];
+            if factor > 1.0 {
+                chords.extend([ctrl.with_shift(), meta.with_shift()]);
+            }
+            for chord in chords {
+                keymap.bind(
+                    chord,
+                    RadiantSceneMessage::ZoomCamera {
+                        factor,
+                        position: None,
+                    },
+                );
+            }
+        }
+        // The selection tool is registered by the scene, other tools by the host app.
+        keymap.bind(
+            RadiantKeyChord::char("v"),
+            RadiantSceneMessage::SelectTool { id: 0 },
+        );
+        keymap
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{KeyCode, RadiantKeyChord, RadiantKeymap, RadiantSceneMessage};
+
+    fn is_zoom(message: Option<&RadiantSceneMessage>, expected: f32) -> bool {
+        match message {
+            Some(RadiantSceneMessage::ZoomCamera { factor, .. }) => *factor == expected,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn chords_match_their_modifiers() {
+        let keymap = RadiantKeymap::default();
+        let zoom_in = RadiantKeyChord::char("=");
+        assert!(is_zoom(keymap.message(&zoom_in.clone().with_ctrl()), 2.0));
+        assert!(is_zoom(keymap.message(&zoom_in.with_meta()), 2.0));
+        assert!(keymap.message(&RadiantKeyChord::char("=")).is_none());
+        assert!(keymap
+            .message(&RadiantKeyChord::char("=").with_ctrl().with_alt())
+            .is_none());
+        assert!(matches!(
+            keymap.message(&RadiantKeyChord::char("z").with_ctrl()),
+            Some(RadiantSceneMessage::Undo {})
+        ));
+    }
+
+    #[test]
+    fn shift_is_ignored_for_characters_only() {
+        let keymap = RadiantKeymap::default();
+        // "+" is typed with Shift on many layouts.
+        let plus = RadiantKeyChord::char("+").with_ctrl().with_shift();
+        assert!(is_zoom(keymap.message(&plus), 2.0));
+        assert!(matches!(
+            keymap.message(&RadiantKeyChord::char("v").with_shift()),
+            Some(RadiantSceneMessage::SelectTool { id: 0 })
+        ));
+        for chord in [
+            RadiantKeyChord::char("z").with_ctrl().with_shift(),
+            RadiantKeyChord::char("z").with_meta().with_shift(),
+        ] {
+            assert!(keymap.message(&chord).is_none());
+        }
+        let nudge = keymap.message(&RadiantKeyChord::key(KeyCode::ArrowUp).with_shift());
+        assert!(matches!(
+            nudge,
+            Some(RadiantSceneMessage::NudgeSelectedNode { delta }) if *delta == [0.0, -10.0]
+        ));
+        let delete = RadiantKeyChord::key(KeyCode::Delete).with_shift();
+        assert!(keymap.message(&delete).is_none());
+    }
+
+    #[test]
+    fn arrows_nudge_by_one_or_ten_with_shift() {
+        let keymap = RadiantKeymap::default();
+        for (key, direction) in [
+            (KeyCode::ArrowUp, [0.0, -1.0]),
+            (KeyCode::ArrowDown, [0.0, 1.0]),
+            (KeyCode::ArrowLeft, [-1.0, 0.0]),
+            (KeyCode::ArrowRight, [1.0, 0.0]),
+        ] {
+            let chord = RadiantKeyChord::key(key);
+            for (chord, distance) in [(chord.clone(), 1.0), (chord.with_shift(), 10.0)] {
+                let expected = [direction[0] * distance, direction[1] * distance];
+                assert!(matches!(
+                    keymap.message(&chord),
+                    Some(RadiantSceneMessage::NudgeSelectedNode { delta }) if *delta == expected
+                ));
+            }
+        }
+        let chord = RadiantKeyChord::key(KeyCode::ArrowUp).with_ctrl();
+        assert!(keymap.message(&chord).is_none());
+    }
+
+    #[test]
+    fn bindings_are_replaced_and_removed() {
+        let mut keymap = RadiantKeymap::new();
+        let chord = RadiantKeyChord::char("r");
+        keymap.bind(chord.clone(), RadiantSceneMessage::SelectTool { id: 1 });
+        keymap.bind(chord.clone(), RadiantSceneMessage::SelectTool { id: 2 });
+        assert_eq!(keymap.bindings.len(), 1);
+        assert!(matches!(
+            keymap.message(&chord),
+            Some(RadiantSceneMessage::SelectTool { id: 2 })
+        ));
+        // An exact binding with Shift wins over the one without.
+        let shifted = chord.clone().with_shift();
+        keymap.bind(shifted.clone(), RadiantSceneMessage::SelectTool { id: 3 });
+        assert!(matches!(
+            keymap.message(&shifted),
+            Some(RadiantSceneMessage::SelectTool { id: 3 })
+        ));
+
+        assert!(keymap.unbind(&chord).is_some());
+        assert!(keymap.message(&chord).is_none());
+        assert!(keymap.unbind(&chord).is_none());
+    }
+}
@@ -1,27 +1,33 @@
+pub mod camera;
 pub mod components;
 pub mod document;
 pub mod interactions;
+pub mod keymap;
 pub mod message;
 pub mod nodes;
+pub mod picking;
+pub mod registry;
 pub mod render;
 pub mod scene;
+pub mod spatial;
 pub mod texture;
 pub mod tools;
 pub mod utils;
 
-use std::{
-    collections::HashMap,
-    sync::{RwLockReadGuard, RwLockWriteGuard},
-};
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
 
+pub use camera::*;
 pub use components::*;
 pub use document::*;
-use epaint::Color32;
 pub use interactions::*;
+pub use keymap::*;
 pub use message::*;
 pub use nodes::*;
+pub use picking::*;
+pub use registry::*;
 pub use render::*;
 pub use scene::*;
+pub use spatial::*;
 pub use texture::*;
 pub use tools::*;
 pub use utils::*;
@@ -32,11 +38,23 @@ pub struct ScreenDescriptor {
     /// Size of the window in physical pixels.
     pub size_in_pixels: [u32; 2],
 
-    /// HiDPI scale factor (pixels per point).
+    /// HiDPI scale factor (pixels per point), times the zoom of the camera.
     pub pixels_per_point: f32,
+
+    /// Document position shown at the top left corner of the window, in points.
+    pub offset: [f32; 2],
+
+    /// Zoom of the camera, in screen points per document point.
+    pub zoom: f32,
 }
 
 impl ScreenDescriptor {
+    /// Document length shown as `length` points on screen, as for handles and tolerances
+    /// that keep their size on screen at any zoom.
+    pub fn document_length(&self, length: f32) -> f32 {
+        length / self.zoom
+    }
+
     /// size in "logical" points
     pub fn screen_size_in_points(&self) -> [f32; 2] {
         [
@@ -44,6 +62,33 @@ impl ScreenDescriptor {
             self.size_in_pixels[1] as f32 / self.pixels_per_point,
         ]
     }
+
+    /// Document position of a position in physical pixels from the top left corner.
+    pub fn position_in_points(&self, position_in_pixels: [f32; 2]) -> [f32; 2] {
+        [
+            position_in_pixels[0] / self.pixels_per_point + self.offset[0],
+            position_in_pixels[1] / self.pixels_per_point + self.offset[1],
+        ]
+    }
+
+    /// Physical pixels from the top left corner of a document position.
+    pub fn position_in_pixels(&self, position: [f32; 2]) -> [f32; 2] {
+        [
+            (position[0] - self.offset[0]) * self.pixels_per_point,
+            (position[1] - self.offset[1]) * self.pixels_per_point,
+        ]
+    }
+
+    /// Part of the document shown in the window, as `[left, top, right, bottom]`.
+    pub fn viewport(&self) -> [f32; 4] {
+        let [width, height] = self.screen_size_in_points();
+        [
+            self.offset[0],
+            self.offset[1],
+            self.offset[0] + width,
+            self.offset[1] + height,
+        ]
+    }
 }
 
 pub trait View<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode> {
@@ -76,39 +121,3 @@ pub trait Runtime<
         self.scene_mut().add(node);
     }
 }
-
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
-use uuid::Uuid;
-
-static COUNTER: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(0));
-static NODE_TO_COLORS: Lazy<Mutex<HashMap<Uuid, Color32>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
-static COLOR_TO_NODES: Lazy<Mutex<HashMap<Color32, Uuid>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
-
-pub fn get_color_for_node(node_id: Uuid) -> Color32 {
-    let mut colors = NODE_TO_COLORS.lock().unwrap();
-    if let Some(color) = colors.get(&node_id) {
-        return *color;
-    }
-    let mut counter = COUNTER.lock().unwrap();
-    *counter += 1;
-    let c = *counter;
-    let color = Color32::from_rgb(
-        (c >> 0) as u8 & 0xFF,
-        (c >> 8) as u8 & 0xFF,
-        (c >> 16) as u8 & 0xFF,
-    );
-    colors.insert(node_id, color);
-
-    let mut color_to_nodes = COLOR_TO_NODES.lock().unwrap();
-    color_to_nodes.insert(color, node_id);
-
-    color
-}
-
-pub fn get_node_for_color(color: Color32) -> Option<Uuid> {
-    let color_to_nodes = COLOR_TO_NODES.lock().unwrap();
-    color_to_nodes.get(&color).copied()
-}
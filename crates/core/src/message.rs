@@ -2,7 +2,11 @@ use macro_magic::export_tokens;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{KeyCode, RadiantNode};
+use crate::{
+    AutoLayoutComponent, KeyCode, RadiantBlendMode, RadiantConstraint, RadiantEditGesture,
+    RadiantEffect, RadiantLayoutSizing, RadiantModifiers, RadiantNode, RadiantPaint,
+    RadiantStyleTarget, RadiantStyleValue, StrokeComponent,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantSceneMessage {
@@ -13,33 +17,202 @@ pub enum RadiantSceneMessage {
     SelectNode {
         id: Option<Uuid>,
     },
+    /// Sets the node under the mouse, `None` when there is none.
+    HoverNode {
+        id: Option<Uuid>,
+    },
+    RemoveNode {
+        id: Uuid,
+    },
+    RemoveSelectedNode {},
+    /// Adds a copy of the node with the id `new_id` and selects it.
+    DuplicateNode {
+        id: Uuid,
+        new_id: Uuid,
+    },
+    /// Selects the node and asks the host to start editing its content.
+    EditNode {
+        id: Uuid,
+    },
+    /// Selects the node under `position` and asks the host to show its context menu.
+    OpenContextMenu {
+        id: Option<Uuid>,
+        position: [f32; 2],
+    },
     TransformNode {
         id: Uuid,
         position: [f32; 2],
         scale: [f32; 2],
     },
+    /// Moves the selected node by `delta`.
+    NudgeSelectedNode {
+        delta: [f32; 2],
+    },
     SetTransform {
         id: Uuid,
         position: [f32; 2],
         scale: [f32; 2],
     },
+    SetEndpoints {
+        id: Uuid,
+        start: [f32; 2],
+        end: [f32; 2],
+    },
+    SetCornerRadius {
+        id: Uuid,
+        corner_radius: [f32; 4],
+    },
     SetFillColor {
         id: Uuid,
         fill_color: epaint::Color32,
     },
+    SetFill {
+        id: Uuid,
+        paint: RadiantPaint,
+    },
     SetStrokeColor {
         id: Uuid,
         stroke_color: epaint::Color32,
     },
+    SetStroke {
+        id: Uuid,
+        stroke: StrokeComponent,
+    },
+    SetOpacity {
+        id: Uuid,
+        opacity: f32,
+    },
+    SetBlendMode {
+        id: Uuid,
+        blend_mode: RadiantBlendMode,
+    },
+    SetEffects {
+        id: Uuid,
+        effects: Vec<RadiantEffect>,
+    },
+    SetMask {
+        id: Uuid,
+        mask: bool,
+    },
+    /// Clips the children of an artboard to its bounds.
+    SetClipContent {
+        id: Uuid,
+        clip_content: bool,
+    },
+    /// Lays out the children of an artboard in a row or a column, in the order they were
+    /// added.
+    SetAutoLayout {
+        id: Uuid,
+        auto_layout: Option<AutoLayoutComponent>,
+    },
+    SetLayoutSizing {
+        id: Uuid,
+        sizing: [RadiantLayoutSizing; 2],
+    },
+    SetConstraints {
+        id: Uuid,
+        constraints: [RadiantConstraint; 2],
+    },
+    /// Sets the component registered under `type_tag` from its serialized value. Components
+    /// of unregistered types are kept as they are.
+    SetComponent {
+        id: Uuid,
+        type_tag: String,
+        value: serde_json::Value,
+    },
+    RemoveComponent {
+        id: Uuid,
+        type_tag: String,
+    },
+    RenameNode {
+        id: Uuid,
+        name: String,
+    },
+    SetVisible {
+        id: Uuid,
+        visible: bool,
+    },
+    SetLocked {
+        id: Uuid,
+        locked: bool,
+    },
+    ResizeArtboard {
+        id: Uuid,
+        position: [f32; 2],
+        size: [f32; 2],
+    },
+    CreateSymbol {
+        id: Option<Uuid>,
+        name: String,
+        node_ids: Vec<Uuid>,
+    },
+    CreateStyle {
+        id: Option<Uuid>,
+        name: String,
+        value: RadiantStyleValue,
+    },
+    UpdateStyle {
+        id: Uuid,
+        value: RadiantStyleValue,
+    },
+    ApplyStyle {
+        id: Uuid,
+        style_id: Uuid,
+        target: RadiantStyleTarget,
+    },
+    DetachStyle {
+        id: Uuid,
+        target: RadiantStyleTarget,
+    },
     SelectTool {
         id: u32,
     },
+    /// Activates the tool registered under `name`.
+    SelectToolByName {
+        name: String,
+    },
+    /// Abandons what the active tool is doing, such as the node being drawn.
+    CancelTool {},
+    /// Moves the document by `delta` screen points.
+    PanCamera {
+        delta: [f32; 2],
+    },
+    /// Scales the zoom by `factor` around the document position `position`, or around the
+    /// center of the window.
+    ZoomCamera {
+        factor: f32,
+        position: Option<[f32; 2]>,
+    },
+    SetCamera {
+        offset: [f32; 2],
+        zoom: f32,
+    },
+    /// Undoes the edits of the last message, or of the last gesture such as a drag.
+    Undo {},
+    /// Offers the key to the node, or to the selected node when `id` is `None`, and sends
+    /// the message the keymap binds to the chord when the node does not consume it.
     HandleKey {
         id: Option<Uuid>,
         key: KeyCode,
+        #[serde(default)]
+        modifiers: RadiantModifiers,
     },
 }
 
+impl RadiantSceneMessage {
+    /// Node edited by the consecutive messages of a drag or of typing, with the kind of
+    /// edit, so that they are undone at once.
+    pub fn edit_gesture(&self) -> Option<RadiantEditGesture> {
+        match self {
+            Self::TransformNode { id, .. } => Some((*id, "transform")),
+            Self::SetEndpoints { id, .. } => Some((*id, "endpoints")),
+            Self::SetCornerRadius { id, .. } => Some((*id, "corner_radius")),
+            Self::HandleKey { id: Some(id), .. } => Some((*id, "key")),
+            _ => None,
+        }
+    }
+}
+
 #[export_tokens]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantSceneResponse<M, N: RadiantNode> {
@@ -49,6 +222,21 @@ pub enum RadiantSceneResponse<M, N: RadiantNode> {
     Selected {
         node: N,
     },
+    Hovered {
+        id: Option<uuid::Uuid>,
+    },
+    /// The node is being edited, and selected like with `Selected`.
+    Editing {
+        node: N,
+    },
+    ContextMenu {
+        id: Option<uuid::Uuid>,
+        position: [f32; 2],
+    },
+    CameraUpdated {
+        offset: [f32; 2],
+        zoom: f32,
+    },
     TransformUpdated {
         id: uuid::Uuid,
         position: [f32; 2],
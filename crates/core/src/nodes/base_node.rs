@@ -1,41 +1,120 @@
-use std::{any::{Any, TypeId}, sync::Arc, fmt::Debug};
+use std::{any::Any, sync::{Arc, OnceLock}, fmt::Debug};
 
-use crate::{ColorComponent, SelectionComponent, TransformComponent, Vec3, Observer};
-use epaint::ClippedPrimitive;
-use serde::{Deserialize, Serialize};
+use crate::{
+    ColorComponent, LayerComponent, LayoutItemComponent, Observer, RadiantComponentMap,
+    RadiantPaint, SelectionComponent, StrokeComponent, StyleComponent, TransformComponent, Vec3,
+};
+use epaint::{ClippedPrimitive, ClippedShape, Rect, Shape, TessellationOptions};
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
 pub type SubscriptionCallback = dyn Fn(&str)->() + 'static;
 
-#[derive(Serialize, Deserialize, Default)]
+/// Shapes a node is drawn with, in document points.
+pub struct RadiantShapes {
+    pub bounding_rect: Rect,
+    /// Shapes painted with the fill of the node, whose gradients span the bounding rect.
+    pub fill: Vec<Shape>,
+    /// Shapes drawn over the fill in their own colors, such as the stroke.
+    pub shapes: Vec<Shape>,
+    /// Shapes the node is picked with, which the picking registry paints with its id.
+    pub selection: Vec<Shape>,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct BaseNode {
     pub id: Uuid,
+    /// Name shown in the layers panel.
+    #[serde(default)]
+    pub name: String,
+    /// Hidden nodes are neither drawn nor picked.
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    /// Locked nodes are drawn but cannot be picked.
+    #[serde(default)]
+    pub locked: bool,
     pub transform: TransformComponent,
     pub selection: SelectionComponent,
     pub color: ColorComponent,
+    /// Overrides the fill color of `color` with a gradient.
+    #[serde(default)]
+    pub fill: Option<RadiantPaint>,
+    /// Stroke, layer, layout and style components, and the ones of other registered types
+    /// such as metadata.
+    #[serde(
+        default = "builtin_components",
+        deserialize_with = "deserialize_components"
+    )]
+    pub components: RadiantComponentMap,
     #[serde(skip)]
     pub primitives: Vec<ClippedPrimitive>,
     #[serde(skip)]
     pub selection_primitives: Vec<ClippedPrimitive>,
     #[serde(skip)]
     pub needs_tessellation: bool,
+    /// Incremented every time the node needs to be tessellated, telling primitives cached
+    /// outside of the node apart from its current ones.
+    #[serde(skip)]
+    pub revision: u64,
     #[serde(skip)]
     pub bounding_rect: [f32; 4],
+    /// Shapes built when the node last changed, tessellated on the next attach.
+    #[serde(skip)]
+    pub shapes: Option<RadiantShapes>,
     #[serde(skip)]
     pub observers: Observer<Arc<SubscriptionCallback>>,
 }
 
+fn default_visible() -> bool {
+    true
+}
+
+/// Components every node carries, with their defaults.
+fn builtin_components() -> RadiantComponentMap {
+    let mut components = RadiantComponentMap::new();
+    add_builtin_components(&mut components);
+    components
+}
+
+fn add_builtin_components(components: &mut RadiantComponentMap) {
+    components.get_or_insert_default::<StrokeComponent>();
+    components.get_or_insert_default::<LayerComponent>();
+    components.get_or_insert_default::<LayoutItemComponent>();
+    components.get_or_insert_default::<StyleComponent>();
+}
+
+fn deserialize_components<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<RadiantComponentMap, D::Error> {
+    let mut components = RadiantComponentMap::deserialize(deserializer)?;
+    add_builtin_components(&mut components);
+    Ok(components)
+}
+
+impl Default for BaseNode {
+    fn default() -> Self {
+        Self::new(Uuid::nil(), Vec3::default(), Vec3::default())
+    }
+}
+
 impl Clone for BaseNode {
     fn clone(&self) -> Self {
         Self {
             id: self.id,
+            name: self.name.clone(),
+            visible: self.visible,
+            locked: self.locked,
             transform: self.transform.clone(),
             selection: self.selection.clone(),
             color: self.color.clone(),
+            fill: self.fill.clone(),
+            components: self.components.clone(),
             primitives: Vec::new(),
             selection_primitives: Vec::new(),
             needs_tessellation: true,
+            revision: 0,
             bounding_rect: [0.0, 0.0, 0.0, 0.0],
+            shapes: None,
             observers: Observer::default(),
         }
     }
@@ -45,12 +124,18 @@ impl Debug for BaseNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BaseNode")
             .field("id", &self.id)
+            .field("name", &self.name)
+            .field("visible", &self.visible)
+            .field("locked", &self.locked)
             .field("transform", &self.transform)
             .field("selection", &self.selection)
             .field("color", &self.color)
+            .field("fill", &self.fill)
+            .field("components", &self.components)
             .field("primitives", &self.primitives)
             .field("selection_primitives", &self.selection_primitives)
             .field("needs_tessellation", &self.needs_tessellation)
+            .field("revision", &self.revision)
             .field("bounding_rect", &self.bounding_rect)
             .finish()
     }
@@ -67,19 +152,73 @@ impl BaseNode {
 
         Self {
             id,
+            name: String::new(),
+            visible: true,
+            locked: false,
             transform,
             selection,
             color,
+            fill: None,
+            components: builtin_components(),
             primitives: Vec::new(),
             selection_primitives: Vec::new(),
             needs_tessellation: true,
+            revision: 0,
             bounding_rect: [0.0, 0.0, 0.0, 0.0],
+            shapes: None,
             observers: Observer::default(),
         }
     }
 
+    /// Whether the node can be hit by the mouse.
+    pub fn is_pickable(&self) -> bool {
+        self.visible && !self.locked
+    }
+
     pub fn set_needs_tessellation(&mut self) {
         self.needs_tessellation = true;
+        // The node may have changed since its shapes were built.
+        self.shapes = None;
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    /// Tessellates the shapes of the node into its primitives and selection primitives.
+    pub fn tessellate_shapes(&mut self, shapes: RadiantShapes, pixels_per_point: f32) {
+        self.needs_tessellation = false;
+
+        let tessellate = |shapes: Vec<Shape>| {
+            epaint::tessellator::tessellate_shapes(
+                pixels_per_point,
+                TessellationOptions::default(),
+                [1, 1],
+                vec![],
+                shapes
+                    .into_iter()
+                    .map(|shape| ClippedShape(Rect::EVERYTHING, shape))
+                    .collect(),
+            )
+        };
+        self.primitives = tessellate(shapes.fill);
+        self.fill_paint()
+            .apply(&mut self.primitives, shapes.bounding_rect);
+        self.primitives.append(&mut tessellate(shapes.shapes));
+        self.selection_primitives = tessellate(shapes.selection);
+    }
+
+    pub fn fill_paint(&self) -> RadiantPaint {
+        self.fill
+            .clone()
+            .unwrap_or(RadiantPaint::Solid(self.color.fill_color()))
+    }
+
+    pub fn set_fill_paint(&mut self, paint: RadiantPaint) {
+        match paint {
+            RadiantPaint::Solid(color) => {
+                self.color.set_fill_color(color);
+                self.fill = None;
+            }
+            paint => self.fill = Some(paint.normalized()),
+        }
     }
 
     pub fn notify(&self, message: String) {
@@ -89,28 +228,48 @@ impl BaseNode {
     }
 }
 
+/// Accessors of the components every node carries. A component removed from the map reads
+/// as its default, and is added back when it is changed.
+macro_rules! builtin_component {
+    ($get:ident, $get_mut:ident, $component:ty) => {
+        pub fn $get(&self) -> &$component {
+            static DEFAULT: OnceLock<$component> = OnceLock::new();
+            self.components
+                .get()
+                .unwrap_or_else(|| DEFAULT.get_or_init(Default::default))
+        }
+
+        pub fn $get_mut(&mut self) -> &mut $component {
+            self.components.get_or_insert_default()
+        }
+    };
+}
+
 impl BaseNode {
+    builtin_component!(stroke, stroke_mut, StrokeComponent);
+    builtin_component!(layer, layer_mut, LayerComponent);
+    builtin_component!(layout, layout_mut, LayoutItemComponent);
+    builtin_component!(style, style_mut, StyleComponent);
+
+    /// The transform, selection or color component of type `T`, or the component of type
+    /// `T` in the component map.
     pub fn get_component<T: crate::RadiantComponent + 'static>(&self) -> Option<&T> {
-        if TypeId::of::<T>() == TypeId::of::<SelectionComponent>() {
-            unsafe { Some(&*(&self.selection as *const dyn Any as *const T)) }
-        } else if TypeId::of::<T>() == TypeId::of::<TransformComponent>() {
-            unsafe { Some(&*(&self.transform as *const dyn Any as *const T)) }
-        } else if TypeId::of::<T>() == TypeId::of::<ColorComponent>() {
-            unsafe { Some(&*(&self.color as *const dyn Any as *const T)) }
-        } else {
-            None
-        }
+        let components: [&dyn Any; 3] = [&self.selection, &self.transform, &self.color];
+        components
+            .into_iter()
+            .find_map(|component| component.downcast_ref())
+            .or_else(|| self.components.get())
     }
 
     pub fn get_component_mut<T: crate::RadiantComponent + 'static>(&mut self) -> Option<&mut T> {
-        if TypeId::of::<T>() == TypeId::of::<SelectionComponent>() {
-            unsafe { Some(&mut *(&mut self.selection as *mut dyn Any as *mut T)) }
-        } else if TypeId::of::<T>() == TypeId::of::<TransformComponent>() {
-            unsafe { Some(&mut *(&mut self.transform as *mut dyn Any as *mut T)) }
-        } else if TypeId::of::<T>() == TypeId::of::<ColorComponent>() {
-            unsafe { Some(&mut *(&mut self.color as *mut dyn Any as *mut T)) }
-        } else {
-            None
+        let components: [&mut dyn Any; 3] =
+            [&mut self.selection, &mut self.transform, &mut self.color];
+        match components
+            .into_iter()
+            .find_map(|component| component.downcast_mut())
+        {
+            Some(component) => Some(component),
+            None => self.components.get_mut(),
         }
     }
 }
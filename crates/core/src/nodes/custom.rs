@@ -0,0 +1,207 @@
+use std::{any::Any, fmt::Debug};
+
+use crate::{BaseNode, RadiantNode, RadiantTessellatable, RadiantTypeRegistry, ScreenDescriptor};
+use epaint::ClippedPrimitive;
+use macro_magic::export_tokens;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+/// Node kind defined outside of radiantkit. Once registered with
+/// [`RadiantTypeRegistry::register_node_type`], it is stored in documents as a
+/// [`RadiantCustomNode`] and serialized under its type tag.
+pub trait RadiantTypedNode: RadiantNode + DeserializeOwned + Debug {
+    const TYPE_TAG: &'static str;
+
+    /// Handles a message sent with [`RadiantCustomMessage`], returning whether the
+    /// interactions of the node need updating.
+    fn handle_message(&mut self, _message: serde_json::Value) -> bool {
+        false
+    }
+}
+
+/// Declares the object safe [`RadiantDynNode`], implements it for every
+/// [`RadiantTypedNode`] and forwards the methods of `RadiantCustomNode` to it, from one
+/// list of the forwarded trait methods.
+macro_rules! forward_to_dyn_node {
+    ($(
+        impl $trait:ident {
+            $(fn $name:ident(&$($receiver:ident)+ $(, $arg:ident: $ty:ty)*) $(-> $ret:ty)?;)*
+        }
+    )*) => {
+        /// Object safe part of [`RadiantNode`], implemented for every [`RadiantTypedNode`].
+        pub trait RadiantDynNode: Debug {
+            fn type_tag(&self) -> &'static str;
+            fn as_any(&self) -> &dyn Any;
+            fn as_any_mut(&mut self) -> &mut dyn Any;
+            fn clone_box(&self) -> Box<dyn RadiantDynNode>;
+            fn to_value(&self) -> serde_json::Result<serde_json::Value>;
+            fn handle_message(&mut self, message: serde_json::Value) -> bool;
+            $($(fn $name(&$($receiver)+ $(, $arg: $ty)*) $(-> $ret)?;)*)*
+        }
+
+        impl<T: RadiantTypedNode> RadiantDynNode for T {
+            fn type_tag(&self) -> &'static str {
+                T::TYPE_TAG
+            }
+
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn Any {
+                self
+            }
+
+            fn clone_box(&self) -> Box<dyn RadiantDynNode> {
+                Box::new(self.clone())
+            }
+
+            fn to_value(&self) -> serde_json::Result<serde_json::Value> {
+                serde_json::to_value(self)
+            }
+
+            fn handle_message(&mut self, message: serde_json::Value) -> bool {
+                RadiantTypedNode::handle_message(self, message)
+            }
+
+            $($(
+                fn $name(&$($receiver)+ $(, $arg: $ty)*) $(-> $ret)? {
+                    $trait::$name(forward_to_dyn_node!(@self $($receiver)+) $(, $arg)*)
+                }
+            )*)*
+        }
+
+        $(
+            impl $trait for RadiantCustomNode {
+                $(
+                    fn $name(&$($receiver)+ $(, $arg: $ty)*) $(-> $ret)? {
+                        forward_to_dyn_node!(@self $($receiver)+).0.$name($($arg),*)
+                    }
+                )*
+
+                forward_to_dyn_node!(@extra $trait);
+            }
+        )*
+    };
+    // `self` is taken from the invocation, as hygiene keeps it from matching the receiver
+    // otherwise.
+    (@self mut $self:ident) => {
+        $self
+    };
+    (@self $self:ident) => {
+        $self
+    };
+    (@extra RadiantNode) => {
+        fn node_type(&self) -> &str {
+            self.type_tag()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self.0.as_any()
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self.0.as_any_mut()
+        }
+    };
+    (@extra $trait:ident) => {};
+}
+
+forward_to_dyn_node! {
+    impl RadiantTessellatable {
+        fn attach(&mut self, screen_descriptor: &ScreenDescriptor);
+        fn detach(&mut self);
+        fn set_needs_tessellation(&mut self, notify: bool);
+        fn tessellate(
+            &mut self,
+            selection: bool,
+            screen_descriptor: &ScreenDescriptor,
+            fonts_manager: &epaint::text::Fonts
+        ) -> Vec<ClippedPrimitive>;
+    }
+
+    impl RadiantNode {
+        fn base(&self) -> &BaseNode;
+        fn base_mut(&mut self) -> &mut BaseNode;
+        fn get_bounding_rect(&self) -> [f32; 4];
+        fn get_visual_rect(&self) -> [f32; 4];
+        fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid>;
+        fn handle_key_down(&mut self, key: crate::KeyCode) -> bool;
+        fn replace(&mut self, node: &str);
+    }
+}
+
+/// Node of a type registered at runtime, serialized as its type tag and its own fields.
+#[derive(Debug)]
+pub struct RadiantCustomNode(pub Box<dyn RadiantDynNode>);
+
+#[derive(Serialize, Deserialize)]
+struct TaggedNode {
+    #[serde(rename = "type")]
+    type_tag: String,
+    node: serde_json::Value,
+}
+
+impl RadiantCustomNode {
+    pub fn new<T: RadiantTypedNode>(node: T) -> Self {
+        Self(Box::new(node))
+    }
+
+    pub fn type_tag(&self) -> &'static str {
+        self.0.type_tag()
+    }
+
+    pub fn handle_message(&mut self, message: serde_json::Value) -> bool {
+        self.0.handle_message(message)
+    }
+}
+
+impl Clone for RadiantCustomNode {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_box())
+    }
+}
+
+impl Serialize for RadiantCustomNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = self.0.to_value().map_err(serde::ser::Error::custom)?;
+        TaggedNode {
+            type_tag: self.type_tag().to_string(),
+            node,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RadiantCustomNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let TaggedNode { type_tag, node } = TaggedNode::deserialize(deserializer)?;
+        let registry = RadiantTypeRegistry::scoped().ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "node type `{}` deserialized outside of a registry scope",
+                type_tag
+            ))
+        })?;
+        registry
+            .custom_node(&type_tag, node)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Message for the custom node `id`, which its type deserializes itself.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RadiantCustomMessage {
+    pub id: Uuid,
+    pub message: serde_json::Value,
+}
+
+#[export_tokens]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantAddCustomNodeMessage {
+    /// Adds a node of the registered type `type_tag`, deserialized from `node`.
+    AddCustomNode {
+        id: Option<uuid::Uuid>,
+        type_tag: String,
+        node: serde_json::Value,
+    },
+}
@@ -1,14 +1,48 @@
-use crate::{BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor};
-use epaint::ClippedPrimitive;
+use crate::{
+    AutoLayoutComponent, BaseNode, RadiantLayer, RadiantLayoutItem, RadiantLayoutSizing,
+    RadiantLineNode, RadiantMaskMarker, RadiantNode, RadiantPickingRegistry, RadiantPrimitiveBatch,
+    RadiantTessellatable, ScreenDescriptor,
+};
+use epaint::{vec2, ClippedPrimitive, Rect};
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RadiantGroupNode<N: RadiantNode> {
     pub base: BaseNode,
     pub nodes: BTreeMap<Uuid, Arc<RwLock<N>>>,
+    /// Whether children are clipped to the bounds of the group.
+    #[serde(default)]
+    pub clip_content: bool,
+    /// Lays out the children in a row or a column instead of their own positions.
+    #[serde(default)]
+    pub auto_layout: Option<AutoLayoutComponent>,
+    /// Ids of the children in the order auto layouts place them in.
+    #[serde(default)]
+    order: Vec<Uuid>,
+    /// What the children were last laid out from, so that they are only laid out again
+    /// once it changes.
+    #[serde(skip)]
+    arranged: Option<RadiantArrangement>,
+    /// Children moved by the auto layout since they were last taken.
+    #[serde(skip)]
+    moved: Vec<Uuid>,
+    /// Position and size the children last followed with their constraints.
+    #[serde(skip)]
+    frame: Option<[f32; 4]>,
+    /// Batched primitives of the children, with the clip rect they were clipped to and the
+    /// revision of the child they were built from.
+    #[serde(skip)]
+    batches: HashMap<Uuid, (Rect, u64, Vec<ClippedPrimitive>)>,
+    /// Whether a child may be a mask, in which case every child is tessellated since masks
+    /// clip content outside of their own bounds.
+    #[serde(skip)]
+    has_masks: bool,
 }
 
 impl<N: RadiantNode> RadiantGroupNode<N> {
@@ -17,11 +51,34 @@ impl<N: RadiantNode> RadiantGroupNode<N> {
         Self {
             base,
             nodes: BTreeMap::new(),
+            clip_content: false,
+            auto_layout: None,
+            order: Vec::new(),
+            arranged: None,
+            moved: Vec::new(),
+            frame: None,
+            batches: HashMap::new(),
+            has_masks: false,
         }
     }
 
     pub fn add(&mut self, node: N) {
-        self.nodes.insert(node.get_id(), Arc::new(RwLock::new(node)));
+        let id = node.get_id();
+        self.has_masks |= node.base().layer().is_mask();
+        self.nodes.insert(id, Arc::new(RwLock::new(node)));
+        self.order.retain(|child| *child != id);
+        self.order.push(id);
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Option<N> {
+        self.batches.remove(&id);
+        self.order.retain(|child| *child != id);
+        let node = self.nodes.remove(&id)?;
+        let node = match Arc::try_unwrap(node) {
+            Ok(node) => node.into_inner(),
+            Err(node) => node.read().clone(),
+        };
+        Some(node)
     }
 
     pub fn node(&self, id: Uuid) -> Option<&Arc<RwLock<N>>> {
@@ -37,8 +94,389 @@ impl<N: RadiantNode> RadiantGroupNode<N> {
     }
 
     pub fn replace_node(&mut self, id: Uuid, node: N) {
+        self.batches.remove(&id);
+        self.has_masks |= node.base().layer().is_mask();
         self.nodes.insert(id, Arc::new(RwLock::new(node)));
     }
+
+    /// Ids of the children in the order auto layouts place them in. Children missing from
+    /// the stored order, as in documents saved without one, come last.
+    pub fn order(&self) -> Vec<Uuid> {
+        let mut listed = BTreeSet::new();
+        let mut order = self
+            .order
+            .iter()
+            .copied()
+            .filter(|id| self.nodes.contains_key(id) && listed.insert(*id))
+            .collect::<Vec<_>>();
+        if order.len() < self.nodes.len() {
+            order.extend(self.nodes.keys().filter(|id| !listed.contains(id)));
+        }
+        order
+    }
+
+    /// Reorders the children, ignoring the ids that are not children of the group.
+    pub fn set_order(&mut self, order: Vec<Uuid>) {
+        self.order = order;
+        self.order = self.order();
+    }
+
+    /// Ids of the children moved by the auto layout since this was last called.
+    pub fn take_moved(&mut self) -> Vec<Uuid> {
+        std::mem::take(&mut self.moved)
+    }
+
+    /// Rect the children are clipped to when clipping content.
+    pub fn clip_rect(&self) -> Option<Rect> {
+        if !self.clip_content {
+            return None;
+        }
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+        Some(Rect::from_min_size(
+            epaint::pos2(position.x, position.y),
+            epaint::vec2(scale.x, scale.y),
+        ))
+    }
+
+    /// Whether children at `position` show through the clip rect and the masks of the
+    /// group, which clip everything drawn in it.
+    pub fn reveals(&self, position: [f32; 2]) -> bool {
+        if let Some(clip_rect) = self.clip_rect() {
+            if !clip_rect.contains(position.into()) {
+                return false;
+            }
+        }
+        let masks = self
+            .nodes
+            .values()
+            .map(|node| node.read())
+            .filter(|node| node.base().visible && node.base().layer().is_mask())
+            .collect::<Vec<_>>();
+        masks.is_empty()
+            || masks
+                .iter()
+                .any(|node| node.hit_test(position, 0.0).is_some())
+    }
+
+    /// Moves and resizes the children following the auto layout, resizing the group too
+    /// when it hugs its content. Runs on every tessellation, but only lays the children
+    /// out again once the layout, the group or the bounds of a child changed.
+    fn reflow(
+        &mut self,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+    ) {
+        let Some(auto_layout) = self.auto_layout else {
+            self.arranged = None;
+            return;
+        };
+
+        let children = self
+            .order()
+            .into_iter()
+            .filter_map(|id| self.nodes.get(&id).cloned())
+            .collect::<Vec<_>>();
+        // Some nodes only know their size once their content has been laid out.
+        for node in &children {
+            let mut node = node.write();
+            if node.base().needs_tessellation {
+                node.tessellate(false, screen_descriptor, fonts_manager);
+            }
+        }
+        let arrangement = self.arrangement(auto_layout, &children);
+        if self.arranged.as_ref() == Some(&arrangement) {
+            return;
+        }
+
+        let items = arrangement
+            .children
+            .iter()
+            .map(
+                |(_, [left, top, right, bottom], sizing)| RadiantLayoutItem {
+                    size: vec2(right - left, bottom - top),
+                    sizing: *sizing,
+                },
+            )
+            .collect::<Vec<_>>();
+        let [x, y, width, height] = arrangement.frame;
+        let (size, rects) = auto_layout.arrange(vec2(x, y), vec2(width, height), &items);
+        self.base.transform.set_scale(&[size.x, size.y].into());
+
+        for ((node, rect), item) in children.iter().zip(rects).zip(items) {
+            // Only filling nodes are resized.
+            let resize = item
+                .sizing
+                .map(|sizing| sizing == RadiantLayoutSizing::Fill);
+            let mut node = node.write();
+            if fit_node(&mut *node, rect, resize, false) {
+                self.moved.push(node.get_id());
+            }
+        }
+        self.arranged = Some(self.arrangement(auto_layout, &children));
+    }
+
+    fn arrangement(
+        &self,
+        auto_layout: AutoLayoutComponent,
+        children: &[Arc<RwLock<N>>],
+    ) -> RadiantArrangement {
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+        RadiantArrangement {
+            auto_layout,
+            frame: [position.x, position.y, scale.x, scale.y],
+            children: children
+                .iter()
+                .map(|node| {
+                    let node = node.read();
+                    (
+                        node.get_id(),
+                        node.get_bounding_rect(),
+                        node.base().layout().sizing(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Tessellates the children in `visible`, or every child when it is `None`, from the
+    /// bottommost up. Children that did not change since they were last tessellated reuse
+    /// their batched primitives, so the renderer draws them from the buffers it already
+    /// uploaded. Selection primitives are painted with the ids of `picking` when given.
+    pub fn tessellate_nodes(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+        visible: Option<&BTreeSet<Uuid>>,
+        mut picking: Option<&mut RadiantPickingRegistry>,
+    ) -> Vec<ClippedPrimitive> {
+        self.apply_constraints();
+        self.reflow(screen_descriptor, fonts_manager);
+
+        let is_mask = |id: &Uuid| {
+            self.nodes
+                .get(id)
+                .is_some_and(|node| node.read().base().layer().is_mask())
+        };
+        let ids = visible
+            .filter(|_| self.auto_layout.is_none() && !self.has_masks)
+            .filter(|visible| !visible.iter().any(is_mask))
+            .map(|visible| {
+                let mut ids = self.order();
+                ids.retain(|id| visible.contains(id));
+                ids
+            });
+        let ids = match ids {
+            Some(ids) => ids,
+            None => {
+                self.has_masks = false;
+                self.order()
+            }
+        };
+
+        let clip_rect = self.clip_rect().unwrap_or(Rect::EVERYTHING);
+        let mut primitives = Vec::new();
+        let mut mask_primitives = Vec::new();
+        for id in ids {
+            let Some(node) = self.nodes.get(&id).cloned() else {
+                continue;
+            };
+            let mut node = node.write();
+            // Hidden nodes are left out entirely, locked ones only from picking.
+            if !node.base().visible || (selection && node.base().locked) {
+                continue;
+            }
+            if node.base().layer().is_mask() || selection {
+                self.has_masks |= node.base().layer().is_mask();
+                let mut node_primitives =
+                    node.tessellate(selection, screen_descriptor, fonts_manager);
+                for primitive in &mut node_primitives {
+                    primitive.clip_rect = primitive.clip_rect.intersect(clip_rect);
+                }
+                if node.base().layer().is_mask() {
+                    mask_primitives.extend(node_primitives);
+                } else {
+                    if let Some(picking) = picking.as_deref_mut() {
+                        picking.paint(id, &mut node_primitives);
+                    }
+                    primitives.extend(node_primitives);
+                }
+                continue;
+            }
+
+            let revision = node.base().revision;
+            let cached = self.batches.get(&id).filter(|(rect, cached_revision, _)| {
+                *rect == clip_rect
+                    && *cached_revision == revision
+                    && !node.base().needs_tessellation
+            });
+            let node_primitives = match cached {
+                Some((_, _, node_primitives)) => node_primitives.clone(),
+                None => {
+                    let mut node_primitives =
+                        node.tessellate(false, screen_descriptor, fonts_manager);
+                    for primitive in &mut node_primitives {
+                        primitive.clip_rect = primitive.clip_rect.intersect(clip_rect);
+                    }
+                    let node_primitives = RadiantPrimitiveBatch::batch(node_primitives);
+                    self.batches
+                        .insert(id, (clip_rect, revision, node_primitives.clone()));
+                    node_primitives
+                }
+            };
+            let [left, top, right, bottom] = node.get_visual_rect();
+            let bounds = Rect::from_min_max(epaint::pos2(left, top), epaint::pos2(right, bottom))
+                .intersect(clip_rect);
+            primitives.append(&mut node.base().layer().wrap(node_primitives, bounds));
+        }
+        if !mask_primitives.is_empty() {
+            primitives = RadiantMaskMarker::clip(mask_primitives, primitives);
+        }
+        if selection {
+            primitives
+        } else {
+            self.base.layer().wrap(primitives, Rect::EVERYTHING)
+        }
+    }
+
+    /// Moves the group to `position` and resizes it to `size`. Children follow their
+    /// constraints, unless the group has an auto layout which reflows them instead.
+    pub fn resize(&mut self, position: [f32; 2], size: [f32; 2]) {
+        if self.frame.is_none() {
+            self.frame = Some(self.current_frame());
+        }
+        self.base.transform.set_position(&position.into());
+        self.base.transform.set_scale(&size.into());
+        self.apply_constraints();
+    }
+
+    fn current_frame(&self) -> [f32; 4] {
+        let position = self.base.transform.position();
+        let scale = self.base.transform.scale();
+        [position.x, position.y, scale.x, scale.y]
+    }
+
+    /// Moves and resizes the children following their constraints when the group was
+    /// moved or resized since they last followed it, whatever changed its transform.
+    fn apply_constraints(&mut self) {
+        let frame = self.current_frame();
+        let Some(old_frame) = self.frame.replace(frame) else {
+            return;
+        };
+        if old_frame == frame || self.auto_layout.is_some() {
+            return;
+        }
+
+        let [old_x, old_y, old_width, old_height] = old_frame;
+        let [new_x, new_y, new_width, new_height] = frame;
+        for node in self.nodes.values() {
+            let mut node = node.write();
+            let [left, top, right, bottom] = node.get_bounding_rect();
+            if right <= left && bottom <= top {
+                // Not measured yet.
+                continue;
+            }
+            let [horizontal, vertical] = node.base().layout().constraints();
+            let [x, width] =
+                horizontal.apply([old_x, old_width], [new_x, new_width], [left, right - left]);
+            let [y, height] = vertical.apply(
+                [old_y, old_height],
+                [new_y, new_height],
+                [top, bottom - top],
+            );
+            fit_node(
+                &mut *node,
+                Rect::from_min_size(epaint::pos2(x, y), vec2(width, height)),
+                [true; 2],
+                true,
+            );
+        }
+    }
+}
+
+/// Layout a group arranged its children from: its auto layout, its position and size, and
+/// the id, bounding rect and sizing of every child in order.
+#[derive(Debug, Clone, PartialEq)]
+struct RadiantArrangement {
+    auto_layout: AutoLayoutComponent,
+    frame: [f32; 4],
+    children: Vec<(Uuid, [f32; 4], [RadiantLayoutSizing; 2])>,
+}
+
+/// Moves `node` so that its bounding rect starts at the corner of `rect`, and resizes it on
+/// the `resize` axes by the difference between both rects. Observers are notified when
+/// `notify` is set. Returns whether the node changed.
+fn fit_node<N: RadiantNode>(node: &mut N, rect: Rect, resize: [bool; 2], notify: bool) -> bool {
+    let [left, top, right, bottom] = node.get_bounding_rect();
+    if let Some(line) = node.downcast_mut::<RadiantLineNode>() {
+        return fit_endpoints(line, [left, top, right, bottom], rect, resize, notify);
+    }
+    let mut changed = false;
+
+    let offset = vec2(rect.min.x - left, rect.min.y - top);
+    if offset.length_sq() > f32::EPSILON {
+        node.transform_mut()
+            .transform_xy(&[offset.x, offset.y].into());
+        changed = true;
+    }
+
+    let mut growth = vec2(
+        rect.width() - (right - left),
+        rect.height() - (bottom - top),
+    );
+    for axis in 0..2 {
+        if !resize[axis] {
+            growth[axis] = 0.0;
+        }
+    }
+    if growth.length_sq() > f32::EPSILON {
+        let scale = node.transform().scale();
+        node.transform_mut()
+            .set_scale(&[scale.x + growth.x, scale.y + growth.y].into());
+        changed = true;
+    }
+
+    if changed {
+        node.set_needs_tessellation(notify);
+    }
+    changed
+}
+
+/// Fits lines, which are resized by moving their endpoints relative to their bounding rect
+/// rather than through their scale.
+fn fit_endpoints(
+    node: &mut RadiantLineNode,
+    [left, top, right, bottom]: [f32; 4],
+    rect: Rect,
+    resize: [bool; 2],
+    notify: bool,
+) -> bool {
+    let old_min = [left, top];
+    let old_size = [right - left, bottom - top];
+    let new_min = [rect.min.x, rect.min.y];
+    let new_size = [rect.width(), rect.height()];
+    let endpoints = node.get_endpoints();
+    let [start, end] = endpoints.map(|point| {
+        [0, 1].map(|axis| {
+            let ratio = if resize[axis] && old_size[axis] > f32::EPSILON {
+                new_size[axis] / old_size[axis]
+            } else {
+                1.0
+            };
+            new_min[axis] + (point[axis] - old_min[axis]) * ratio
+        })
+    });
+    let moved = [start, end]
+        .iter()
+        .zip(endpoints)
+        .any(|(new, old)| (new[0] - old[0]).abs() + (new[1] - old[1]).abs() > f32::EPSILON);
+    if moved {
+        node.set_endpoints(start, end);
+        node.set_needs_tessellation(notify);
+    }
+    moved
 }
 
 impl<N: RadiantNode> RadiantTessellatable for RadiantGroupNode<N> {
@@ -54,7 +492,12 @@ impl<N: RadiantNode> RadiantTessellatable for RadiantGroupNode<N> {
         }
     }
 
-    fn set_needs_tessellation(&mut self, _notify: bool) {}
+    fn set_needs_tessellation(&mut self, notify: bool) {
+        self.base.set_needs_tessellation();
+        if notify {
+            self.base.notify(serde_json::to_string(self).unwrap());
+        }
+    }
 
     fn tessellate(
         &mut self,
@@ -62,11 +505,7 @@ impl<N: RadiantNode> RadiantTessellatable for RadiantGroupNode<N> {
         screen_descriptor: &ScreenDescriptor,
         fonts_manager: &epaint::text::Fonts,
     ) -> Vec<ClippedPrimitive> {
-        let mut primitives = Vec::new();
-        for node in &mut self.nodes.values_mut() {
-            primitives.append(&mut node.write().tessellate(selection, screen_descriptor, fonts_manager));
-        }
-        primitives
+        self.tessellate_nodes(selection, screen_descriptor, fonts_manager, None, None)
     }
 }
 
@@ -79,6 +518,35 @@ impl<N: RadiantNode> RadiantNode for RadiantGroupNode<N> {
         &mut self.base
     }
 
+    fn node_type(&self) -> &str {
+        "group"
+    }
+
+    fn layer(&self) -> RadiantLayer {
+        let children = self
+            .order()
+            .iter()
+            .rev()
+            .filter_map(|id| self.nodes.get(id))
+            .map(|node| node.read().layer())
+            .collect();
+        RadiantLayer::new(&self.base, self.node_type(), children)
+    }
+
+    /// Children are tested from the topmost down, so the group itself is never hit.
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        if !self.reveals(position) {
+            return None;
+        }
+        self.order().iter().rev().find_map(|id| {
+            let node = self.nodes.get(id)?.read();
+            if node.base().layer().is_mask() || !node.base().is_pickable() {
+                return None;
+            }
+            node.hit_test(position, tolerance)
+        })
+    }
+
     fn handle_key_down(&mut self, key: crate::KeyCode) -> bool {
         for node in &mut self.nodes.values_mut() {
             if node.write().handle_key_down(key.clone()) {
@@ -88,3 +556,163 @@ impl<N: RadiantNode> RadiantNode for RadiantGroupNode<N> {
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        RadiantConstraint, RadiantGroupNode, RadiantLineNode, RadiantNode, RadiantTessellatable,
+        ScreenDescriptor,
+    };
+    use epaint::text::{FontDefinitions, Fonts};
+    use uuid::Uuid;
+
+    fn line(
+        start: [f32; 2],
+        end: [f32; 2],
+        constraints: [RadiantConstraint; 2],
+    ) -> RadiantLineNode {
+        let mut node = RadiantLineNode::new(Uuid::new_v4(), start, end);
+        node.base_mut().stroke_mut().set_width(0.0);
+        node.base_mut().layout_mut().set_constraints(constraints);
+        node.set_needs_tessellation(false);
+        node
+    }
+
+    fn endpoints(group: &RadiantGroupNode<RadiantLineNode>, id: Uuid) -> [[f32; 2]; 2] {
+        group.get_node(id).unwrap().get_endpoints()
+    }
+
+    #[test]
+    fn lines_follow_constraints() {
+        let mut group = RadiantGroupNode::new(Uuid::new_v4());
+        group.resize([0.0, 0.0], [100.0, 100.0]);
+
+        let scaled = line([10.0, 20.0], [30.0, 60.0], [RadiantConstraint::Scale; 2]);
+        let stretched = line(
+            [90.0, 10.0],
+            [10.0, 30.0],
+            [RadiantConstraint::StartAndEnd, RadiantConstraint::Start],
+        );
+        let ended = line([80.0, 90.0], [90.0, 80.0], [RadiantConstraint::End; 2]);
+        let ids = [scaled.get_id(), stretched.get_id(), ended.get_id()];
+        group.add(scaled);
+        group.add(stretched);
+        group.add(ended);
+
+        group.resize([10.0, 0.0], [200.0, 50.0]);
+        assert_eq!(endpoints(&group, ids[0]), [[30.0, 10.0], [70.0, 30.0]]);
+        // The start is the right end of the line, and keeps its distance to the right.
+        assert_eq!(endpoints(&group, ids[1]), [[200.0, 10.0], [20.0, 30.0]]);
+        assert_eq!(endpoints(&group, ids[2]), [[190.0, 40.0], [200.0, 30.0]]);
+    }
+
+    #[test]
+    fn constraints_follow_transform_changes() {
+        let mut group = RadiantGroupNode::new(Uuid::new_v4());
+        group.resize([0.0, 0.0], [100.0, 100.0]);
+        let node = line([10.0, 10.0], [20.0, 20.0], [RadiantConstraint::End; 2]);
+        let id = node.get_id();
+        group.add(node);
+
+        group.base.transform.set_scale(&[150.0, 100.0].into());
+        group.apply_constraints();
+        assert_eq!(endpoints(&group, id), [[60.0, 10.0], [70.0, 20.0]]);
+    }
+
+    #[test]
+    fn masks_limit_hit_testing() {
+        let mut group = RadiantGroupNode::new(Uuid::new_v4());
+        let mut mask = line([0.0, 50.0], [100.0, 50.0], Default::default());
+        mask.base_mut().layer_mut().set_mask(true);
+        let content = line([50.0, 0.0], [50.0, 100.0], Default::default());
+        let id = content.get_id();
+        group.add(mask);
+        group.add(content);
+
+        assert_eq!(group.hit_test([50.0, 50.0], 0.0), Some(id));
+        assert_eq!(group.hit_test([50.0, 10.0], 0.0), None);
+        assert_eq!(group.hit_test([10.0, 50.0], 0.0), None);
+    }
+
+    #[test]
+    fn layers_and_hits_follow_the_order() {
+        let mut group = RadiantGroupNode::new(Uuid::new_v4());
+        let nodes = [
+            line([0.0, 50.0], [100.0, 50.0], Default::default()),
+            line([50.0, 0.0], [50.0, 100.0], Default::default()),
+            line([0.0, 0.0], [100.0, 100.0], Default::default()),
+        ];
+        let mut ids = nodes.iter().map(|node| node.get_id()).collect::<Vec<_>>();
+        for node in nodes {
+            group.add(node);
+        }
+
+        ids.sort();
+        ids.swap(0, 2);
+        group.set_order(ids.clone());
+        let layers = group
+            .layer()
+            .children
+            .iter()
+            .map(|layer| layer.id)
+            .collect::<Vec<_>>();
+        assert_eq!(layers, ids.iter().rev().copied().collect::<Vec<_>>());
+        assert_eq!(group.hit_test([50.0, 50.0], 0.0), Some(ids[2]));
+
+        group.set_order(vec![ids[2], ids[0], ids[1]]);
+        assert_eq!(group.hit_test([50.0, 50.0], 0.0), Some(ids[1]));
+    }
+
+    #[test]
+    fn clip_rect_limits_hit_testing_when_clipping_content() {
+        let mut group = RadiantGroupNode::new(Uuid::new_v4());
+        group.resize([0.0, 0.0], [100.0, 100.0]);
+        let content = line([50.0, 50.0], [150.0, 50.0], Default::default());
+        let id = content.get_id();
+        group.add(content);
+
+        assert_eq!(group.hit_test([120.0, 50.0], 0.0), Some(id));
+        group.clip_content = true;
+        assert_eq!(group.hit_test([120.0, 50.0], 0.0), None);
+        assert_eq!(group.hit_test([80.0, 50.0], 0.0), Some(id));
+    }
+
+    #[test]
+    fn unchanged_nodes_are_not_tessellated_again() {
+        let screen_descriptor = ScreenDescriptor {
+            size_in_pixels: [100, 100],
+            pixels_per_point: 1.0,
+            offset: [0.0, 0.0],
+            zoom: 1.0,
+        };
+        let fonts = Fonts::new(1.0, 1024, FontDefinitions::default());
+        let mut group = RadiantGroupNode::new(Uuid::new_v4());
+        let mut unchanged = RadiantLineNode::new(Uuid::new_v4(), [0.0, 0.0], [10.0, 10.0]);
+        unchanged.base_mut().stroke_mut().set_width(2.0);
+        let mut changed = unchanged.clone();
+        changed.set_id(Uuid::new_v4());
+        let ids = [unchanged.get_id(), changed.get_id()];
+        group.add(unchanged);
+        group.add(changed);
+        let primitives = group.tessellate_nodes(false, &screen_descriptor, &fonts, None, None);
+        assert!(!primitives.is_empty());
+
+        // Primitives cleared from the node stay cleared unless it is tessellated again.
+        for id in ids {
+            group
+                .get_node_mut(id)
+                .unwrap()
+                .base_mut()
+                .primitives
+                .clear();
+        }
+        group
+            .get_node_mut(ids[1])
+            .unwrap()
+            .set_needs_tessellation(false);
+        let again = group.tessellate_nodes(false, &screen_descriptor, &fonts, None, None);
+        assert_eq!(again.len(), primitives.len());
+        assert!(group.get_node(ids[0]).unwrap().base().primitives.is_empty());
+        assert!(!group.get_node(ids[1]).unwrap().base().primitives.is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use crate::{
+    BaseNode, RadiantNode, RadiantPaint, RadiantTessellatable, ScreenDescriptor, TextComponent,
+    Vec3,
+};
+use epaint::{ClippedPrimitive, Color32, Rect};
+use macro_magic::export_tokens;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// A reusable component made of nodes of the document. The nodes themselves act as the
+/// master, and every instance of the symbol renders a copy of them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RadiantSymbol {
+    pub id: Uuid,
+    pub name: String,
+    pub node_ids: Vec<Uuid>,
+}
+
+/// Values replacing the ones of a master node in a single instance.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RadiantInstanceOverride {
+    pub text: Option<String>,
+    pub fill_color: Option<Color32>,
+    pub stroke_color: Option<Color32>,
+    pub visible: Option<bool>,
+}
+
+#[export_tokens]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantAddInstanceMessage {
+    AddInstance {
+        id: Option<uuid::Uuid>,
+        symbol_id: uuid::Uuid,
+        position: [f32; 2],
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantInstanceMessage {
+    SetOverride {
+        id: Uuid,
+        node_id: Uuid,
+        value: RadiantInstanceOverride,
+    },
+    ResetOverrides {
+        id: Uuid,
+    },
+}
+
+impl RadiantInstanceMessage {
+    pub fn id(&self) -> Uuid {
+        match self {
+            RadiantInstanceMessage::SetOverride { id, .. }
+            | RadiantInstanceMessage::ResetOverrides { id } => *id,
+        }
+    }
+}
+
+/// An instance of a symbol. The master node positioned first is placed at the position of
+/// the instance, and the other nodes keep their offset to it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(bound(deserialize = ""))]
+pub struct RadiantInstanceNode<N: RadiantNode> {
+    pub base: BaseNode,
+    pub symbol: RadiantSymbol,
+    /// Overrides keyed by the id of the master node they apply to.
+    #[serde(default)]
+    pub overrides: BTreeMap<Uuid, RadiantInstanceOverride>,
+    /// Copies of the master nodes, rebuilt by the document whenever the master or the
+    /// instance changes.
+    #[serde(skip)]
+    pub nodes: Vec<N>,
+}
+
+impl<N: RadiantNode> RadiantInstanceNode<N> {
+    pub fn new(id: Uuid, symbol: RadiantSymbol, position: [f32; 2]) -> Self {
+        let base = BaseNode::new(id, position.into(), Vec3::zero());
+        Self {
+            base,
+            symbol,
+            overrides: BTreeMap::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn handle_message(&mut self, message: RadiantInstanceMessage) {
+        match message {
+            RadiantInstanceMessage::SetOverride { node_id, value, .. } => {
+                self.overrides.insert(node_id, value);
+            }
+            RadiantInstanceMessage::ResetOverrides { .. } => {
+                self.overrides.clear();
+            }
+        }
+        self.set_needs_tessellation(true);
+    }
+
+    /// Replaces the content of the instance with copies of `masters`, moved to the instance
+    /// position and with the overrides applied.
+    pub fn sync(&mut self, masters: &[N]) {
+        let origin = masters.iter().fold([f32::MAX; 2], |origin, master| {
+            let position = master.transform().position();
+            [origin[0].min(position.x), origin[1].min(position.y)]
+        });
+        let position = self.base.transform.position();
+        let offset = [position.x - origin[0], position.y - origin[1]];
+        self.nodes = masters
+            .iter()
+            .map(|master| {
+                let mut node = master.clone();
+                node.transform_mut().transform_xy(&offset.into());
+                if let Some(value) = self.overrides.get(&master.get_id()) {
+                    if let Some(text) = &value.text {
+                        if let Some(component) = node.get_component_mut::<TextComponent>() {
+                            component.set_text(text.clone());
+                        }
+                    }
+                    if let Some(color) = value.fill_color {
+                        node.base_mut().set_fill_paint(RadiantPaint::Solid(color));
+                    }
+                    if let Some(color) = value.stroke_color {
+                        node.color_mut().set_stroke_color(color);
+                    }
+                }
+                node.set_needs_tessellation(false);
+                node
+            })
+            .collect();
+        self.base.set_needs_tessellation();
+    }
+
+    fn is_visible(&self, node: &N) -> bool {
+        self.overrides
+            .get(&node.get_id())
+            .and_then(|value| value.visible)
+            .unwrap_or(true)
+    }
+}
+
+impl<N: RadiantNode> RadiantTessellatable for RadiantInstanceNode<N> {
+    fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
+        for node in &mut self.nodes {
+            node.attach(screen_descriptor);
+        }
+    }
+
+    fn detach(&mut self) {
+        for node in &mut self.nodes {
+            node.detach();
+        }
+        self.base.primitives.clear();
+        self.base.selection_primitives.clear();
+    }
+
+    fn set_needs_tessellation(&mut self, notify: bool) {
+        self.base.set_needs_tessellation();
+        if notify {
+            self.base.notify(serde_json::to_string(self).unwrap());
+        }
+    }
+
+    fn tessellate(
+        &mut self,
+        selection: bool,
+        screen_descriptor: &ScreenDescriptor,
+        fonts_manager: &epaint::text::Fonts,
+    ) -> Vec<ClippedPrimitive> {
+        if self.base.needs_tessellation {
+            self.base.needs_tessellation = false;
+            self.base.primitives.clear();
+            self.base.selection_primitives.clear();
+
+            let mut bounds = Rect::NOTHING;
+            let mut nodes = std::mem::take(&mut self.nodes);
+            for node in &mut nodes {
+                if !self.is_visible(node) {
+                    continue;
+                }
+                let primitives = node.tessellate(false, screen_descriptor, fonts_manager);
+                let [left, top, right, bottom] = node.get_visual_rect();
+                let rect = Rect::from_min_max(epaint::pos2(left, top), epaint::pos2(right, bottom));
+                self.base
+                    .primitives
+                    .append(&mut node.base().layer().wrap(primitives, rect));
+                self.base.selection_primitives.extend(node.tessellate(
+                    true,
+                    screen_descriptor,
+                    fonts_manager,
+                ));
+                let [left, top, right, bottom] = node.get_bounding_rect();
+                bounds = bounds.union(Rect::from_min_max(
+                    epaint::pos2(left, top),
+                    epaint::pos2(right, bottom),
+                ));
+            }
+            self.nodes = nodes;
+
+            self.base.bounding_rect = if bounds.is_positive() {
+                [bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y]
+            } else {
+                [0.0; 4]
+            };
+        }
+        if selection {
+            self.base.selection_primitives.clone()
+        } else {
+            self.base.primitives.clone()
+        }
+    }
+}
+
+impl<N: RadiantNode> RadiantNode for RadiantInstanceNode<N> {
+    fn base(&self) -> &BaseNode {
+        &self.base
+    }
+
+    fn node_type(&self) -> &str {
+        "instance"
+    }
+
+    fn base_mut(&mut self) -> &mut BaseNode {
+        &mut self.base
+    }
+
+    /// Visual rects of the nodes, so that their shadows and blurs are included, grown by
+    /// the effects of the instance.
+    fn get_visual_rect(&self) -> [f32; 4] {
+        let bounds = self
+            .nodes
+            .iter()
+            .filter(|node| self.is_visible(node))
+            .fold(Rect::NOTHING, |bounds, node| {
+                let [left, top, right, bottom] = node.get_visual_rect();
+                bounds.union(Rect::from_min_max(
+                    epaint::pos2(left, top),
+                    epaint::pos2(right, bottom),
+                ))
+            });
+        if !bounds.is_positive() {
+            return [0.0; 4];
+        }
+        let rect = self.base.layer().expand_bounds(bounds);
+        [rect.min.x, rect.min.y, rect.max.x, rect.max.y]
+    }
+
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        self.nodes
+            .iter()
+            .rev()
+            .filter(|node| self.is_visible(node))
+            .any(|node| node.hit_test(position, tolerance).is_some())
+            .then_some(self.base.id)
+    }
+
+    fn replace(&mut self, node: &str) {
+        let node: Self = serde_json::from_str(node).unwrap();
+        let observers = std::mem::take(&mut self.base.observers);
+        self.base = node.base;
+        self.base.observers = observers;
+        self.symbol = node.symbol;
+        self.overrides = node.overrides;
+        self.set_needs_tessellation(false);
+    }
+}
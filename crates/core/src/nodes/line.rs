@@ -1,100 +1,231 @@
 use crate::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3,
+    distance_to_segment, BaseNode, RadiantNode, RadiantShapes, RadiantTessellatable,
+    ScreenDescriptor, StrokeComponent, Vec3, PICKING_COLOR,
 };
-use epaint::{ClippedPrimitive, ClippedShape, Rect, TessellationOptions};
+use epaint::{ClippedPrimitive, Color32, Pos2, Rect, Shape, Stroke};
+use radiantkit_macros::RadiantTessellatable;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+const SELECTION_STROKE_WIDTH: f32 = 8.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum RadiantArrowHead {
+    #[default]
+    None,
+    Open,
+    Triangle,
+    Circle,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantLineMessage {
+    SetStrokeWidth {
+        id: Uuid,
+        stroke_width: f32,
+    },
+    /// Sets the stroke to a dash and gap length, or to a solid line when `None`.
+    SetDash {
+        id: Uuid,
+        dash: Option<[f32; 2]>,
+    },
+    SetArrowHeads {
+        id: Uuid,
+        start: RadiantArrowHead,
+        end: RadiantArrowHead,
+    },
+}
+
+impl RadiantLineMessage {
+    pub fn id(&self) -> Uuid {
+        match self {
+            RadiantLineMessage::SetStrokeWidth { id, .. } => *id,
+            RadiantLineMessage::SetDash { id, .. } => *id,
+            RadiantLineMessage::SetArrowHeads { id, .. } => *id,
+        }
+    }
+}
+
+/// A line segment between `start` and `end`, both relative to the node position, so that
+/// moving the node moves the line.
+#[derive(Serialize, Deserialize, Debug, Clone, RadiantTessellatable)]
+#[serde(from = "RadiantLineNodeData")]
+#[radiant_node(build_shapes = build_shapes)]
 pub struct RadiantLineNode {
     pub base: BaseNode,
     pub start: Vec3,
     pub end: Vec3,
+    #[serde(default)]
+    pub start_arrow: RadiantArrowHead,
+    #[serde(default)]
+    pub end_arrow: RadiantArrowHead,
+}
+
+/// Serialized form of a line, which may hold the stroke of older documents.
+#[derive(Deserialize)]
+struct RadiantLineNodeData {
+    base: BaseNode,
+    start: Vec3,
+    end: Vec3,
+    #[serde(default)]
+    start_arrow: RadiantArrowHead,
+    #[serde(default)]
+    end_arrow: RadiantArrowHead,
+    /// Stroke width of lines saved before they were stroked with the stroke component.
+    #[serde(default)]
+    stroke_width: Option<f32>,
+    /// Dash and gap length of lines saved before they were stroked with the stroke
+    /// component.
+    #[serde(default)]
+    dash: Option<[f32; 2]>,
+}
+
+impl From<RadiantLineNodeData> for RadiantLineNode {
+    fn from(data: RadiantLineNodeData) -> Self {
+        let mut node = Self {
+            base: data.base,
+            start: data.start,
+            end: data.end,
+            start_arrow: data.start_arrow,
+            end_arrow: data.end_arrow,
+        };
+        if let Some(stroke_width) = data.stroke_width {
+            node.base.stroke_mut().set_width(stroke_width);
+        }
+        if let Some(dash) = data.dash {
+            node.base.stroke_mut().set_dash_pattern(dash.to_vec());
+        }
+        node
+    }
+}
+
+impl Default for RadiantLineNode {
+    fn default() -> Self {
+        Self::new(Uuid::nil(), [0.0, 0.0], [0.0, 0.0])
+    }
 }
 
 impl RadiantLineNode {
     pub fn new(id: Uuid, start: [f32; 2], end: [f32; 2]) -> Self {
-        let base = BaseNode::new(id, start.into(), Vec3::default());
+        let mut base = BaseNode::new(id, Vec3::zero(), Vec3::default());
+        base.color.set_stroke_color(Color32::BLUE);
         Self {
             base,
             start: start.into(),
             end: end.into(),
+            start_arrow: RadiantArrowHead::None,
+            end_arrow: RadiantArrowHead::None,
         }
     }
 
-    fn tessellate(&mut self, screen_descriptor: &ScreenDescriptor) {
-        if !self.base.needs_tessellation {
-            return;
-        }
-        self.base.needs_tessellation = false;
-
-        let pixels_per_point = screen_descriptor.pixels_per_point;
-
-        let points = [self.start.into(), self.end.into()];
-
-        let color = epaint::Color32::BLUE;
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::LineSegment {
-                points,
-                stroke: epaint::Stroke::new(1.0, color),
-            },
-        )];
-        self.base.primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
-
-        let color = get_color_for_node(self.base.id);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::LineSegment {
-                points,
-                stroke: epaint::Stroke::new(8.0, color),
-            },
-        )];
-        self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
+    /// Absolute start and end points, which the line is edited through instead of a
+    /// bounding box.
+    pub fn get_endpoints(&self) -> [[f32; 2]; 2] {
+        let [start, end] = self.points();
+        [[start.x, start.y], [end.x, end.y]]
+    }
+
+    pub fn set_endpoints(&mut self, start: [f32; 2], end: [f32; 2]) {
+        let position = self.base.transform.position();
+        self.start = [start[0] - position.x, start[1] - position.y].into();
+        self.end = [end[0] - position.x, end[1] - position.y].into();
     }
-}
 
-impl RadiantTessellatable for RadiantLineNode {
-    fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
-        self.tessellate(screen_descriptor);
+    fn points(&self) -> [Pos2; 2] {
+        let position = self.base.transform.position();
+        [
+            Vec3::new_with_added(&position, &self.start).into(),
+            Vec3::new_with_added(&position, &self.end).into(),
+        ]
     }
 
-    fn detach(&mut self) {
-        self.base.primitives.clear();
-        self.base.selection_primitives.clear();
+    fn arrow_length(&self) -> f32 {
+        self.base.stroke().width() * 4.0 + 6.0
     }
 
-    fn set_needs_tessellation(&mut self, notify: bool) {
-        self.base.set_needs_tessellation();
-        if notify {
-            self.base.notify(serde_json::to_string(self).unwrap());
+    fn arrow_shapes(
+        &self,
+        tip: Pos2,
+        from: Pos2,
+        arrow: RadiantArrowHead,
+        color: Color32,
+    ) -> Vec<Shape> {
+        let vector = tip - from;
+        if vector.length() < f32::EPSILON {
+            return Vec::new();
+        }
+        let direction = vector.normalized();
+        let normal = direction.rot90();
+        let length = self.arrow_length();
+        let base = tip - direction * length;
+        let left = base + normal * length * 0.5;
+        let right = base - normal * length * 0.5;
+        let stroke = Stroke::new(self.base.stroke().width(), color);
+
+        match arrow {
+            RadiantArrowHead::None => Vec::new(),
+            RadiantArrowHead::Open => vec![
+                Shape::line_segment([left, tip], stroke),
+                Shape::line_segment([right, tip], stroke),
+            ],
+            RadiantArrowHead::Triangle => {
+                vec![Shape::convex_polygon(
+                    vec![tip, right, left],
+                    color,
+                    Stroke::NONE,
+                )]
+            }
+            RadiantArrowHead::Circle => vec![Shape::circle_filled(tip, length * 0.5, color)],
         }
     }
 
-    fn tessellate(
-        &mut self,
-        selection: bool,
-        screen_descriptor: &ScreenDescriptor,
-        _fonts_manager: &epaint::text::Fonts,
-    ) -> Vec<ClippedPrimitive> {
-        self.tessellate(screen_descriptor);
-        if selection {
-            self.base.selection_primitives.clone()
-        } else {
-            self.base.primitives.clone()
+    /// Moves an end of the segment back so it does not poke through a filled arrow head.
+    fn inset_point(&self, tip: Pos2, from: Pos2, arrow: RadiantArrowHead) -> Pos2 {
+        let vector = tip - from;
+        let length = vector.length();
+        let inset = match arrow {
+            RadiantArrowHead::Triangle => self.arrow_length(),
+            RadiantArrowHead::Circle => self.arrow_length() * 0.5,
+            RadiantArrowHead::None | RadiantArrowHead::Open => 0.0,
+        };
+        if length <= inset {
+            return tip;
+        }
+        tip - vector / length * inset
+    }
+
+    fn shapes(&self, color: Color32, stroke: &StrokeComponent) -> Vec<Shape> {
+        let [start, end] = self.points();
+        let segment = [
+            self.inset_point(start, end, self.start_arrow),
+            self.inset_point(end, start, self.end_arrow),
+        ];
+
+        let mut shapes = stroke.shapes(&segment, false, color);
+        shapes.append(&mut self.arrow_shapes(start, end, self.start_arrow, color));
+        shapes.append(&mut self.arrow_shapes(end, start, self.end_arrow, color));
+        shapes
+    }
+
+    fn build_shapes(&self) -> RadiantShapes {
+        let [start, end] = self.points();
+        let arrow_extent = match (self.start_arrow, self.end_arrow) {
+            (RadiantArrowHead::None, RadiantArrowHead::None) => 0.0,
+            _ => self.arrow_length(),
+        };
+        let extent = self.base.stroke().width().max(arrow_extent);
+        let bounding_rect = Rect::from_two_pos(start, end).expand(extent * 0.5);
+
+        let mut stroke = self.base.stroke().clone();
+        stroke.set_width(stroke.width().max(SELECTION_STROKE_WIDTH));
+        stroke.set_dash_pattern(Vec::new());
+
+        RadiantShapes {
+            bounding_rect,
+            fill: Vec::new(),
+            shapes: self.shapes(self.base.color.stroke_color(), self.base.stroke()),
+            selection: self.shapes(PICKING_COLOR, &stroke),
         }
     }
 }
@@ -104,7 +235,109 @@ impl RadiantNode for RadiantLineNode {
         &self.base
     }
 
+    fn node_type(&self) -> &str {
+        "line"
+    }
+
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
+
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        if self.base.color.stroke_color().a() == 0 {
+            return None;
+        }
+        let [start, end] = self.points();
+        let width = self.base.stroke().width();
+        (distance_to_segment(position.into(), start, end) <= width / 2.0 + tolerance)
+            .then_some(self.base.id)
+    }
+
+    fn replace(&mut self, node: &str) {
+        self.replace_keeping_observers(node);
+    }
+}
+
+impl RadiantLineNode {
+    pub fn handle_message(&mut self, message: RadiantLineMessage) -> bool {
+        match message {
+            RadiantLineMessage::SetStrokeWidth { stroke_width, .. } => {
+                self.base.stroke_mut().set_width(stroke_width);
+            }
+            RadiantLineMessage::SetDash { dash, .. } => {
+                self.base
+                    .stroke_mut()
+                    .set_dash_pattern(dash.map(|dash| dash.to_vec()).unwrap_or_default());
+            }
+            RadiantLineMessage::SetArrowHeads { start, end, .. } => {
+                self.start_arrow = start;
+                self.end_arrow = end;
+            }
+        }
+        self.set_needs_tessellation(true);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{RadiantLineNode, RadiantNode, RadiantTessellatable, TransformComponent, Vec3};
+    use uuid::Uuid;
+
+    #[test]
+    fn endpoints_follow_position() {
+        let mut node = RadiantLineNode::new(Uuid::nil(), [10.0, 20.0], [30.0, 40.0]);
+        assert_eq!(node.get_endpoints(), [[10.0, 20.0], [30.0, 40.0]]);
+
+        node.get_component_mut::<TransformComponent>()
+            .unwrap()
+            .set_position(&Vec3::new(5.0, 5.0, 0.0));
+        assert_eq!(node.get_endpoints(), [[15.0, 25.0], [35.0, 45.0]]);
+
+        node.set_endpoints([0.0, 0.0], [10.0, 0.0]);
+        let start: [f32; 2] = node.start.into();
+        assert_eq!(start, [-5.0, -5.0]);
+        assert_eq!(node.get_endpoints(), [[0.0, 0.0], [10.0, 0.0]]);
+    }
+
+    #[test]
+    fn endpoints_stay_relative_on_load() {
+        let mut node = RadiantLineNode::new(Uuid::nil(), [0.0, 0.0], [40.0, 40.0]);
+        node.get_component_mut::<TransformComponent>()
+            .unwrap()
+            .set_position(&Vec3::new(10.0, 20.0, 0.0));
+
+        let node: RadiantLineNode =
+            serde_json::from_value(serde_json::to_value(&node).unwrap()).unwrap();
+        assert_eq!(node.get_endpoints(), [[10.0, 20.0], [50.0, 60.0]]);
+        let end: [f32; 2] = node.end.into();
+        assert_eq!(end, [40.0, 40.0]);
+    }
+
+    #[test]
+    fn stroke_width_and_dash_are_converted_on_load() {
+        let node = RadiantLineNode::new(Uuid::nil(), [0.0, 0.0], [10.0, 0.0]);
+        let mut data = serde_json::to_value(&node).unwrap();
+        data["stroke_width"] = 3.0.into();
+        data["dash"] = serde_json::json!([4.0, 2.0]);
+
+        let node: RadiantLineNode = serde_json::from_value(data).unwrap();
+        assert_eq!(node.stroke().width(), 3.0);
+        assert_eq!(node.stroke().dash_pattern(), &[4.0, 2.0]);
+    }
+
+    #[test]
+    fn replace_keeps_the_node_when_the_data_is_invalid() {
+        let mut node = RadiantLineNode::new(Uuid::nil(), [0.0, 0.0], [10.0, 0.0]);
+        let mut moved = node.clone();
+        moved.set_endpoints([5.0, 5.0], [20.0, 5.0]);
+        moved.set_needs_tessellation(false);
+
+        node.replace("{");
+        assert_eq!(node.get_endpoints(), [[0.0, 0.0], [10.0, 0.0]]);
+
+        node.replace(&serde_json::to_string(&moved).unwrap());
+        assert_eq!(node.get_endpoints(), [[5.0, 5.0], [20.0, 5.0]]);
+        assert_eq!(node.get_bounding_rect(), moved.get_bounding_rect());
+    }
 }
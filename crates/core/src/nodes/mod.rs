@@ -1,21 +1,54 @@
 pub mod base_node;
+pub mod custom;
 pub mod group;
+pub mod instance;
 pub mod line;
 pub mod rectangle;
 
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
 
 pub use base_node::*;
+pub use custom::*;
 pub use group::*;
+pub use instance::*;
 pub use line::*;
 pub use rectangle::*;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{ColorComponent, ScreenDescriptor, TransformComponent, Subscription, SubscriptionId};
+use crate::{
+    ColorComponent, ScreenDescriptor, StrokeComponent, Subscription, SubscriptionId,
+    TransformComponent,
+};
 use epaint::ClippedPrimitive;
 
+/// Entry of a node in the layer tree that host apps render their layers panel from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RadiantLayer {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub visible: bool,
+    pub locked: bool,
+    /// Children from the topmost down.
+    pub children: Vec<RadiantLayer>,
+}
+
+impl RadiantLayer {
+    pub fn new(base: &BaseNode, node_type: &str, children: Vec<RadiantLayer>) -> Self {
+        Self {
+            id: base.id,
+            name: base.name.clone(),
+            node_type: node_type.to_string(),
+            visible: base.visible,
+            locked: base.locked,
+            children,
+        }
+    }
+}
+
 pub trait RadiantTessellatable {
     fn attach(&mut self, screen_descriptor: &ScreenDescriptor);
     fn detach(&mut self);
@@ -33,9 +66,34 @@ pub trait RadiantNode: 'static + Serialize + Clone + RadiantTessellatable {
     fn base(&self) -> &BaseNode;
     fn base_mut(&mut self) -> &mut BaseNode;
 
+    /// The node as [`Any`], so that what is specific to one kind of node is reached by
+    /// downcasting. Nodes holding one of several kinds return the node they hold.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn downcast_ref<T: RadiantNode>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+    fn downcast_mut<T: RadiantNode>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut()
+    }
+
     fn get_id(&self) -> Uuid {
         self.base().id
     }
+
+    /// Kind of the node shown in the layer tree, such as `rectangle`.
+    fn node_type(&self) -> &str {
+        "node"
+    }
+
+    fn layer(&self) -> RadiantLayer {
+        RadiantLayer::new(self.base(), self.node_type(), Vec::new())
+    }
     fn set_id(&mut self, id: Uuid) {
         self.base_mut().id = id;
     }
@@ -44,6 +102,25 @@ pub trait RadiantNode: 'static + Serialize + Clone + RadiantTessellatable {
         self.base().bounding_rect
     }
 
+    /// Bounding rect grown to include the stroke and the effects of the node.
+    fn get_visual_rect(&self) -> [f32; 4] {
+        let [left, top, right, bottom] = self.get_bounding_rect();
+        let rect = epaint::Rect::from_min_max(epaint::pos2(left, top), epaint::pos2(right, bottom))
+            .expand(self.stroke().outer_extent());
+        let rect = self.base().layer().expand_bounds(rect);
+        [rect.min.x, rect.min.y, rect.max.x, rect.max.y]
+    }
+
+    /// Id of the node under `position`, or of the descendant under it for nodes with
+    /// children. `tolerance` grows the area around the outline that still hits the node.
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        let [left, top, right, bottom] = self.get_bounding_rect();
+        epaint::Rect::from_min_max(epaint::pos2(left, top), epaint::pos2(right, bottom))
+            .expand(tolerance)
+            .contains(position.into())
+            .then(|| self.get_id())
+    }
+
     fn transform(&self) -> &TransformComponent {
         &self.base().transform
     }
@@ -58,6 +135,13 @@ pub trait RadiantNode: 'static + Serialize + Clone + RadiantTessellatable {
         &mut self.base_mut().color
     }
 
+    fn stroke(&self) -> &StrokeComponent {
+        self.base().stroke()
+    }
+    fn stroke_mut(&mut self) -> &mut StrokeComponent {
+        self.base_mut().stroke_mut()
+    }
+
     fn handle_key_down(&mut self, _key: crate::KeyCode) -> bool {
         false
     }
@@ -1,119 +1,103 @@
 use crate::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3, Observer,
+    rounded_rect_contains, BaseNode, RadiantNode, RadiantShapes, RadiantTessellatable,
+    ScreenDescriptor, Vec3, PICKING_COLOR,
 };
-use epaint::{ClippedPrimitive, ClippedShape, Rect, TessellationOptions};
+use epaint::{ClippedPrimitive, Color32, Rect, RectShape, Rounding, Shape};
+use radiantkit_macros::RadiantTessellatable;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, RadiantTessellatable)]
+#[radiant_node(build_shapes = build_shapes)]
 pub struct RadiantRectangleNode {
     base: BaseNode,
+    /// Radii of the top left, top right, bottom right and bottom left corners.
+    #[serde(default)]
+    corner_radius: [f32; 4],
 }
 
 impl RadiantRectangleNode {
     pub fn new_wasm(id: Uuid, position: Vec3, scale: Vec3) -> Self {
         let base = BaseNode::new(id, position, scale);
-        Self { base }
+        Self {
+            base,
+            corner_radius: [0.0; 4],
+        }
     }
 }
 
 impl RadiantRectangleNode {
     pub fn new(id: Uuid, position: [f32; 2], scale: [f32; 2]) -> Self {
         let base = BaseNode::new(id, position.into(), scale.into());
-        Self { base }
-    }
-
-    fn tessellate(&mut self, screen_descriptor: &ScreenDescriptor) {
-        if !self.base.needs_tessellation {
-            return;
+        Self {
+            base,
+            corner_radius: [0.0; 4],
         }
-        self.base.needs_tessellation = false;
-
-        let pixels_per_point = screen_descriptor.pixels_per_point;
-        let position = self.base.transform.position();
-        let scale = self.base.transform.scale();
-
-        let rect = epaint::Rect::from_two_pos(
-            position.into(),
-            Vec3::new_with_added(&position, &scale).into(),
-        );
-
-        let rounding = epaint::Rounding::default();
-
-        let fill_color = self.base.color.fill_color();
-        let rect_shape = epaint::RectShape::filled(rect, rounding, fill_color);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::Rect(rect_shape),
-        )];
-        self.base.primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
-
-        let fill_color = get_color_for_node(self.base.id);
-        let rect_shape = epaint::RectShape::filled(rect, rounding, fill_color);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::Rect(rect_shape),
-        )];
-        self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
     }
-}
 
-impl RadiantTessellatable for RadiantRectangleNode {
-    fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
-        self.tessellate(screen_descriptor);
+    /// Radii of the top left, top right, bottom right and bottom left corners.
+    pub fn get_corner_radius(&self) -> [f32; 4] {
+        self.corner_radius
     }
 
-    fn detach(&mut self) {
-        self.base.primitives.clear();
-        self.base.selection_primitives.clear();
+    pub fn set_corner_radius(&mut self, corner_radius: [f32; 4]) {
+        self.corner_radius = corner_radius.map(|radius| radius.max(0.0));
     }
 
-    fn set_needs_tessellation(&mut self, notify: bool) {
+    fn rect(&self) -> Rect {
         let position = self.base.transform.position();
         let scale = self.base.transform.scale();
 
-        let rect = epaint::Rect::from_two_pos(
+        Rect::from_two_pos(
             position.into(),
             Vec3::new_with_added(&position, &scale).into(),
-        );
-
-        self.base.bounding_rect = [
-            rect.left_top().x,
-            rect.left_top().y,
-            rect.right_bottom().x,
-            rect.right_bottom().y,
-        ];
-
-        self.base.set_needs_tessellation();
-        if notify {
-            self.base.notify(serde_json::to_string(self).unwrap());
+        )
+    }
+
+    fn rounding(&self) -> Rounding {
+        let [top_left, top_right, bottom_right, bottom_left] = self.corner_radius;
+        Rounding {
+            nw: top_left,
+            ne: top_right,
+            sw: bottom_left,
+            se: bottom_right,
         }
     }
 
-    fn tessellate(
-        &mut self,
-        selection: bool,
-        screen_descriptor: &ScreenDescriptor,
-        _fonts_manager: &epaint::text::Fonts,
-    ) -> Vec<ClippedPrimitive> {
-        self.tessellate(screen_descriptor);
-        if selection {
-            self.base.selection_primitives.clone()
+    fn build_shapes(&self) -> RadiantShapes {
+        let rect = self.rect();
+        let rounding = self.rounding();
+
+        let mut outline = Vec::new();
+        epaint::tessellator::path::rounded_rectangle(&mut outline, rect, rounding);
+        let stroke_color = self.base.color.stroke_color();
+
+        let fill = vec![Shape::Rect(RectShape::filled(
+            rect,
+            rounding,
+            Color32::WHITE,
+        ))];
+        let shapes = self.base.stroke().shapes(&outline, true, stroke_color);
+
+        let stroke_shapes = if stroke_color == Color32::TRANSPARENT {
+            Vec::new()
         } else {
-            self.base.primitives.clone()
+            self.base.stroke().shapes(&outline, true, PICKING_COLOR)
+        };
+        let selection = std::iter::once(Shape::Rect(RectShape::filled(
+            rect,
+            rounding,
+            PICKING_COLOR,
+        )))
+        .chain(stroke_shapes)
+        .collect();
+
+        RadiantShapes {
+            bounding_rect: rect,
+            fill,
+            shapes,
+            selection,
         }
     }
 }
@@ -123,15 +107,24 @@ impl RadiantNode for RadiantRectangleNode {
         &self.base
     }
 
+    fn node_type(&self) -> &str {
+        "rectangle"
+    }
+
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
 
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        let mut margin = tolerance;
+        if self.base.color.stroke_color() != Color32::TRANSPARENT {
+            margin += self.base.stroke().width() / 2.0;
+        }
+        rounded_rect_contains(position.into(), self.rect().expand(margin), self.rounding())
+            .then_some(self.base.id)
+    }
+
     fn replace(&mut self, node: &str) {
-        let node: Self = serde_json::from_str(node).unwrap();
-        let observers = std::mem::replace(&mut self.base.observers, Observer::default());
-        self.base = node.base;
-        self.base.observers = observers;
-        self.set_needs_tessellation(false);
+        self.replace_keeping_observers(node);
     }
 }
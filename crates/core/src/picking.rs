@@ -0,0 +1,161 @@
+use crate::{RadiantDocumentListener, RadiantDocumentNode, RadiantNode};
+use epaint::{ClippedPrimitive, Color32, Primitive};
+use parking_lot::RwLock;
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+/// Color nodes tessellate their selection primitives with, before the picking registry
+/// paints them with the id of their node.
+pub const PICKING_COLOR: Color32 = Color32::WHITE;
+
+/// Ids written to the picking render target for the nodes of a scene. Id 0 is left for
+/// the background, and the ids of removed nodes are reused.
+#[derive(Debug, Default)]
+pub struct RadiantPickingRegistry {
+    next_id: u32,
+    free_ids: Vec<u32>,
+    ids: HashMap<Uuid, u32>,
+    nodes: HashMap<u32, Uuid>,
+}
+
+impl RadiantPickingRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Id of the node, assigned on first use. Returns `None` once every id is in use.
+    pub fn id(&mut self, node_id: Uuid) -> Option<u32> {
+        if let Some(id) = self.ids.get(&node_id) {
+            return Some(*id);
+        }
+        let id = match self.free_ids.pop() {
+            Some(id) => id,
+            None => {
+                self.next_id = self.next_id.checked_add(1)?;
+                self.next_id
+            }
+        };
+        self.ids.insert(node_id, id);
+        self.nodes.insert(id, node_id);
+        Some(id)
+    }
+
+    pub fn node(&self, id: u32) -> Option<Uuid> {
+        self.nodes.get(&id).copied()
+    }
+
+    pub fn remove(&mut self, node_id: Uuid) {
+        if let Some(id) = self.ids.remove(&node_id) {
+            self.nodes.remove(&id);
+            self.free_ids.push(id);
+        }
+    }
+
+    /// Frees the ids of the nodes for which `keep` returns false.
+    pub fn retain(&mut self, mut keep: impl FnMut(Uuid) -> bool) {
+        let removed = self
+            .ids
+            .keys()
+            .copied()
+            .filter(|node_id| !keep(*node_id))
+            .collect::<Vec<_>>();
+        for node_id in removed {
+            self.remove(node_id);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.next_id = 0;
+        self.free_ids.clear();
+        self.ids.clear();
+        self.nodes.clear();
+    }
+
+    /// Paints the meshes of `primitives` with the id of the node, leaving them out of the
+    /// picking render target when no id is left.
+    pub fn paint(&mut self, node_id: Uuid, primitives: &mut [ClippedPrimitive]) {
+        let color = color_for_picking_id(self.id(node_id).unwrap_or_default());
+        for primitive in primitives {
+            if let Primitive::Mesh(mesh) = &mut primitive.primitive {
+                for vertex in &mut mesh.vertices {
+                    vertex.color = color;
+                }
+            }
+        }
+    }
+}
+
+/// Frees the picking ids of nodes as they are removed from the document, whether by the
+/// scene or by collaborators.
+pub struct RadiantPickingListener {
+    id: Uuid,
+    picking: Arc<RwLock<RadiantPickingRegistry>>,
+}
+
+impl RadiantPickingListener {
+    pub fn new(picking: Arc<RwLock<RadiantPickingRegistry>>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            picking,
+        }
+    }
+}
+
+impl<N: RadiantNode> RadiantDocumentListener<N> for RadiantPickingListener {
+    fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    fn on_node_added(&mut self, _document: &RadiantDocumentNode<N>, _node: Uuid) {}
+
+    fn on_node_changed(&mut self, _id: Uuid, _data: &str) {}
+
+    fn on_node_removed(&mut self, id: Uuid) {
+        self.picking.write().remove(id);
+    }
+}
+
+/// Vertex color holding `id` in its bytes, which the picking shader writes out as is.
+pub fn color_for_picking_id(id: u32) -> Color32 {
+    let [r, g, b, a] = id.to_le_bytes();
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        RadiantDocumentNode, RadiantLineNode, RadiantPickingListener, RadiantPickingRegistry,
+    };
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    #[test]
+    fn ids_are_freed_when_nodes_are_removed() {
+        let picking = Arc::new(RwLock::new(RadiantPickingRegistry::new()));
+        let mut document = RadiantDocumentNode::<RadiantLineNode>::new();
+        document.add_listener(Box::new(RadiantPickingListener::new(picking.clone())));
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        document.add(RadiantLineNode::new(first, [0.0, 0.0], [10.0, 0.0]));
+        document.add(RadiantLineNode::new(second, [0.0, 0.0], [10.0, 0.0]));
+        let id = picking.write().id(first);
+        picking.write().id(second);
+
+        // As when removed by a collaborator, which is not notified of its own removals.
+        document.remove_excluding_listener(first, Uuid::new_v4());
+        assert_eq!(picking.read().len(), 1);
+        assert_eq!(picking.read().node(id.unwrap()), None);
+        assert_eq!(picking.write().id(Uuid::new_v4()), id);
+
+        document.remove(second);
+        assert_eq!(picking.read().len(), 1);
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{
+    LayerComponent, LayoutItemComponent, RadiantCustomNode, RadiantDynComponent,
+    RadiantMetadataComponent, RadiantTypedComponent, RadiantTypedNode, StrokeComponent,
+    StyleComponent, TextComponent,
+};
+use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
+
+type NodeDeserializer = fn(serde_json::Value) -> serde_json::Result<RadiantCustomNode>;
+type ComponentDeserializer =
+    fn(serde_json::Value) -> serde_json::Result<Box<dyn RadiantDynComponent>>;
+
+thread_local! {
+    /// Registry of the document whose nodes are being deserialized on this thread.
+    static SCOPED_REGISTRY: RefCell<Option<RadiantTypeRegistry>> = const { RefCell::new(None) };
+}
+
+/// Types defined outside of radiantkit that the nodes of a document can hold, and the
+/// components they can carry. The document owns its registry, and makes it available to
+/// serde with [`RadiantTypeRegistry::scope`] while it deserializes nodes.
+#[derive(Debug, Clone)]
+pub struct RadiantTypeRegistry {
+    node_types: Arc<BTreeMap<&'static str, NodeDeserializer>>,
+    component_types: Arc<BTreeMap<&'static str, ComponentDeserializer>>,
+}
+
+impl Default for RadiantTypeRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            node_types: Default::default(),
+            component_types: Default::default(),
+        };
+        registry.register_component_type::<StrokeComponent>();
+        registry.register_component_type::<LayerComponent>();
+        registry.register_component_type::<LayoutItemComponent>();
+        registry.register_component_type::<StyleComponent>();
+        registry.register_component_type::<TextComponent>();
+        registry.register_component_type::<RadiantMetadataComponent>();
+        registry
+    }
+}
+
+impl RadiantTypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `T` so that custom nodes tagged with its type tag can be deserialized.
+    pub fn register_node_type<T: RadiantTypedNode>(&mut self) {
+        Arc::make_mut(&mut self.node_types).insert(T::TYPE_TAG, |value| {
+            Ok(RadiantCustomNode::new(serde_json::from_value::<T>(value)?))
+        });
+    }
+
+    pub fn node_types(&self) -> Vec<&'static str> {
+        self.node_types.keys().copied().collect()
+    }
+
+    /// Deserializes a node of the registered type `type_tag`.
+    pub fn custom_node(
+        &self,
+        type_tag: &str,
+        node: serde_json::Value,
+    ) -> serde_json::Result<RadiantCustomNode> {
+        let deserialize = self.node_types.get(type_tag).ok_or_else(|| {
+            serde::de::Error::custom(format!("unregistered node type `{}`", type_tag))
+        })?;
+        deserialize(node)
+    }
+
+    /// Registers `T` so that components tagged with its type tag can be deserialized.
+    pub fn register_component_type<T: RadiantTypedComponent>(&mut self) {
+        Arc::make_mut(&mut self.component_types).insert(T::TYPE_TAG, |value| {
+            Ok(Box::new(serde_json::from_value::<T>(value)?))
+        });
+    }
+
+    pub fn component_types(&self) -> Vec<&'static str> {
+        self.component_types.keys().copied().collect()
+    }
+
+    /// Deserializes a component of the type `type_tag`, or returns `None` when the type is
+    /// not registered.
+    pub fn component(
+        &self,
+        type_tag: &str,
+        value: serde_json::Value,
+    ) -> Option<serde_json::Result<Box<dyn RadiantDynComponent>>> {
+        let deserialize = self.component_types.get(type_tag)?;
+        Some(deserialize(value))
+    }
+
+    /// Runs `f` with the registry available to the deserialization of custom nodes and
+    /// components.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<RadiantTypeRegistry>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED_REGISTRY.with(|scoped| *scoped.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(SCOPED_REGISTRY.with(|scoped| scoped.replace(Some(self.clone()))));
+        f()
+    }
+
+    /// Registry of the innermost [`RadiantTypeRegistry::scope`] on this thread, if any.
+    pub(crate) fn scoped() -> Option<RadiantTypeRegistry> {
+        SCOPED_REGISTRY.with(|scoped| scoped.borrow().clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        BaseNode, RadiantCustomNode, RadiantNode, RadiantTessellatable, RadiantTypeRegistry,
+        RadiantTypedNode, ScreenDescriptor,
+    };
+    use epaint::ClippedPrimitive;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    struct TestNode {
+        base: BaseNode,
+    }
+
+    impl RadiantTessellatable for TestNode {
+        fn attach(&mut self, _screen_descriptor: &ScreenDescriptor) {}
+
+        fn detach(&mut self) {}
+
+        fn set_needs_tessellation(&mut self, _notify: bool) {}
+
+        fn tessellate(
+            &mut self,
+            _selection: bool,
+            _screen_descriptor: &ScreenDescriptor,
+            _fonts_manager: &epaint::text::Fonts,
+        ) -> Vec<ClippedPrimitive> {
+            Vec::new()
+        }
+    }
+
+    impl RadiantNode for TestNode {
+        fn base(&self) -> &BaseNode {
+            &self.base
+        }
+
+        fn base_mut(&mut self) -> &mut BaseNode {
+            &mut self.base
+        }
+    }
+
+    impl RadiantTypedNode for TestNode {
+        const TYPE_TAG: &'static str = "test";
+    }
+
+    #[test]
+    fn custom_nodes_deserialize_within_the_scope_of_their_registry() {
+        let id = Uuid::new_v4();
+        let node = RadiantCustomNode::new(TestNode {
+            base: BaseNode::new(id, [0.0, 0.0, 0.0].into(), [10.0, 10.0, 0.0].into()),
+        });
+        let data = serde_json::to_string(&node).unwrap();
+
+        let mut registry = RadiantTypeRegistry::new();
+        assert!(registry
+            .scope(|| serde_json::from_str::<RadiantCustomNode>(&data))
+            .is_err());
+
+        registry.register_node_type::<TestNode>();
+        assert_eq!(registry.node_types(), vec!["test"]);
+        let node = registry
+            .scope(|| serde_json::from_str::<RadiantCustomNode>(&data))
+            .unwrap();
+        assert_eq!(node.get_id(), id);
+        assert_eq!(node.node_type(), "test");
+        assert!(node.downcast_ref::<TestNode>().is_some());
+
+        assert!(serde_json::from_str::<RadiantCustomNode>(&data).is_err());
+    }
+}
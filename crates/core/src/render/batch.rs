@@ -0,0 +1,65 @@
+use epaint::{ClippedPrimitive, Mesh, PaintCallback, Primitive, Rect};
+use std::{any::Any, sync::Arc};
+
+/// Meshes of a node, uploaded once into their own GPU buffers and drawn from them for as
+/// long as the same batch is rendered. Building a new batch is what makes the renderer
+/// upload the meshes again.
+#[derive(Debug, Clone)]
+pub struct RadiantPrimitiveBatch {
+    pub meshes: Vec<(Rect, Mesh)>,
+}
+
+impl RadiantPrimitiveBatch {
+    /// Batches `primitives` when they are only made of meshes. Markers must stay visible to
+    /// the render manager, so the primitives are returned as they are otherwise.
+    pub fn batch(primitives: Vec<ClippedPrimitive>) -> Vec<ClippedPrimitive> {
+        if primitives.is_empty()
+            || primitives
+                .iter()
+                .any(|primitive| matches!(primitive.primitive, Primitive::Callback(_)))
+        {
+            return primitives;
+        }
+        let meshes = primitives
+            .into_iter()
+            .filter_map(|primitive| match primitive.primitive {
+                Primitive::Mesh(mesh) => Some((primitive.clip_rect, mesh)),
+                Primitive::Callback(_) => None,
+            })
+            .collect();
+        vec![ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Callback(PaintCallback {
+                rect: Rect::EVERYTHING,
+                callback: Arc::new(Self { meshes }),
+            }),
+        }]
+    }
+
+    /// The batch and the key identifying its buffers, which stays the same for every copy
+    /// of the primitive.
+    pub fn from_primitive(primitive: &ClippedPrimitive) -> Option<(usize, &Self)> {
+        match &primitive.primitive {
+            Primitive::Callback(callback) => {
+                let batch = callback.callback.downcast_ref::<Self>()?;
+                Some((Self::key(&callback.callback), batch))
+            }
+            Primitive::Mesh(_) => None,
+        }
+    }
+
+    pub(crate) fn key(callback: &Arc<dyn Any + Send + Sync>) -> usize {
+        Arc::as_ptr(callback) as *const u8 as usize
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.meshes
+            .iter()
+            .map(|(_, mesh)| mesh.vertices.len())
+            .sum()
+    }
+
+    pub fn index_count(&self) -> usize {
+        self.meshes.iter().map(|(_, mesh)| mesh.indices.len()).sum()
+    }
+}
@@ -0,0 +1,148 @@
+// Composites an offscreen layer onto its backdrop and renders layer effects.
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+};
+
+struct Locals {
+    opacity: f32,
+    blend_mode: u32,
+    // Blur radius and shadow spread in pixels.
+    radius: f32,
+    spread: f32,
+    // Blur direction, either (1, 0) or (0, 1).
+    direction: vec2<f32>,
+    // Shadow offset in pixels.
+    offset: vec2<f32>,
+    // Premultiplied shadow color.
+    color: vec4<f32>,
+};
+
+@group(0) @binding(0) var r_layer: texture_2d<f32>;
+@group(0) @binding(1) var r_backdrop: texture_2d<f32>;
+@group(0) @binding(2) var<uniform> r_locals: Locals;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // A single triangle covering the whole target.
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    return out;
+}
+
+fn unpremultiply(color: vec4<f32>) -> vec3<f32> {
+    if color.a <= 0.0 {
+        return vec3<f32>(0.0);
+    }
+    return color.rgb / color.a;
+}
+
+fn hard_light(backdrop: vec3<f32>, source: vec3<f32>) -> vec3<f32> {
+    let multiply = backdrop * 2.0 * source;
+    let screen = 1.0 - (1.0 - backdrop) * (1.0 - (2.0 * source - 1.0));
+    return select(screen, multiply, source <= vec3<f32>(0.5));
+}
+
+fn blend(backdrop: vec3<f32>, source: vec3<f32>) -> vec3<f32> {
+    switch r_locals.blend_mode {
+        case 1u: {
+            return backdrop * source;
+        }
+        case 2u: {
+            return backdrop + source - backdrop * source;
+        }
+        case 3u: {
+            return hard_light(source, backdrop);
+        }
+        default: {
+            return source;
+        }
+    }
+}
+
+// Loads a texel of the layer, treating everything outside of the texture as transparent.
+fn load_layer(position: vec2<f32>) -> vec4<f32> {
+    let size = vec2<f32>(textureDimensions(r_layer));
+    let texel = floor(position);
+    if any(texel < vec2<f32>(0.0)) || any(texel >= size) {
+        return vec4<f32>(0.0);
+    }
+    return textureLoad(r_layer, vec2<i32>(texel), 0);
+}
+
+// Alpha of the layer grown (positive spread) or shrunk (negative spread).
+fn spread_alpha(position: vec2<f32>) -> f32 {
+    let spread = r_locals.spread;
+    var alpha = load_layer(position).a;
+    if spread == 0.0 {
+        return alpha;
+    }
+    for (var i = 0; i < 8; i++) {
+        let angle = f32(i) * 0.78539816;
+        let sample = load_layer(position + vec2<f32>(cos(angle), sin(angle)) * abs(spread)).a;
+        alpha = select(min(alpha, sample), max(alpha, sample), spread > 0.0);
+    }
+    return alpha;
+}
+
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    let position = vec2<i32>(in.position.xy);
+    let layer = textureLoad(r_layer, position, 0);
+    let backdrop = textureLoad(r_backdrop, position, 0);
+
+    let alpha = layer.a * r_locals.opacity;
+    let source = unpremultiply(layer);
+    let mixed = (1.0 - backdrop.a) * source + backdrop.a * blend(unpremultiply(backdrop), source);
+
+    return vec4<f32>(
+        alpha * mixed + (1.0 - alpha) * backdrop.rgb,
+        alpha + backdrop.a * (1.0 - alpha),
+    );
+}
+
+@fragment
+fn fs_blit(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureLoad(r_layer, vec2<i32>(in.position.xy), 0);
+}
+
+// The layer clipped to the shape of the backdrop texture.
+@fragment
+fn fs_mask(in: VertexOutput) -> @location(0) vec4<f32> {
+    let position = vec2<i32>(in.position.xy);
+    return textureLoad(r_layer, position, 0) * textureLoad(r_backdrop, position, 0).a;
+}
+
+// One direction of a separable gaussian blur.
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    let radius = r_locals.radius;
+    if radius < 0.5 {
+        return load_layer(in.position.xy);
+    }
+
+    let sigma = radius / 2.0;
+    // Large radii are sampled sparsely to keep the number of taps bounded.
+    let step = max(1.0, ceil(radius / 32.0));
+    var color = vec4<f32>(0.0);
+    var weights = 0.0;
+    for (var x = -ceil(radius); x <= ceil(radius); x += step) {
+        let weight = exp(-(x * x) / (2.0 * sigma * sigma));
+        color += load_layer(in.position.xy + r_locals.direction * x) * weight;
+        weights += weight;
+    }
+    return color / weights;
+}
+
+@fragment
+fn fs_drop_shadow(in: VertexOutput) -> @location(0) vec4<f32> {
+    return r_locals.color * spread_alpha(in.position.xy - r_locals.offset);
+}
+
+@fragment
+fn fs_inner_shadow(in: VertexOutput) -> @location(0) vec4<f32> {
+    // The spread of an inner shadow shrinks the shape casting it.
+    let alpha = spread_alpha(in.position.xy - r_locals.offset);
+    return r_locals.color * (1.0 - alpha);
+}
@@ -0,0 +1,551 @@
+use crate::{LayerComponent, RadiantBlendMode, RadiantEffect, ScissorRect, ScreenDescriptor};
+use epaint::{ClippedPrimitive, Color32, PaintCallback, Primitive, Rect};
+use std::{num::NonZeroU64, sync::Arc};
+use wgpu::util::DeviceExt;
+
+/// Delimits primitives that are rendered into their own layer and composited onto the
+/// content below once the layer ends.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RadiantLayerMarker {
+    Begin,
+    /// Ends the current layer. `bounds` encloses everything the layer and its effects
+    /// may cover, in points.
+    End {
+        style: LayerComponent,
+        bounds: Rect,
+    },
+}
+
+impl From<RadiantLayerMarker> for ClippedPrimitive {
+    fn from(marker: RadiantLayerMarker) -> Self {
+        ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Callback(PaintCallback {
+                rect: Rect::EVERYTHING,
+                callback: Arc::new(marker),
+            }),
+        }
+    }
+}
+
+impl RadiantLayerMarker {
+    pub fn from_primitive(primitive: &ClippedPrimitive) -> Option<Self> {
+        match &primitive.primitive {
+            Primitive::Callback(callback) => callback.callback.downcast_ref::<Self>().cloned(),
+            Primitive::Mesh(_) => None,
+        }
+    }
+}
+
+/// Screen sized texture used as a render target and as a compositing source.
+pub struct RadiantLayerTexture {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+}
+
+impl RadiantLayerTexture {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, size: [u32; 2]) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("radiant_layer_texture"),
+            size: wgpu::Extent3d {
+                width: size[0],
+                height: size[1],
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self { texture, view }
+    }
+}
+
+/// Textures used to render the effects of a layer.
+pub struct RadiantEffectTargets<'a> {
+    /// The layer the effects belong to.
+    pub layer: &'a RadiantLayerTexture,
+    /// The layer the effects are rendered onto, below `layer`.
+    pub parent: &'a RadiantLayerTexture,
+    pub backdrop: &'a RadiantLayerTexture,
+    pub scratch: [&'a RadiantLayerTexture; 2],
+}
+
+/// Values of `Locals` in composite.wgsl.
+#[derive(Default)]
+struct PassUniform {
+    opacity: f32,
+    blend_mode: u32,
+    radius: f32,
+    spread: f32,
+    direction: [f32; 2],
+    offset: [f32; 2],
+    color: [f32; 4],
+}
+
+impl PassUniform {
+    fn to_words(&self) -> [u32; 12] {
+        [
+            self.opacity.to_bits(),
+            self.blend_mode,
+            self.radius.to_bits(),
+            self.spread.to_bits(),
+            self.direction[0].to_bits(),
+            self.direction[1].to_bits(),
+            self.offset[0].to_bits(),
+            self.offset[1].to_bits(),
+            self.color[0].to_bits(),
+            self.color[1].to_bits(),
+            self.color[2].to_bits(),
+            self.color[3].to_bits(),
+        ]
+    }
+}
+
+/// A fullscreen draw reading from `source` and `backdrop` into `target`.
+struct Pass<'a> {
+    pipeline: &'a wgpu::RenderPipeline,
+    source: &'a wgpu::TextureView,
+    backdrop: &'a wgpu::TextureView,
+    target: &'a wgpu::TextureView,
+    /// Whether `target` is cleared first, otherwise its content outside of `scissor` is kept.
+    clear: bool,
+    scissor: Option<&'a ScissorRect>,
+}
+
+/// Blends offscreen layers onto their backdrop with an opacity and a blend mode, and renders
+/// their shadows and blurs.
+pub struct RadiantCompositor {
+    composite_pipeline: wgpu::RenderPipeline,
+    blit_pipeline: wgpu::RenderPipeline,
+    over_pipeline: wgpu::RenderPipeline,
+    atop_pipeline: wgpu::RenderPipeline,
+    mask_pipeline: wgpu::RenderPipeline,
+    blur_pipeline: wgpu::RenderPipeline,
+    drop_shadow_pipeline: wgpu::RenderPipeline,
+    inner_shadow_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    /// Whether the targets store linear colors, in which case shadow colors are converted.
+    linear: bool,
+}
+
+impl RadiantCompositor {
+    pub fn new(device: &wgpu::Device, output_color_format: wgpu::TextureFormat) -> Self {
+        let module = device.create_shader_module(wgpu::include_wgsl!("composite.wgsl"));
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("radiant_composite_bind_group_layout"),
+            entries: &[
+                texture_entry(0),
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZeroU64::new(48),
+                        ty: wgpu::BufferBindingType::Uniform,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("radiant_composite_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let create_pipeline = |entry_point, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("radiant_composite_pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: output_color_format,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+        let over = Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING);
+        // Draws the source only where the target is opaque, keeping the target alpha.
+        let atop = Some(wgpu::BlendState {
+            color: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::DstAlpha,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+            alpha: wgpu::BlendComponent {
+                src_factor: wgpu::BlendFactor::Zero,
+                dst_factor: wgpu::BlendFactor::One,
+                operation: wgpu::BlendOperation::Add,
+            },
+        });
+
+        Self {
+            composite_pipeline: create_pipeline("fs_composite", None),
+            blit_pipeline: create_pipeline("fs_blit", None),
+            over_pipeline: create_pipeline("fs_blit", over),
+            atop_pipeline: create_pipeline("fs_blit", atop),
+            mask_pipeline: create_pipeline("fs_mask", over),
+            blur_pipeline: create_pipeline("fs_blur", None),
+            drop_shadow_pipeline: create_pipeline("fs_drop_shadow", None),
+            inner_shadow_pipeline: create_pipeline("fs_inner_shadow", None),
+            bind_group_layout,
+            linear: output_color_format.is_srgb(),
+        }
+    }
+
+    fn bind_group(
+        &self,
+        device: &wgpu::Device,
+        layer: &wgpu::TextureView,
+        backdrop: &wgpu::TextureView,
+        uniform: &PassUniform,
+    ) -> wgpu::BindGroup {
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("radiant_composite_uniform_buffer"),
+            contents: bytemuck::cast_slice(&uniform.to_words()),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("radiant_composite_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(layer),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(backdrop),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    fn run(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pass: Pass,
+        uniform: &PassUniform,
+    ) {
+        let bind_group = self.bind_group(device, pass.source, pass.backdrop, uniform);
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Composite Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: pass.target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: if pass.clear {
+                        wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+                    } else {
+                        wgpu::LoadOp::Load
+                    },
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+        if let Some(scissor) = pass.scissor {
+            render_pass.set_scissor_rect(scissor.x, scissor.y, scissor.width, scissor.height);
+        }
+        render_pass.set_pipeline(pass.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Blends `layer` over the content of `target`. `backdrop` receives a copy of `target`
+    /// since a texture cannot be sampled while it is being rendered to.
+    pub fn composite(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        layer: &RadiantLayerTexture,
+        target: &RadiantLayerTexture,
+        backdrop: &RadiantLayerTexture,
+        layer_style: &LayerComponent,
+    ) {
+        encoder.copy_texture_to_texture(
+            target.texture.as_image_copy(),
+            backdrop.texture.as_image_copy(),
+            target.texture.size(),
+        );
+
+        let blend_mode = match layer_style.blend_mode() {
+            RadiantBlendMode::Normal => 0,
+            RadiantBlendMode::Multiply => 1,
+            RadiantBlendMode::Screen => 2,
+            RadiantBlendMode::Overlay => 3,
+        };
+        self.run(
+            device,
+            encoder,
+            Pass {
+                pipeline: &self.composite_pipeline,
+                source: &layer.view,
+                backdrop: &backdrop.view,
+                target: &target.view,
+                clear: false,
+                scissor: None,
+            },
+            &PassUniform {
+                opacity: layer_style.opacity(),
+                blend_mode,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Copies `source` onto `view`, which may be a surface texture.
+    pub fn blit(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &RadiantLayerTexture,
+        view: &wgpu::TextureView,
+    ) {
+        self.run(
+            device,
+            encoder,
+            Pass {
+                pipeline: &self.blit_pipeline,
+                source: &source.view,
+                backdrop: &source.view,
+                target: view,
+                clear: false,
+                scissor: None,
+            },
+            &PassUniform::default(),
+        );
+    }
+
+    /// Renders the effects of a layer before it is composited. Background blurs and drop
+    /// shadows are drawn onto the parent, inner shadows and layer blurs modify the layer.
+    pub fn apply_effects(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        targets: &RadiantEffectTargets,
+        layer_style: &LayerComponent,
+        bounds: Rect,
+        screen_descriptor: &ScreenDescriptor,
+    ) {
+        let effects = layer_style.effects();
+        let pixels_per_point = screen_descriptor.pixels_per_point;
+        // Blurs read pixels up to their radius away from the layer.
+        let margin = effects
+            .iter()
+            .fold(0.0_f32, |margin, effect| margin.max(effect.blur_radius()))
+            + 1.0;
+        let scissor = ScissorRect::new(&bounds.expand(margin), screen_descriptor);
+        if scissor.width == 0 || scissor.height == 0 {
+            return;
+        }
+
+        let background_blurs = effects
+            .iter()
+            .filter(|effect| matches!(effect, RadiantEffect::BackgroundBlur { .. }));
+        let drop_shadows = effects
+            .iter()
+            .filter(|effect| matches!(effect, RadiantEffect::DropShadow { .. }));
+        let inner_shadows = effects
+            .iter()
+            .filter(|effect| matches!(effect, RadiantEffect::InnerShadow { .. }));
+        let layer_blurs = effects
+            .iter()
+            .filter(|effect| matches!(effect, RadiantEffect::LayerBlur { .. }));
+
+        let [scratch, blurred] = targets.scratch;
+        for effect in background_blurs
+            .chain(drop_shadows)
+            .chain(inner_shadows)
+            .chain(layer_blurs)
+        {
+            let radius = effect.blur_radius() * pixels_per_point;
+            match *effect {
+                RadiantEffect::BackgroundBlur { .. } => {
+                    encoder.copy_texture_to_texture(
+                        targets.parent.texture.as_image_copy(),
+                        targets.backdrop.texture.as_image_copy(),
+                        targets.parent.texture.size(),
+                    );
+                    self.blur(
+                        device,
+                        encoder,
+                        [targets.backdrop, scratch, blurred],
+                        radius,
+                        &scissor,
+                    );
+                    self.run(
+                        device,
+                        encoder,
+                        Pass {
+                            pipeline: &self.mask_pipeline,
+                            source: &blurred.view,
+                            backdrop: &targets.layer.view,
+                            target: &targets.parent.view,
+                            clear: false,
+                            scissor: Some(&scissor),
+                        },
+                        &PassUniform::default(),
+                    );
+                }
+                RadiantEffect::DropShadow {
+                    offset,
+                    spread,
+                    color,
+                    ..
+                }
+                | RadiantEffect::InnerShadow {
+                    offset,
+                    spread,
+                    color,
+                    ..
+                } => {
+                    let inner = matches!(effect, RadiantEffect::InnerShadow { .. });
+                    let color = self.shadow_color(color, layer_style.opacity());
+                    self.run(
+                        device,
+                        encoder,
+                        Pass {
+                            pipeline: if inner {
+                                &self.inner_shadow_pipeline
+                            } else {
+                                &self.drop_shadow_pipeline
+                            },
+                            source: &targets.layer.view,
+                            backdrop: &targets.layer.view,
+                            target: &scratch.view,
+                            clear: true,
+                            scissor: Some(&scissor),
+                        },
+                        &PassUniform {
+                            spread: (if inner { -spread } else { spread }) * pixels_per_point,
+                            offset: [offset[0] * pixels_per_point, offset[1] * pixels_per_point],
+                            color,
+                            ..Default::default()
+                        },
+                    );
+                    self.blur(
+                        device,
+                        encoder,
+                        [scratch, blurred, scratch],
+                        radius,
+                        &scissor,
+                    );
+                    self.run(
+                        device,
+                        encoder,
+                        Pass {
+                            pipeline: if inner {
+                                &self.atop_pipeline
+                            } else {
+                                &self.over_pipeline
+                            },
+                            source: &scratch.view,
+                            backdrop: &scratch.view,
+                            target: if inner {
+                                &targets.layer.view
+                            } else {
+                                &targets.parent.view
+                            },
+                            clear: false,
+                            scissor: Some(&scissor),
+                        },
+                        &PassUniform::default(),
+                    );
+                }
+                RadiantEffect::LayerBlur { .. } => {
+                    self.blur(
+                        device,
+                        encoder,
+                        [targets.layer, scratch, targets.layer],
+                        radius,
+                        &scissor,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Blurs the first texture into the last one in two directions, going through the
+    /// second one.
+    fn blur(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        [source, scratch, target]: [&RadiantLayerTexture; 3],
+        radius: f32,
+        scissor: &ScissorRect,
+    ) {
+        for (source, target, direction, clear) in [
+            (source, scratch, [1.0, 0.0], true),
+            (scratch, target, [0.0, 1.0], false),
+        ] {
+            self.run(
+                device,
+                encoder,
+                Pass {
+                    pipeline: &self.blur_pipeline,
+                    source: &source.view,
+                    backdrop: &source.view,
+                    target: &target.view,
+                    clear,
+                    scissor: Some(scissor),
+                },
+                &PassUniform {
+                    radius,
+                    direction,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    /// Premultiplied color in the color space of the targets.
+    fn shadow_color(&self, color: Color32, opacity: f32) -> [f32; 4] {
+        let color = color.linear_multiply(opacity);
+        if self.linear {
+            epaint::Rgba::from(color).to_array()
+        } else {
+            color.to_array().map(|channel| channel as f32 / 255.0)
+        }
+    }
+}
@@ -8,9 +8,8 @@ struct VertexOutput {
 
 struct Locals {
     screen_size: vec2<f32>,
-    // Uniform buffers need to be at least 16 bytes in WebGL.
-    // See https://github.com/gfx-rs/wgpu/issues/2072
-    _padding: vec2<u32>,
+    // Document position at the top left corner of the screen.
+    offset: vec2<f32>,
 };
 @group(0) @binding(0) var<uniform> r_locals: Locals;
 
@@ -45,7 +44,8 @@ fn unpack_color(color: u32) -> vec4<f32> {
     ) / 255.0;
 }
 
-fn position_from_screen(screen_pos: vec2<f32>) -> vec4<f32> {
+fn position_from_screen(document_pos: vec2<f32>) -> vec4<f32> {
+    let screen_pos = document_pos - r_locals.offset;
     return vec4<f32>(
         2.0 * screen_pos.x / r_locals.screen_size.x - 1.0,
         1.0 - 2.0 * screen_pos.y / r_locals.screen_size.y,
@@ -91,3 +91,37 @@ fn fs_main_gamma_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
     // let out_color_gamma = in.color; //vec4<f32>(1.0, 0.0, 0.0, 1.0); //in.color;
     return out_color_gamma;
 }
+
+// Only used to write mask shapes into the stencil buffer, dropping their feathered edges.
+@fragment
+fn fs_stencil(in: VertexOutput) -> @location(0) vec4<f32> {
+    let alpha = in.color.a * textureSample(r_tex_color, r_tex_sampler, in.tex_coord).a;
+    if alpha < 0.5 {
+        discard;
+    }
+    return vec4<f32>(0.0);
+}
+
+// Picking: vertex colors hold the id of their node, written out as is.
+
+struct PickingOutput {
+    @location(0) @interpolate(flat) id: u32,
+    @builtin(position) position: vec4<f32>,
+};
+
+@vertex
+fn vs_picking(
+    @location(0) a_pos: vec2<f32>,
+    @location(1) a_tex_coord: vec2<f32>,
+    @location(2) a_color: u32,
+) -> PickingOutput {
+    var out: PickingOutput;
+    out.id = a_color;
+    out.position = position_from_screen(a_pos);
+    return out;
+}
+
+@fragment
+fn fs_picking(in: PickingOutput) -> @location(0) u32 {
+    return in.id;
+}
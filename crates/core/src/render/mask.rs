@@ -0,0 +1,58 @@
+use epaint::{ClippedPrimitive, PaintCallback, Primitive, Rect};
+use std::sync::Arc;
+
+/// Delimits primitives clipped by mask shapes. The renderer writes the shapes into the
+/// stencil buffer and only draws the clipped primitives where the shapes cover them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadiantMaskMarker {
+    /// Following meshes add their shape to a new mask.
+    Begin,
+    /// Following meshes are clipped by the mask.
+    Content,
+    /// Following meshes remove their shape from the mask.
+    End,
+    /// Following meshes are only clipped by the enclosing masks.
+    Restore,
+}
+
+impl From<RadiantMaskMarker> for ClippedPrimitive {
+    fn from(marker: RadiantMaskMarker) -> Self {
+        ClippedPrimitive {
+            clip_rect: Rect::EVERYTHING,
+            primitive: Primitive::Callback(PaintCallback {
+                rect: Rect::EVERYTHING,
+                callback: Arc::new(marker),
+            }),
+        }
+    }
+}
+
+impl RadiantMaskMarker {
+    pub fn from_primitive(primitive: &ClippedPrimitive) -> Option<Self> {
+        match &primitive.primitive {
+            Primitive::Callback(callback) => callback.callback.downcast_ref::<Self>().copied(),
+            Primitive::Mesh(_) => None,
+        }
+    }
+
+    /// Clips `content` to the union of the meshes in `mask`.
+    pub fn clip(
+        mask: Vec<ClippedPrimitive>,
+        mut content: Vec<ClippedPrimitive>,
+    ) -> Vec<ClippedPrimitive> {
+        let mask = mask
+            .into_iter()
+            .filter(|primitive| matches!(primitive.primitive, Primitive::Mesh(_)))
+            .collect::<Vec<_>>();
+
+        let mut primitives = Vec::with_capacity(mask.len() * 2 + content.len() + 4);
+        primitives.push(Self::Begin.into());
+        primitives.extend(mask.iter().cloned());
+        primitives.push(Self::Content.into());
+        primitives.append(&mut content);
+        primitives.push(Self::End.into());
+        primitives.extend(mask);
+        primitives.push(Self::Restore.into());
+        primitives
+    }
+}
@@ -1,5 +1,11 @@
+pub mod batch;
+pub mod compositor;
+pub mod mask;
 pub mod render_manager;
 pub mod renderer;
 
+pub use batch::*;
+pub use compositor::*;
+pub use mask::*;
 pub use render_manager::*;
 pub use renderer::*;
@@ -1,6 +1,9 @@
-use crate::{get_node_for_color, RadiantRenderer, ScreenDescriptor};
-use epaint::{textures::TexturesDelta, ClippedPrimitive, Color32, ImageDelta};
-use uuid::Uuid;
+use crate::{
+    RadiantCompositor, RadiantEffectTargets, RadiantLayerMarker, RadiantLayerTexture,
+    RadiantRenderState, RadiantRenderer, ScreenDescriptor, RADIANT_PICKING_FORMAT,
+    RADIANT_STENCIL_FORMAT,
+};
+use epaint::{textures::TexturesDelta, ClippedPrimitive, ImageDelta};
 
 pub struct RadiantRenderManager {
     pub config: wgpu::SurfaceConfiguration,
@@ -10,6 +13,16 @@ pub struct RadiantRenderManager {
 
     pub renderer: RadiantRenderer,
     pub offscreen_renderer: RadiantRenderer,
+    pub compositor: RadiantCompositor,
+
+    /// Render targets for nested layers, the first one holding the whole canvas.
+    layer_textures: Vec<RadiantLayerTexture>,
+    backdrop_texture: Option<RadiantLayerTexture>,
+    /// Intermediate targets for shadows and blurs.
+    effect_textures: Vec<RadiantLayerTexture>,
+    /// Stencil buffer holding the shapes of masks, shared by every render pass.
+    stencil_texture: Option<wgpu::Texture>,
+    stencil_texture_view: Option<wgpu::TextureView>,
 
     offscreen_texture: Option<wgpu::Texture>,
     offscreen_texture_view: Option<wgpu::TextureView>,
@@ -27,9 +40,15 @@ impl RadiantRenderManager {
         queue: wgpu::Queue,
         font_image_delta: Option<ImageDelta>,
     ) -> Self {
-        let mut renderer = RadiantRenderer::new(&device, config.format, None, 1);
-        let mut offscreen_renderer =
-            RadiantRenderer::new(&device, wgpu::TextureFormat::Rgba8Unorm, None, 1);
+        let mut renderer =
+            RadiantRenderer::new(&device, config.format, Some(RADIANT_STENCIL_FORMAT), 1);
+        let mut offscreen_renderer = RadiantRenderer::new(
+            &device,
+            RADIANT_PICKING_FORMAT,
+            Some(RADIANT_STENCIL_FORMAT),
+            1,
+        );
+        let compositor = RadiantCompositor::new(&device, config.format);
 
         if let Some(image_delta) = font_image_delta {
             renderer.update_texture(&device, &queue, epaint::TextureId::default(), &image_delta);
@@ -49,6 +68,13 @@ impl RadiantRenderManager {
 
             renderer,
             offscreen_renderer,
+            compositor,
+
+            layer_textures: Vec::new(),
+            backdrop_texture: None,
+            effect_textures: Vec::new(),
+            stencil_texture: None,
+            stencil_texture_view: None,
 
             offscreen_texture: None,
             offscreen_texture_view: None,
@@ -65,6 +91,12 @@ impl RadiantRenderManager {
             self.config.height = new_size[1];
             self.surface.configure(&self.device, &self.config);
 
+            self.layer_textures.clear();
+            self.backdrop_texture = None;
+            self.effect_textures.clear();
+            self.stencil_texture = None;
+            self.stencil_texture_view = None;
+
             let texture_width = new_size[0];
             let texture_height = new_size[1];
 
@@ -77,7 +109,7 @@ impl RadiantRenderManager {
                 mip_level_count: 1,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8Unorm,
+                format: RADIANT_PICKING_FORMAT,
                 usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::RENDER_ATTACHMENT,
                 label: None,
                 view_formats: &[],
@@ -150,6 +182,12 @@ impl RadiantRenderManager {
         selection: bool,
         encoder: &mut wgpu::CommandEncoder,
     ) -> Result<(), wgpu::SurfaceError> {
+        self.ensure_stencil_texture(screen_descriptor.size_in_pixels);
+        let layered = !selection
+            && primitives
+                .iter()
+                .any(|primitive| RadiantLayerMarker::from_primitive(primitive).is_some());
+
         let view;
         if selection {
             self.offscreen_renderer.update_buffers(
@@ -190,6 +228,11 @@ impl RadiantRenderManager {
                     a: 1.0,
                 }
             };
+            if layered {
+                self.render_layers(&primitives, screen_descriptor, background_color, encoder);
+                return Ok(());
+            }
+
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -200,7 +243,7 @@ impl RadiantRenderManager {
                         store: true,
                     },
                 })],
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: Some(self.stencil_attachment(true)),
             });
 
             if selection {
@@ -215,13 +258,228 @@ impl RadiantRenderManager {
         Ok(())
     }
 
+    /// Renders primitives containing layer markers. Every layer is drawn into its own texture
+    /// and composited onto its parent when it ends, the result is then copied to the surface.
+    fn render_layers(
+        &mut self,
+        primitives: &[ClippedPrimitive],
+        screen_descriptor: &ScreenDescriptor,
+        background_color: wgpu::Color,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let size = screen_descriptor.size_in_pixels;
+        let max_depth = primitives
+            .iter()
+            .fold((0, 0), |(depth, max_depth), primitive| {
+                match RadiantLayerMarker::from_primitive(primitive) {
+                    Some(RadiantLayerMarker::Begin) => (depth + 1, max_depth.max(depth + 1)),
+                    Some(RadiantLayerMarker::End { .. }) => (depth.max(1) - 1, max_depth),
+                    None => (depth, max_depth),
+                }
+            })
+            .1;
+        let fits = |texture: &RadiantLayerTexture| {
+            let extent = texture.texture.size();
+            extent.width == size[0] && extent.height == size[1]
+        };
+        if !self.layer_textures.iter().all(fits) {
+            self.layer_textures.clear();
+        }
+        while self.layer_textures.len() <= max_depth {
+            self.layer_textures.push(RadiantLayerTexture::new(
+                &self.device,
+                self.config.format,
+                size,
+            ));
+        }
+        if !self.backdrop_texture.as_ref().is_some_and(fits) {
+            self.backdrop_texture = Some(RadiantLayerTexture::new(
+                &self.device,
+                self.config.format,
+                size,
+            ));
+        }
+        let has_effects = primitives.iter().any(|primitive| {
+            matches!(
+                RadiantLayerMarker::from_primitive(primitive),
+                Some(RadiantLayerMarker::End { style, .. }) if !style.effects().is_empty()
+            )
+        });
+        if !self.effect_textures.iter().all(fits) {
+            self.effect_textures.clear();
+        }
+        while has_effects && self.effect_textures.len() < 2 {
+            self.effect_textures.push(RadiantLayerTexture::new(
+                &self.device,
+                self.config.format,
+                size,
+            ));
+        }
+
+        self.clear_layer(encoder, 0, background_color);
+
+        let mut depth = 0;
+        let mut start = 0;
+        let mut state = RadiantRenderState::default();
+        for (index, primitive) in primitives.iter().enumerate() {
+            let Some(marker) = RadiantLayerMarker::from_primitive(primitive) else {
+                continue;
+            };
+            self.draw_layer(
+                encoder,
+                depth,
+                &primitives[start..index],
+                &mut state,
+                screen_descriptor,
+            );
+            start = index + 1;
+
+            match marker {
+                RadiantLayerMarker::Begin => {
+                    depth += 1;
+                    self.clear_layer(encoder, depth, wgpu::Color::TRANSPARENT);
+                }
+                RadiantLayerMarker::End { style, bounds } => {
+                    if depth == 0 {
+                        continue;
+                    }
+                    if !style.effects().is_empty() {
+                        self.compositor.apply_effects(
+                            &self.device,
+                            encoder,
+                            &RadiantEffectTargets {
+                                layer: &self.layer_textures[depth],
+                                parent: &self.layer_textures[depth - 1],
+                                backdrop: self.backdrop_texture.as_ref().unwrap(),
+                                scratch: [&self.effect_textures[0], &self.effect_textures[1]],
+                            },
+                            &style,
+                            bounds,
+                            screen_descriptor,
+                        );
+                    }
+                    self.compositor.composite(
+                        &self.device,
+                        encoder,
+                        &self.layer_textures[depth],
+                        &self.layer_textures[depth - 1],
+                        self.backdrop_texture.as_ref().unwrap(),
+                        &style,
+                    );
+                    depth -= 1;
+                }
+            }
+        }
+        self.draw_layer(
+            encoder,
+            depth,
+            &primitives[start..],
+            &mut state,
+            screen_descriptor,
+        );
+
+        self.compositor.blit(
+            &self.device,
+            encoder,
+            &self.layer_textures[0],
+            self.current_view.as_ref().unwrap(),
+        );
+    }
+
+    /// Clears the layer at `depth`, and the stencil buffer along with the bottom layer.
+    fn clear_layer(&self, encoder: &mut wgpu::CommandEncoder, depth: usize, color: wgpu::Color) {
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Clear Layer Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.layer_textures[depth].view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(color),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: (depth == 0).then(|| self.stencil_attachment(true)),
+        });
+    }
+
+    /// Draws `primitives` into the layer at `depth`, continuing from `state`.
+    fn draw_layer(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        depth: usize,
+        primitives: &[ClippedPrimitive],
+        state: &mut RadiantRenderState,
+        screen_descriptor: &ScreenDescriptor,
+    ) {
+        if primitives.is_empty() {
+            return;
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Layer Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.layer_textures[depth].view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: Some(self.stencil_attachment(false)),
+        });
+        self.renderer
+            .render_range(&mut render_pass, screen_descriptor, primitives, state);
+    }
+
+    fn ensure_stencil_texture(&mut self, size: [u32; 2]) {
+        if let Some(texture) = &self.stencil_texture {
+            let extent = texture.size();
+            if extent.width == size[0] && extent.height == size[1] {
+                return;
+            }
+        }
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("radiant_stencil_texture"),
+            size: wgpu::Extent3d {
+                width: size[0].max(1),
+                height: size[1].max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: RADIANT_STENCIL_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        self.stencil_texture_view = Some(texture.create_view(&Default::default()));
+        self.stencil_texture = Some(texture);
+    }
+
+    fn stencil_attachment(&self, clear: bool) -> wgpu::RenderPassDepthStencilAttachment<'_> {
+        wgpu::RenderPassDepthStencilAttachment {
+            view: self.stencil_texture_view.as_ref().unwrap(),
+            depth_ops: None,
+            stencil_ops: Some(wgpu::Operations {
+                load: if clear {
+                    wgpu::LoadOp::Clear(0)
+                } else {
+                    wgpu::LoadOp::Load
+                },
+                store: true,
+            }),
+        }
+    }
+
+    /// Renders picking primitives and returns the id written under `mouse_position`, if
+    /// any.
     pub async fn render_offscreen(
         &mut self,
         primitives: Vec<ClippedPrimitive>,
         screen_descriptor: &ScreenDescriptor,
         selection: bool,
         mouse_position: [f32; 2],
-    ) -> Result<Option<Uuid>, wgpu::SurfaceError> {
+    ) -> Result<Option<u32>, wgpu::SurfaceError> {
         let texture_width = screen_descriptor.size_in_pixels[0];
         let texture_height = screen_descriptor.size_in_pixels[1];
 
@@ -260,7 +518,7 @@ impl RadiantRenderManager {
 
         let submission_id = self.queue.submit(Some(encoder.finish()));
 
-        let color: Color32;
+        let id: u32;
 
         // We need to scope the mapping variables so that we can
         // unmap the buffer
@@ -278,15 +536,14 @@ impl RadiantRenderManager {
 
             let data = buffer_slice.get_mapped_range();
 
-            let posx: u32 = (mouse_position[0] * screen_descriptor.pixels_per_point) as u32;
-            let posy: u32 = (mouse_position[1] * screen_descriptor.pixels_per_point) as u32;
+            let [posx, posy] = screen_descriptor.position_in_pixels(mouse_position);
+            let (posx, posy) = (posx as u32, posy as u32);
             let index = (posy * padded_bytes_per_row + posx * 4) as usize;
 
-            color = Color32::from_rgb(
-                *data.get(index).unwrap(),
-                *data.get(index + 1).unwrap(),
-                *data.get(index + 2).unwrap(),
-            );
+            id = data
+                .get(index..index + 4)
+                .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .unwrap_or_default();
 
             // use image::{ImageBuffer, Rgba};
             // let buffer =
@@ -299,6 +556,6 @@ impl RadiantRenderManager {
             buffer.unmap();
         }
 
-        Ok(get_node_for_color(color))
+        Ok((id != 0).then_some(id))
     }
 }
@@ -1,39 +1,75 @@
-use crate::ScreenDescriptor;
+use crate::{RadiantMaskMarker, RadiantPrimitiveBatch, ScreenDescriptor};
 use epaint::emath::NumExt;
-use epaint::{Primitive, Vertex};
+use epaint::{Mesh, Primitive, Vertex};
+use std::any::Any;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::num::NonZeroU64;
 use std::ops::Range;
+use std::sync::Arc;
 use wgpu::util::DeviceExt;
 
+/// Format of the stencil buffer the renderer writes mask shapes to.
+pub const RADIANT_STENCIL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Stencil8;
+
+/// Format of the render target the ids of picked nodes are written to.
+pub const RADIANT_PICKING_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
 /// Uniform buffer used when rendering.
 #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 #[repr(C)]
 struct UniformBuffer {
     screen_size_in_points: [f32; 2],
-    // Uniform buffers need to be at least 16 bytes in WebGL.
+    // Also keeps the buffer at the 16 bytes uniform buffers need at least in WebGL.
     // See https://github.com/gfx-rs/wgpu/issues/2072
-    _padding: [u32; 2],
+    offset: [f32; 2],
 }
 
 impl PartialEq for UniformBuffer {
     fn eq(&self, other: &Self) -> bool {
-        self.screen_size_in_points == other.screen_size_in_points
+        self.screen_size_in_points == other.screen_size_in_points && self.offset == other.offset
     }
 }
 
+/// Progress of rendering primitives over several render passes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RadiantRenderState {
+    /// Number of meshes already rendered.
+    pub mesh_offset: usize,
+    /// Number of masks enclosing the next primitives.
+    pub mask_depth: u32,
+    /// Index of the mask pipeline in use while mask shapes are being rendered.
+    mask_pipeline: Option<usize>,
+}
+
 struct SlicedBuffer {
     buffer: wgpu::Buffer,
     slices: Vec<Range<usize>>,
     capacity: wgpu::BufferAddress,
 }
 
+/// Buffers of a primitive batch, kept for as long as the batch is rendered.
+struct CachedBatch {
+    /// Keeps the batch alive so that its key is not reused by another one.
+    _batch: Arc<dyn Any + Send + Sync>,
+    index_buffer: wgpu::Buffer,
+    vertex_buffer: wgpu::Buffer,
+    /// Index and vertex slices of each mesh of the batch.
+    slices: Vec<(Range<usize>, Range<usize>)>,
+    /// Whether the batch was part of the last primitives given to `update_buffers`.
+    used: bool,
+}
+
 pub struct RadiantRenderer {
     pipeline: wgpu::RenderPipeline,
+    /// Pipelines adding shapes to and removing them from the stencil buffer, available when
+    /// the output depth format has a stencil aspect.
+    mask_pipelines: Option<[wgpu::RenderPipeline; 2]>,
 
     index_buffer: SlicedBuffer,
     vertex_buffer: SlicedBuffer,
+    /// Buffers of the primitive batches, keyed by batch.
+    batches: HashMap<usize, CachedBatch>,
 
     uniform_buffer: wgpu::Buffer,
     previous_uniform_buffer_content: UniformBuffer,
@@ -61,7 +97,7 @@ impl RadiantRenderer {
             label: Some("egui_uniform_buffer"),
             contents: bytemuck::cast_slice(&[UniformBuffer {
                 screen_size_in_points: [0.0, 0.0],
-                _padding: Default::default(),
+                offset: [0.0, 0.0],
             }]),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
@@ -123,72 +159,116 @@ impl RadiantRenderer {
             push_constant_ranges: &[],
         });
 
-        let depth_stencil = output_depth_format.map(|format| wgpu::DepthStencilState {
-            format,
-            depth_write_enabled: false,
-            depth_compare: wgpu::CompareFunction::Always,
-            stencil: wgpu::StencilState::default(),
-            bias: wgpu::DepthBiasState::default(),
-        });
-
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("egui_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                entry_point: "vs_main",
-                module: &module,
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: 5 * 4,
-                    step_mode: wgpu::VertexStepMode::Vertex,
-                    // 0: vec2 position
-                    // 1: vec2 texture coordinates
-                    // 2: uint color
-                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Uint32],
-                }],
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                unclipped_depth: false,
-                conservative: false,
-                cull_mode: None,
-                front_face: wgpu::FrontFace::default(),
-                polygon_mode: wgpu::PolygonMode::default(),
-                strip_index_format: None,
-            },
-            depth_stencil,
-            multisample: wgpu::MultisampleState {
-                alpha_to_coverage_enabled: false,
-                count: msaa_samples,
-                mask: !0,
-            },
-
-            fragment: Some(wgpu::FragmentState {
-                module: &module,
-                entry_point: if output_color_format.is_srgb() {
-                    log::warn!("Detected a linear (sRGBA aware) framebuffer {:?}. egui prefers Rgba8Unorm or Bgra8Unorm", output_color_format);
-                    "fs_main_linear_framebuffer"
+        let picking = output_color_format == RADIANT_PICKING_FORMAT;
+        let vertex_entry_point = if picking { "vs_picking" } else { "vs_main" };
+        let fragment_entry_point = if picking {
+            "fs_picking"
+        } else if output_color_format.is_srgb() {
+            log::warn!("Detected a linear (sRGBA aware) framebuffer {:?}. egui prefers Rgba8Unorm or Bgra8Unorm", output_color_format);
+            "fs_main_linear_framebuffer"
+        } else {
+            "fs_main_gamma_framebuffer" // this is what we prefer
+        };
+        // Meshes are only drawn where the stencil matches the current mask depth.
+        let draw_stencil = wgpu::StencilFaceState {
+            compare: wgpu::CompareFunction::Equal,
+            fail_op: wgpu::StencilOperation::Keep,
+            depth_fail_op: wgpu::StencilOperation::Keep,
+            pass_op: wgpu::StencilOperation::Keep,
+        };
+        let create_pipeline = |entry_point, write_mask, stencil: wgpu::StencilFaceState| {
+            let depth_stencil = output_depth_format.map(|format| wgpu::DepthStencilState {
+                format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: if format.has_stencil_aspect() {
+                    wgpu::StencilState {
+                        front: stencil,
+                        back: stencil,
+                        read_mask: !0,
+                        write_mask: !0,
+                    }
                 } else {
-                    "fs_main_gamma_framebuffer" // this is what we prefer
+                    wgpu::StencilState::default()
                 },
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: output_color_format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                        alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
-                            dst_factor: wgpu::BlendFactor::One,
-                            operation: wgpu::BlendOperation::Add,
+                bias: wgpu::DepthBiasState::default(),
+            });
+
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("egui_pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    entry_point: vertex_entry_point,
+                    module: &module,
+                    buffers: &[wgpu::VertexBufferLayout {
+                        array_stride: 5 * 4,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        // 0: vec2 position
+                        // 1: vec2 texture coordinates
+                        // 2: uint color
+                        attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Uint32],
+                    }],
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    unclipped_depth: false,
+                    conservative: false,
+                    cull_mode: None,
+                    front_face: wgpu::FrontFace::default(),
+                    polygon_mode: wgpu::PolygonMode::default(),
+                    strip_index_format: None,
+                },
+                depth_stencil,
+                multisample: wgpu::MultisampleState {
+                    alpha_to_coverage_enabled: false,
+                    count: msaa_samples,
+                    mask: !0,
+                },
+
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point,
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: output_color_format,
+                        // Ids cannot be blended.
+                        blend: (!picking).then_some(wgpu::BlendState {
+                            color: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::One,
+                                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                            alpha: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::OneMinusDstAlpha,
+                                dst_factor: wgpu::BlendFactor::One,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                        }),
+                        write_mask,
+                    })],
+                }),
+                multiview: None,
+            })
+        };
+
+        let pipeline = create_pipeline(fragment_entry_point, wgpu::ColorWrites::ALL, draw_stencil);
+        let mask_pipelines = output_depth_format
+            .filter(|format| format.has_stencil_aspect())
+            .map(|_| {
+                [
+                    wgpu::StencilOperation::IncrementClamp,
+                    wgpu::StencilOperation::DecrementClamp,
+                ]
+                .map(|pass_op| {
+                    create_pipeline(
+                        if picking { "fs_picking" } else { "fs_stencil" },
+                        wgpu::ColorWrites::empty(),
+                        wgpu::StencilFaceState {
+                            pass_op,
+                            ..draw_stencil
                         },
-                    }),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            multiview: None,
-        });
+                    )
+                })
+            });
 
         const VERTEX_BUFFER_START_CAPACITY: wgpu::BufferAddress =
             (std::mem::size_of::<Vertex>() * 1024) as _;
@@ -197,6 +277,7 @@ impl RadiantRenderer {
 
         Self {
             pipeline,
+            mask_pipelines,
             vertex_buffer: SlicedBuffer {
                 buffer: create_vertex_buffer(device, VERTEX_BUFFER_START_CAPACITY),
                 slices: Vec::with_capacity(64),
@@ -211,8 +292,9 @@ impl RadiantRenderer {
             // Buffers on wgpu are zero initialized, so this is indeed its current state!
             previous_uniform_buffer_content: UniformBuffer {
                 screen_size_in_points: [0.0, 0.0],
-                _padding: [0, 0],
+                offset: [0.0, 0.0],
             },
+            batches: HashMap::default(),
             uniform_bind_group,
             texture_bind_group_layout,
             textures: HashMap::default(),
@@ -232,7 +314,7 @@ impl RadiantRenderer {
 
         let uniform_buffer_content = UniformBuffer {
             screen_size_in_points,
-            _padding: Default::default(),
+            offset: screen_descriptor.offset,
         };
         if uniform_buffer_content != self.previous_uniform_buffer_content {
             // crate::profile_scope!("update uniforms");
@@ -257,8 +339,7 @@ impl RadiantRenderer {
                         // } else {
                         //     log::warn!("Unknown paint callback: expected `egui_wgpu::Callback`");
                         // };
-                        // acc
-                        (0, 0)
+                        acc
                     }
                 }
             })
@@ -333,6 +414,65 @@ impl RadiantRenderer {
                 }
             }
         }
+
+        self.update_batches(device, paint_jobs);
+    }
+
+    /// Uploads the batches that were not rendered before, and drops the buffers of the ones
+    /// that are no longer rendered.
+    fn update_batches(&mut self, device: &wgpu::Device, paint_jobs: &[epaint::ClippedPrimitive]) {
+        for clipped_primitive in paint_jobs {
+            let Primitive::Callback(callback) = &clipped_primitive.primitive else {
+                continue;
+            };
+            let Some(batch) = callback.callback.downcast_ref::<RadiantPrimitiveBatch>() else {
+                continue;
+            };
+            let key = RadiantPrimitiveBatch::key(&callback.callback);
+            if let Some(cached) = self.batches.get_mut(&key) {
+                cached.used = true;
+                continue;
+            }
+            if batch.index_count() == 0 || batch.vertex_count() == 0 {
+                continue;
+            }
+
+            let mut indices = Vec::with_capacity(batch.index_count());
+            let mut vertices = Vec::with_capacity(batch.vertex_count());
+            let mut slices = Vec::with_capacity(batch.meshes.len());
+            for (_, mesh) in &batch.meshes {
+                let index_start = indices.len() * std::mem::size_of::<u32>();
+                let vertex_start = vertices.len() * std::mem::size_of::<Vertex>();
+                indices.extend_from_slice(&mesh.indices);
+                vertices.extend_from_slice(&mesh.vertices);
+                slices.push((
+                    index_start..indices.len() * std::mem::size_of::<u32>(),
+                    vertex_start..vertices.len() * std::mem::size_of::<Vertex>(),
+                ));
+            }
+            let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("radiant_batch_index_buffer"),
+                contents: bytemuck::cast_slice(&indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+            let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("radiant_batch_vertex_buffer"),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            self.batches.insert(
+                key,
+                CachedBatch {
+                    _batch: callback.callback.clone(),
+                    index_buffer,
+                    vertex_buffer,
+                    slices,
+                    used: true,
+                },
+            );
+        }
+        self.batches
+            .retain(|_, cached| std::mem::replace(&mut cached.used, false));
     }
 
     pub fn update_texture(
@@ -453,21 +593,37 @@ impl RadiantRenderer {
         screen_descriptor: &ScreenDescriptor,
         paint_jobs: &'a [epaint::ClippedPrimitive],
     ) {
-        let pixels_per_point = screen_descriptor.pixels_per_point;
+        self.render_range(
+            render_pass,
+            screen_descriptor,
+            paint_jobs,
+            &mut RadiantRenderState::default(),
+        );
+    }
+
+    /// Renders a part of the primitives given to `update_buffers`, continuing from `state`
+    /// which is updated to follow `paint_jobs`.
+    pub fn render_range<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        screen_descriptor: &ScreenDescriptor,
+        paint_jobs: &'a [epaint::ClippedPrimitive],
+        state: &mut RadiantRenderState,
+    ) {
         let size_in_pixels = screen_descriptor.size_in_pixels;
 
         // Whether or not we need to reset the render pass because a paint callback has just
         // run.
         let mut needs_reset = true;
 
-        let mut index_buffer_slices = self.index_buffer.slices.iter();
-        let mut vertex_buffer_slices = self.vertex_buffer.slices.iter();
+        let mut index_buffer_slices = self.index_buffer.slices.iter().skip(state.mesh_offset);
+        let mut vertex_buffer_slices = self.vertex_buffer.slices.iter().skip(state.mesh_offset);
 
-        for epaint::ClippedPrimitive {
-            clip_rect,
-            primitive,
-        } in paint_jobs
-        {
+        for clipped_primitive in paint_jobs {
+            let epaint::ClippedPrimitive {
+                clip_rect,
+                primitive,
+            } = clipped_primitive;
             if needs_reset {
                 render_pass.set_viewport(
                     0.0,
@@ -477,20 +633,71 @@ impl RadiantRenderer {
                     0.0,
                     1.0,
                 );
-                render_pass.set_pipeline(&self.pipeline);
+                match (&self.mask_pipelines, state.mask_pipeline) {
+                    (Some(mask_pipelines), Some(index)) => {
+                        render_pass.set_pipeline(&mask_pipelines[index])
+                    }
+                    _ => render_pass.set_pipeline(&self.pipeline),
+                }
+                if self.mask_pipelines.is_some() {
+                    render_pass.set_stencil_reference(state.mask_depth);
+                }
                 render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
                 needs_reset = false;
             }
 
+            if let Some(marker) = RadiantMaskMarker::from_primitive(clipped_primitive) {
+                match marker {
+                    RadiantMaskMarker::Begin => state.mask_pipeline = Some(0),
+                    RadiantMaskMarker::Content => {
+                        state.mask_depth += 1;
+                        state.mask_pipeline = None;
+                    }
+                    RadiantMaskMarker::End => state.mask_pipeline = Some(1),
+                    RadiantMaskMarker::Restore => {
+                        state.mask_depth = state.mask_depth.saturating_sub(1);
+                        state.mask_pipeline = None;
+                    }
+                }
+                needs_reset = true;
+                continue;
+            }
+
+            if let Some((key, batch)) = RadiantPrimitiveBatch::from_primitive(clipped_primitive) {
+                let hidden = state.mask_pipeline.is_some() && self.mask_pipelines.is_none();
+                let Some(cached) = self.batches.get(&key).filter(|_| !hidden) else {
+                    continue;
+                };
+                for ((clip_rect, mesh), (index_slice, vertex_slice)) in
+                    batch.meshes.iter().zip(&cached.slices)
+                {
+                    let rect = ScissorRect::new(clip_rect, screen_descriptor);
+                    if rect.width == 0 || rect.height == 0 {
+                        continue;
+                    }
+                    render_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+                    self.draw_mesh(
+                        render_pass,
+                        mesh,
+                        (&cached.index_buffer, index_slice),
+                        (&cached.vertex_buffer, vertex_slice),
+                    );
+                }
+                continue;
+            }
+
             {
-                let rect = ScissorRect::new(clip_rect, pixels_per_point, size_in_pixels);
+                let rect = ScissorRect::new(clip_rect, screen_descriptor);
 
-                if rect.width == 0 || rect.height == 0 {
+                // Mask shapes are skipped when there is no stencil buffer to write them to.
+                let hidden = state.mask_pipeline.is_some() && self.mask_pipelines.is_none();
+                if rect.width == 0 || rect.height == 0 || hidden {
                     // Skip rendering zero-sized clip areas.
                     if let Primitive::Mesh(_) = primitive {
                         // If this is a mesh, we need to advance the index and vertex buffer iterators:
                         index_buffer_slices.next().unwrap();
                         vertex_buffer_slices.next().unwrap();
+                        state.mesh_offset += 1;
                     }
                     continue;
                 }
@@ -502,25 +709,14 @@ impl RadiantRenderer {
                 Primitive::Mesh(mesh) => {
                     let index_buffer_slice = index_buffer_slices.next().unwrap();
                     let vertex_buffer_slice = vertex_buffer_slices.next().unwrap();
-
-                    if let Some((_texture, bind_group)) = self.textures.get(&mesh.texture_id) {
-                        render_pass.set_bind_group(1, bind_group, &[]);
-                        render_pass.set_index_buffer(
-                            self.index_buffer.buffer.slice(
-                                index_buffer_slice.start as u64..index_buffer_slice.end as u64,
-                            ),
-                            wgpu::IndexFormat::Uint32,
-                        );
-                        render_pass.set_vertex_buffer(
-                            0,
-                            self.vertex_buffer.buffer.slice(
-                                vertex_buffer_slice.start as u64..vertex_buffer_slice.end as u64,
-                            ),
-                        );
-                        render_pass.draw_indexed(0..mesh.indices.len() as u32, 0, 0..1);
-                    } else {
-                        log::warn!("Missing texture: {:?}", mesh.texture_id);
-                    }
+                    state.mesh_offset += 1;
+
+                    self.draw_mesh(
+                        render_pass,
+                        mesh,
+                        (&self.index_buffer.buffer, index_buffer_slice),
+                        (&self.vertex_buffer.buffer, vertex_buffer_slice),
+                    );
                 }
                 Primitive::Callback(_callback) => {
                     // let Some(cbfn) = callback.callback.downcast_ref::<Callback>() else {
@@ -573,6 +769,29 @@ impl RadiantRenderer {
 
         render_pass.set_scissor_rect(0, 0, size_in_pixels[0], size_in_pixels[1]);
     }
+
+    fn draw_mesh<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        mesh: &Mesh,
+        (index_buffer, index_slice): (&'a wgpu::Buffer, &Range<usize>),
+        (vertex_buffer, vertex_slice): (&'a wgpu::Buffer, &Range<usize>),
+    ) {
+        if let Some((_texture, bind_group)) = self.textures.get(&mesh.texture_id) {
+            render_pass.set_bind_group(1, bind_group, &[]);
+            render_pass.set_index_buffer(
+                index_buffer.slice(index_slice.start as u64..index_slice.end as u64),
+                wgpu::IndexFormat::Uint32,
+            );
+            render_pass.set_vertex_buffer(
+                0,
+                vertex_buffer.slice(vertex_slice.start as u64..vertex_slice.end as u64),
+            );
+            render_pass.draw_indexed(0..mesh.indices.len() as u32, 0, 0..1);
+        } else {
+            log::warn!("Missing texture: {:?}", mesh.texture_id);
+        }
+    }
 }
 
 fn create_sampler(
@@ -617,20 +836,24 @@ fn create_index_buffer(device: &wgpu::Device, size: u64) -> wgpu::Buffer {
     })
 }
 /// A Rect in physical pixel space, used for setting clipping rectangles.
-struct ScissorRect {
-    x: u32,
-    y: u32,
-    width: u32,
-    height: u32,
+pub(crate) struct ScissorRect {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
 }
 
 impl ScissorRect {
-    fn new(clip_rect: &epaint::Rect, pixels_per_point: f32, target_size: [u32; 2]) -> Self {
+    pub(crate) fn new(clip_rect: &epaint::Rect, screen_descriptor: &ScreenDescriptor) -> Self {
+        let pixels_per_point = screen_descriptor.pixels_per_point;
+        let target_size = screen_descriptor.size_in_pixels;
+        let [offset_x, offset_y] = screen_descriptor.offset;
+
         // Transform clip rect to physical pixels:
-        let clip_min_x = pixels_per_point * clip_rect.min.x;
-        let clip_min_y = pixels_per_point * clip_rect.min.y;
-        let clip_max_x = pixels_per_point * clip_rect.max.x;
-        let clip_max_y = pixels_per_point * clip_rect.max.y;
+        let clip_min_x = pixels_per_point * (clip_rect.min.x - offset_x);
+        let clip_min_y = pixels_per_point * (clip_rect.min.y - offset_y);
+        let clip_max_x = pixels_per_point * (clip_rect.max.x - offset_x);
+        let clip_max_y = pixels_per_point * (clip_rect.max.y - offset_y);
 
         // Round to integer:
         let clip_min_x = clip_min_x.round() as u32;
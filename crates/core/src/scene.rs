@@ -1,14 +1,27 @@
 use std::sync::Arc;
 
 use crate::{
-    ColorComponent, RadiantDocumentNode, RadiantInteractionManager, RadiantNode,
-    RadiantRenderManager, RadiantSceneMessage, RadiantSceneResponse, RadiantTessellatable,
-    RadiantTextureManager, RadiantToolManager, ScreenDescriptor, SelectionTool, TransformComponent,
+    is_interaction_handle, ColorComponent, LayerComponent, LayoutItemComponent, RadiantCamera,
+    RadiantDocumentNode, RadiantInteractionManager, RadiantKeyChord, RadiantKeymap,
+    RadiantLineNode, RadiantModifiers, RadiantNode, RadiantPickingListener,
+    RadiantPickingRegistry, RadiantPointerTarget, RadiantRectangleNode, RadiantRenderManager,
+    RadiantSceneMessage, RadiantSceneResponse, RadiantStyleTarget, RadiantTessellatable,
+    RadiantTextureManager, RadiantToolManager, ScreenDescriptor, SelectionTool,
+    StrokeComponent, TransformComponent,
+};
+use epaint::{
+    text::FontDefinitions, ClippedPrimitive, ClippedShape, Fonts, Rect, TessellationOptions,
+    TextureId,
 };
-use epaint::{text::FontDefinitions, ClippedPrimitive, Fonts, TextureId};
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use uuid::Uuid;
 
+/// Distance in screen points around the outline of a node that still selects it.
+const HIT_TEST_TOLERANCE: f32 = 4.0;
+/// Scale glyphs are rasterized at most at when zooming in, so that they fit the font atlas,
+/// which grows up to the largest texture the device supports.
+const MAX_FONT_PIXELS_PER_POINT: f32 = 16.0;
+
 pub struct RadiantScene<M, N: RadiantNode> {
     pub document: Arc<RwLock<RadiantDocumentNode<N>>>,
 
@@ -19,6 +32,19 @@ pub struct RadiantScene<M, N: RadiantNode> {
     pub tool_manager: RadiantToolManager<M>,
     pub interaction_manager: RadiantInteractionManager<M>,
     pub texture_manager: RadiantTextureManager,
+    /// Ids the nodes of the document are rendered with when picking, freed by a listener of
+    /// the document as nodes are removed.
+    pub picking: Arc<RwLock<RadiantPickingRegistry>>,
+    /// Node under the mouse.
+    pub hovered_node_id: Option<Uuid>,
+    /// Modifier keys held, set by the view and used when dragging interaction handles.
+    pub modifiers: RadiantModifiers,
+    /// Messages bound to the key chords that the selected node does not consume.
+    pub keymap: RadiantKeymap,
+    /// Part of the document shown, which the screen descriptor follows.
+    pub camera: RadiantCamera,
+    /// Zoom level the nodes were last tessellated for.
+    tessellated_zoom_level: i32,
 }
 
 impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode>
@@ -40,12 +66,21 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
         screen_descriptor: ScreenDescriptor,
     ) -> Self {
         let font_definitions = FontDefinitions::default();
-        let fonts_manager = Fonts::new(screen_descriptor.pixels_per_point, 1600, font_definitions);
+        let fonts_manager = Fonts::new(
+            screen_descriptor
+                .pixels_per_point
+                .min(MAX_FONT_PIXELS_PER_POINT),
+            1600,
+            font_definitions,
+        );
         let texture_manager = RadiantTextureManager::default();
         let render_manager = RadiantRenderManager::new(config, surface, device, queue, None);
+        let picking = Arc::new(RwLock::new(RadiantPickingRegistry::new()));
+        let mut document = RadiantDocumentNode::new();
+        document.add_listener(Box::new(RadiantPickingListener::new(picking.clone())));
 
         Self {
-            document: Arc::new(RwLock::new(RadiantDocumentNode::new())),
+            document: Arc::new(RwLock::new(document)),
 
             screen_descriptor,
 
@@ -54,6 +89,12 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
             tool_manager: RadiantToolManager::new(0u32, Box::new(SelectionTool::new())),
             interaction_manager: RadiantInteractionManager::new(),
             texture_manager,
+            picking,
+            hovered_node_id: None,
+            modifiers: RadiantModifiers::default(),
+            keymap: RadiantKeymap::default(),
+            camera: RadiantCamera::default(),
+            tessellated_zoom_level: RadiantCamera::default().zoom_level(),
         }
     }
 
@@ -70,8 +111,13 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
     }
 
     pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        let pixels_per_point = self
+            .screen_descriptor
+            .pixels_per_point
+            .min(MAX_FONT_PIXELS_PER_POINT);
+        let max_texture_side = self.render_manager.device.limits().max_texture_dimension_2d;
         self.fonts_manager
-            .begin_frame(self.screen_descriptor.pixels_per_point, 1024);
+            .begin_frame(pixels_per_point, max_texture_side as usize);
         if let Some(font_image_delta) = self.fonts_manager.font_image_delta() {
             self.texture_manager
                 .0
@@ -87,12 +133,64 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
             .render(primitives, &self.screen_descriptor, false)
     }
 
-    pub async fn select(&mut self, mouse_position: [f32; 2]) -> Option<Uuid> {
-        let primitives = self.get_primitives(true);
-        self.render_manager
-            .render_offscreen(primitives, &self.screen_descriptor, true, mouse_position)
-            .await
-            .unwrap()
+    /// Topmost interaction handle or node under `position`, found from the geometry of the
+    /// nodes so that no rendering is needed.
+    pub fn select(&self, position: [f32; 2]) -> Option<Uuid> {
+        self.interaction_manager
+            .hit_test(position, self.hit_test_tolerance())
+            .or_else(|| self.document().hit_test(position, self.hit_test_tolerance()))
+    }
+
+    /// Topmost node of the document under `position`, unless a handle of the interactions
+    /// covers it.
+    pub fn node_at(&self, position: [f32; 2]) -> Option<Uuid> {
+        if self
+            .interaction_manager
+            .hit_test(position, self.hit_test_tolerance())
+            .is_some()
+        {
+            return None;
+        }
+        self.document().hit_test(position, self.hit_test_tolerance())
+    }
+
+    /// Node a press on `id` acts on, which is the selected node for interaction handles,
+    /// with its current transform.
+    pub fn pointer_target(&self, id: Uuid) -> Option<RadiantPointerTarget> {
+        let document = self.document();
+        let id = if is_interaction_handle(id) {
+            document.selected_node_id?
+        } else {
+            id
+        };
+        let node = document.get_node(id)?;
+        let transform = node.get_component::<TransformComponent>()?;
+        Some(RadiantPointerTarget {
+            id,
+            position: transform.position().into(),
+            scale: transform.scale().into(),
+        })
+    }
+
+    /// Hit test tolerance in document points, so that it stays the same on screen.
+    fn hit_test_tolerance(&self) -> f32 {
+        self.screen_descriptor.document_length(HIT_TEST_TOLERANCE)
+    }
+
+    /// Node of the document under `position`, found by rendering the nodes with their
+    /// picking ids. Slower than `select`, which does not render anything.
+    pub async fn pick(&mut self, position: [f32; 2]) -> Result<Option<Uuid>, wgpu::SurfaceError> {
+        // The document is locked before the registry, as when its listeners free ids.
+        let primitives = self.document.write().tessellate_picking(
+            &self.screen_descriptor,
+            &self.fonts_manager,
+            &mut self.picking.write(),
+        );
+        let id = self
+            .render_manager
+            .render_offscreen(primitives, &self.screen_descriptor, true, position)
+            .await?;
+        Ok(id.and_then(|id| self.picking.read().node(id)))
     }
 
     fn get_primitives(&mut self, selection: bool) -> Vec<ClippedPrimitive> {
@@ -102,6 +200,15 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
             &self.fonts_manager,
         );
 
+        // The selected node is already outlined by its bounding box.
+        let hover_rect = self
+            .hovered_node_id
+            .filter(|id| self.document().selected_node_id != Some(*id))
+            .and_then(|id| self.document().get_node(id).map(|node| node.get_bounding_rect()));
+        self.interaction_manager
+            .hover_interaction
+            .set_rect(hover_rect, &self.screen_descriptor);
+
         let mut p2 = self.interaction_manager.tessellate(
             selection,
             &self.screen_descriptor,
@@ -109,6 +216,21 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
         );
         primitives.append(&mut p2);
 
+        let overlay = self.tool_manager.active_tool().overlay();
+        if !selection && !overlay.is_empty() {
+            let mut p3 = epaint::tessellator::tessellate_shapes(
+                self.screen_descriptor.pixels_per_point,
+                TessellationOptions::default(),
+                [1, 1],
+                vec![],
+                overlay
+                    .into_iter()
+                    .map(|shape| ClippedShape(Rect::EVERYTHING, shape))
+                    .collect(),
+            );
+            primitives.append(&mut p3);
+        }
+
         primitives
     }
 }
@@ -120,6 +242,7 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
         &mut self,
         message: RadiantSceneMessage,
     ) -> Option<RadiantSceneResponse<M, N>> {
+        self.document_mut().begin_edit(message.edit_gesture());
         match message {
             RadiantSceneMessage::AddArtboard {} => {
                 self.document_mut().add_artboard();
@@ -128,22 +251,55 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                 self.document_mut().set_active_artboard(id);
             }
             RadiantSceneMessage::SelectNode { id } => {
-                self.document_mut().select(id);
-                if let Some(id) = id {
-                    if !self.interaction_manager.is_interaction(id) {
-                        if let Some(mut node) = self.document.write().get_node_mut(id) {
-                            node.tessellate(false, &self.screen_descriptor, &self.fonts_manager);
-                            let response = RadiantSceneResponse::Selected { node: node.clone() };
-                            self.interaction_manager
-                                .enable_interactions(node, &self.screen_descriptor);
-                            return Some(response);
-                        } else {
-                            self.interaction_manager.disable_interactions();
-                        }
-                    }
-                } else {
+                return self.select_node(id);
+            }
+            RadiantSceneMessage::RemoveNode { id } => {
+                if self.document().selected_node_id == Some(id) {
                     self.interaction_manager.disable_interactions();
                 }
+                if let Some(mut node) = self.document_mut().remove(id) {
+                    node.detach();
+                }
+                if self.hovered_node_id == Some(id) {
+                    self.hovered_node_id = None;
+                    return Some(RadiantSceneResponse::Hovered { id: None });
+                }
+            }
+            RadiantSceneMessage::RemoveSelectedNode {} => {
+                let id = self.document().selected_node_id;
+                if let Some(id) = id {
+                    return self.handle_message(RadiantSceneMessage::RemoveNode { id });
+                }
+            }
+            RadiantSceneMessage::DuplicateNode { id, new_id } => {
+                if self.interaction_manager.is_interaction(id) {
+                    return None;
+                }
+                let node = self.document().get_node(id).map(|node| node.clone());
+                if let Some(mut node) = node {
+                    node.set_id(new_id);
+                    node.set_needs_tessellation(true);
+                    self.add(node);
+                    return self.select_node(Some(new_id));
+                }
+            }
+            RadiantSceneMessage::EditNode { id } => {
+                if let Some(RadiantSceneResponse::Selected { node }) = self.select_node(Some(id)) {
+                    return Some(RadiantSceneResponse::Editing { node });
+                }
+            }
+            RadiantSceneMessage::OpenContextMenu { id, position } => {
+                let id = id.filter(|id| !self.interaction_manager.is_interaction(*id));
+                if id.is_some() {
+                    self.select_node(id);
+                }
+                return Some(RadiantSceneResponse::ContextMenu { id, position });
+            }
+            RadiantSceneMessage::HoverNode { id } => {
+                if self.hovered_node_id != id {
+                    self.hovered_node_id = id;
+                    return Some(RadiantSceneResponse::Hovered { id });
+                }
             }
             RadiantSceneMessage::TransformNode {
                 id,
@@ -151,39 +307,58 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                 scale,
             } => {
                 if self.interaction_manager.is_interaction(id) {
-                    if let Some(message) =
-                        self.interaction_manager.handle_interaction(message.into())
+                    if let Some(message) = self
+                        .interaction_manager
+                        .handle_interaction(message.into(), self.modifiers)
                     {
                         return Some(RadiantSceneResponse::Message { message });
                     }
-                } else if let Some(mut node) = self.document.write().get_node_mut(id) {
-                    if let Some(component) = node.get_component_mut::<TransformComponent>() {
-                        component.transform_xy(&position.into());
-                        component.transform_scale(&scale.into());
-
-                        let response = RadiantSceneResponse::TransformUpdated {
-                            id,
-                            position: component.position().into(),
-                            scale: component.scale().into(),
-                        };
-
-                        node.set_needs_tessellation(true);
-                        self.interaction_manager
-                            .update_interactions(node, &self.screen_descriptor);
-
-                        return Some(response);
-                    }
+                } else {
+                    let transform = self.document_mut().transform_node(id, position, scale)?;
+                    return self.transform_updated(id, transform);
                 }
             }
+            RadiantSceneMessage::NudgeSelectedNode { delta } => {
+                let (id, transform) = self.document_mut().nudge_selected_node(delta)?;
+                return self.transform_updated(id, transform);
+            }
             RadiantSceneMessage::SetTransform {
                 id,
                 position,
                 scale,
             } => {
+                let mut document = self.document.write();
+                let mut node = document.get_node_mut(id)?;
+                if let Some(component) = node.get_component_mut::<TransformComponent>() {
+                    component.set_position(&position.into());
+                    component.set_scale(&scale.into());
+                    node.set_needs_tessellation(true);
+
+                    self.interaction_manager
+                        .update_interactions(node, &self.screen_descriptor);
+                    document.reindex(id);
+                }
+            }
+            RadiantSceneMessage::SetEndpoints { id, start, end } => {
+                let updated = match self.document_mut().get_node_mut(id) {
+                    Some(mut node) => match node.downcast_mut::<RadiantLineNode>() {
+                        Some(line) => {
+                            line.set_endpoints(start, end);
+                            node.set_needs_tessellation(true);
+                            true
+                        }
+                        None => false,
+                    },
+                    None => false,
+                };
+                if updated {
+                    self.update_interactions(id);
+                }
+            }
+            RadiantSceneMessage::SetCornerRadius { id, corner_radius } => {
                 if let Some(mut node) = self.document.write().get_node_mut(id) {
-                    if let Some(component) = node.get_component_mut::<TransformComponent>() {
-                        component.set_position(&position.into());
-                        component.set_scale(&scale.into());
+                    if let Some(rectangle) = node.downcast_mut::<RadiantRectangleNode>() {
+                        rectangle.set_corner_radius(corner_radius);
                         node.set_needs_tessellation(true);
 
                         self.interaction_manager
@@ -195,34 +370,338 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                 if let Some(mut node) = self.document_mut().get_node_mut(id) {
                     if let Some(component) = node.get_component_mut::<ColorComponent>() {
                         component.set_fill_color(fill_color);
+                        node.base_mut().fill = None;
+                        node.base_mut().style_mut().set_style(RadiantStyleTarget::Fill, None);
                         node.set_needs_tessellation(true);
                     }
                 }
             }
+            RadiantSceneMessage::SetFill { id, paint } => {
+                if let Some(mut node) = self.document_mut().get_node_mut(id) {
+                    node.base_mut().set_fill_paint(paint);
+                    node.base_mut().style_mut().set_style(RadiantStyleTarget::Fill, None);
+                    node.set_needs_tessellation(true);
+                }
+            }
             RadiantSceneMessage::SetStrokeColor { id, stroke_color } => {
                 if let Some(mut node) = self.document_mut().get_node_mut(id) {
                     if let Some(component) = node.get_component_mut::<ColorComponent>() {
                         component.set_stroke_color(stroke_color);
+                        node.base_mut().style_mut().set_style(RadiantStyleTarget::Stroke, None);
                         node.set_needs_tessellation(true);
                     }
                 }
             }
+            RadiantSceneMessage::SetStroke { id, stroke } => {
+                if let Some(mut node) = self.document_mut().get_node_mut(id) {
+                    if let Some(component) = node.get_component_mut::<StrokeComponent>() {
+                        *component = stroke;
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::SetOpacity { id, opacity } => {
+                let mut document = self.document_mut();
+                if let Some(artboard) = document.artboards.get_mut(&id) {
+                    artboard.base.layer_mut().set_opacity(opacity);
+                    artboard.set_needs_tessellation(true);
+                } else if let Some(mut node) = document.get_node_mut(id) {
+                    if let Some(component) = node.get_component_mut::<LayerComponent>() {
+                        component.set_opacity(opacity);
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::SetBlendMode { id, blend_mode } => {
+                let mut document = self.document_mut();
+                if let Some(artboard) = document.artboards.get_mut(&id) {
+                    artboard.base.layer_mut().set_blend_mode(blend_mode);
+                    artboard.set_needs_tessellation(true);
+                } else if let Some(mut node) = document.get_node_mut(id) {
+                    if let Some(component) = node.get_component_mut::<LayerComponent>() {
+                        component.set_blend_mode(blend_mode);
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::SetEffects { id, effects } => {
+                let mut document = self.document_mut();
+                if let Some(artboard) = document.artboards.get_mut(&id) {
+                    artboard.base.layer_mut().set_effects(effects);
+                    artboard.set_needs_tessellation(true);
+                } else if let Some(mut node) = document.get_node_mut(id) {
+                    if let Some(component) = node.get_component_mut::<LayerComponent>() {
+                        component.set_effects(effects);
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::SetMask { id, mask } => {
+                if let Some(mut node) = self.document_mut().get_node_mut(id) {
+                    if let Some(component) = node.get_component_mut::<LayerComponent>() {
+                        component.set_mask(mask);
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::SetClipContent { id, clip_content } => {
+                // Artboards are the only groups nodes can be added to.
+                match self.document_mut().artboards.get_mut(&id) {
+                    Some(artboard) => {
+                        artboard.clip_content = clip_content;
+                        artboard.set_needs_tessellation(true);
+                    }
+                    None => log::warn!("Cannot clip the content of {}, not an artboard", id),
+                }
+            }
+            RadiantSceneMessage::SetAutoLayout { id, auto_layout } => {
+                // Artboards are the only groups nodes can be added to.
+                match self.document_mut().artboards.get_mut(&id) {
+                    Some(artboard) => {
+                        artboard.auto_layout = auto_layout;
+                        artboard.set_needs_tessellation(true);
+                    }
+                    None => log::warn!("Cannot lay out the children of {}, not an artboard", id),
+                }
+            }
+            RadiantSceneMessage::SetLayoutSizing { id, sizing } => {
+                if let Some(mut node) = self.document_mut().get_node_mut(id) {
+                    if let Some(component) = node.get_component_mut::<LayoutItemComponent>() {
+                        component.set_sizing(sizing);
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::SetConstraints { id, constraints } => {
+                if let Some(mut node) = self.document_mut().get_node_mut(id) {
+                    if let Some(component) = node.get_component_mut::<LayoutItemComponent>() {
+                        component.set_constraints(constraints);
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::SetComponent {
+                id,
+                type_tag,
+                value,
+            } => {
+                let registry = self.document().registry.clone();
+                if let Some(mut node) = self.document_mut().get_node_mut(id) {
+                    match node.base_mut().components.set_value(&registry, &type_tag, value) {
+                        Ok(()) => node.set_needs_tessellation(true),
+                        Err(error) => log::error!("Invalid {} component: {}", type_tag, error),
+                    }
+                }
+            }
+            RadiantSceneMessage::RemoveComponent { id, type_tag } => {
+                if let Some(mut node) = self.document_mut().get_node_mut(id) {
+                    if node.base_mut().components.remove_value(&type_tag) {
+                        node.set_needs_tessellation(true);
+                    }
+                }
+            }
+            RadiantSceneMessage::RenameNode { id, name } => {
+                let mut document = self.document_mut();
+                if let Some(artboard) = document.artboards.get_mut(&id) {
+                    artboard.base.name = name;
+                    artboard.set_needs_tessellation(true);
+                } else if let Some(mut node) = document.get_node_mut(id) {
+                    node.base_mut().name = name;
+                    node.set_needs_tessellation(true);
+                }
+            }
+            RadiantSceneMessage::SetVisible { id, visible } => {
+                let mut document = self.document_mut();
+                if let Some(artboard) = document.artboards.get_mut(&id) {
+                    artboard.base.visible = visible;
+                    artboard.set_needs_tessellation(true);
+                } else if let Some(mut node) = document.get_node_mut(id) {
+                    node.base_mut().visible = visible;
+                    node.set_needs_tessellation(true);
+                }
+                drop(document);
+                return self.release_unpickable();
+            }
+            RadiantSceneMessage::SetLocked { id, locked } => {
+                let mut document = self.document_mut();
+                if let Some(artboard) = document.artboards.get_mut(&id) {
+                    artboard.base.locked = locked;
+                    artboard.set_needs_tessellation(true);
+                } else if let Some(mut node) = document.get_node_mut(id) {
+                    node.base_mut().locked = locked;
+                    node.set_needs_tessellation(true);
+                }
+                drop(document);
+                return self.release_unpickable();
+            }
+            RadiantSceneMessage::ResizeArtboard { id, position, size } => {
+                if let Some(artboard) = self.document_mut().artboards.get_mut(&id) {
+                    artboard.resize(position, size);
+                }
+            }
+            RadiantSceneMessage::CreateSymbol { id, name, node_ids } => {
+                let id = id.unwrap_or(Uuid::new_v4());
+                self.document_mut().create_symbol(id, name, node_ids);
+            }
+            RadiantSceneMessage::CreateStyle { id, name, value } => {
+                let id = id.unwrap_or(Uuid::new_v4());
+                self.document_mut().create_style(id, name, value);
+            }
+            RadiantSceneMessage::UpdateStyle { id, value } => {
+                self.document_mut().update_style(id, value);
+            }
+            RadiantSceneMessage::ApplyStyle {
+                id,
+                style_id,
+                target,
+            } => {
+                self.document_mut().apply_style(id, style_id, target);
+            }
+            RadiantSceneMessage::DetachStyle { id, target } => {
+                self.document_mut().detach_style(id, target);
+            }
             RadiantSceneMessage::SelectTool { id } => {
-                self.tool_manager.activate_tool(id);
+                let message = self.tool_manager.activate_tool(id)?;
+                return Some(RadiantSceneResponse::Message { message });
+            }
+            RadiantSceneMessage::SelectToolByName { name } => {
+                let id = self.tool_manager.tool_id(&name)?;
+                let message = self.tool_manager.activate_tool(id)?;
+                return Some(RadiantSceneResponse::Message { message });
+            }
+            RadiantSceneMessage::CancelTool {} => {
+                let message = self.tool_manager.cancel()?;
+                return Some(RadiantSceneResponse::Message { message });
+            }
+            RadiantSceneMessage::PanCamera { delta } => {
+                let mut camera = self.camera;
+                camera.pan(delta);
+                return self.set_camera(camera);
+            }
+            RadiantSceneMessage::ZoomCamera { factor, position } => {
+                let [left, top, right, bottom] = self.screen_descriptor.viewport();
+                let position = position.unwrap_or([(left + right) / 2.0, (top + bottom) / 2.0]);
+                let mut camera = self.camera;
+                camera.zoom_at(factor, position);
+                return self.set_camera(camera);
+            }
+            RadiantSceneMessage::SetCamera { offset, zoom } => {
+                return self.set_camera(RadiantCamera::new(offset, zoom));
+            }
+            RadiantSceneMessage::Undo {} => {
+                if self.document_mut().undo() {
+                    let id = self.document().selected_node_id;
+                    return self.select_node(id);
+                }
             }
-            RadiantSceneMessage::HandleKey { id, key } => {
-                if let Some(id) = match id {
-                    Some(id) => Some(id),
-                    None => self.document.read().selected_node_id,
-                } {
+            RadiantSceneMessage::HandleKey { id, key, modifiers } => {
+                let id = id.or(self.document().selected_node_id);
+                // Shortcuts with Ctrl or Meta are never typed into the node.
+                if let (Some(id), false) = (id, modifiers.ctrl || modifiers.meta) {
                     if let Some(mut node) = self.document.write().get_node_mut(id) {
-                        if node.handle_key_down(key) {
+                        if node.handle_key_down(key.clone()) {
                             self.interaction_manager
                                 .update_interactions(node, &self.screen_descriptor);
+                            return None;
                         }
                     }
                 }
+                let chord = RadiantKeyChord::new(key, modifiers);
+                if let Some(message) = self.keymap.message(&chord) {
+                    return Some(RadiantSceneResponse::Message {
+                        message: message.clone().into(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Shows the part of the document seen by `camera`. Nodes are tessellated again when
+    /// the zoom reaches another level, since they are tessellated for the pixels they
+    /// cover, but not on every step of a zoom gesture.
+    pub fn set_camera(&mut self, camera: RadiantCamera) -> Option<RadiantSceneResponse<M, N>> {
+        if camera == self.camera {
+            return None;
+        }
+        let scale_factor = self.screen_descriptor.pixels_per_point / self.camera.zoom;
+        let zoomed = camera.zoom != self.camera.zoom;
+        self.camera = camera;
+        self.screen_descriptor.pixels_per_point = scale_factor * camera.zoom;
+        self.screen_descriptor.offset = camera.offset;
+        self.screen_descriptor.zoom = camera.zoom;
+        self.interaction_manager.hover_interaction.invalidate();
+        if zoomed {
+            if camera.zoom_level() != self.tessellated_zoom_level {
+                self.tessellated_zoom_level = camera.zoom_level();
+                self.document_mut().invalidate_tessellation();
+            }
+            let id = self.document().selected_node_id;
+            if let Some(id) = id {
+                self.update_interactions(id);
+            }
+        }
+        Some(RadiantSceneResponse::CameraUpdated {
+            offset: camera.offset,
+            zoom: camera.zoom,
+        })
+    }
+
+    /// Moves the handles of the interactions to the current shape of the node.
+    fn update_interactions(&mut self, id: Uuid) {
+        if let Some(node) = self.document.write().get_node_mut(id) {
+            self.interaction_manager
+                .update_interactions(node, &self.screen_descriptor);
+        }
+    }
+
+    /// Moves the interactions to the node transformed to `transform`.
+    fn transform_updated(
+        &mut self,
+        id: Uuid,
+        transform: TransformComponent,
+    ) -> Option<RadiantSceneResponse<M, N>> {
+        let mut document = self.document.write();
+        self.interaction_manager
+            .update_interactions(document.get_node_mut(id)?, &self.screen_descriptor);
+        Some(RadiantSceneResponse::TransformUpdated {
+            id,
+            position: transform.position().into(),
+            scale: transform.scale().into(),
+        })
+    }
+
+    /// Deselects and unhovers the nodes that can no longer be picked, after a node or
+    /// artboard was hidden or locked.
+    fn release_unpickable(&mut self) -> Option<RadiantSceneResponse<M, N>> {
+        let selected_node_id = self.document().selected_node_id;
+        if selected_node_id.is_some_and(|id| !self.document().is_pickable(id)) {
+            self.select_node(None);
+        }
+        let hovered_node_id = self.hovered_node_id;
+        if hovered_node_id.is_some_and(|id| !self.document().is_pickable(id)) {
+            self.hovered_node_id = None;
+            return Some(RadiantSceneResponse::Hovered { id: None });
+        }
+        None
+    }
+
+    fn select_node(&mut self, id: Option<Uuid>) -> Option<RadiantSceneResponse<M, N>> {
+        self.document_mut().select(id);
+        if let Some(id) = id {
+            if !self.interaction_manager.is_interaction(id) {
+                if let Some(mut node) = self.document.write().get_node_mut(id) {
+                    node.tessellate(false, &self.screen_descriptor, &self.fonts_manager);
+                    let response = RadiantSceneResponse::Selected { node: node.clone() };
+                    self.interaction_manager
+                        .enable_interactions(node, &self.screen_descriptor);
+                    return Some(response);
+                } else {
+                    self.interaction_manager.disable_interactions();
+                }
             }
+        } else {
+            self.interaction_manager.disable_interactions();
         }
         None
     }
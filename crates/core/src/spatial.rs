@@ -0,0 +1,166 @@
+use rstar::{RTree, RTreeObject, AABB};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RadiantSpatialEntry {
+    id: Uuid,
+    artboard_id: Uuid,
+    rect: [f32; 4],
+}
+
+impl RTreeObject for RadiantSpatialEntry {
+    type Envelope = AABB<[f32; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        let [left, top, right, bottom] = self.rect;
+        AABB::from_corners([left, top], [right, bottom])
+    }
+}
+
+/// R-tree of the bounding rects of the nodes of a document.
+///
+/// Results are ordered from the topmost node down, following the order artboards and
+/// their children are drawn in.
+#[derive(Default)]
+pub struct RadiantSpatialIndex {
+    tree: RTree<RadiantSpatialEntry>,
+    entries: HashMap<Uuid, RadiantSpatialEntry>,
+}
+
+impl RadiantSpatialIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, id: Uuid) -> bool {
+        self.entries.contains_key(&id)
+    }
+
+    pub fn rect(&self, id: Uuid) -> Option<[f32; 4]> {
+        self.entries.get(&id).map(|entry| entry.rect)
+    }
+
+    pub fn artboard_id(&self, id: Uuid) -> Option<Uuid> {
+        self.entries.get(&id).map(|entry| entry.artboard_id)
+    }
+
+    /// Adds the node, or moves it if it is already indexed.
+    pub fn insert(&mut self, id: Uuid, artboard_id: Uuid, rect: [f32; 4]) {
+        let entry = RadiantSpatialEntry {
+            id,
+            artboard_id,
+            rect,
+        };
+        if let Some(previous) = self.entries.insert(id, entry) {
+            if previous == entry {
+                return;
+            }
+            self.tree.remove(&previous);
+        }
+        self.tree.insert(entry);
+    }
+
+    pub fn remove(&mut self, id: Uuid) {
+        if let Some(entry) = self.entries.remove(&id) {
+            self.tree.remove(&entry);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = RTree::new();
+        self.entries.clear();
+    }
+
+    /// Nodes whose rect is within `tolerance` of `position`, as artboard and node ids.
+    pub fn query_point(&self, position: [f32; 2], tolerance: f32) -> Vec<(Uuid, Uuid)> {
+        let [x, y] = position;
+        self.query([x - tolerance, y - tolerance, x + tolerance, y + tolerance])
+    }
+
+    /// Nodes whose rect intersects `rect`, as artboard and node ids.
+    pub fn query_rect(&self, rect: [f32; 4]) -> Vec<(Uuid, Uuid)> {
+        self.query(rect)
+    }
+
+    /// Nodes whose rect lies entirely inside `rect`, as artboard and node ids.
+    pub fn query_contained(&self, rect: [f32; 4]) -> Vec<(Uuid, Uuid)> {
+        let [left, top, right, bottom] = rect;
+        let envelope = AABB::from_corners([left, top], [right, bottom]);
+        Self::sorted(self.tree.locate_in_envelope(&envelope))
+    }
+
+    fn query(&self, rect: [f32; 4]) -> Vec<(Uuid, Uuid)> {
+        let [left, top, right, bottom] = rect;
+        let envelope = AABB::from_corners([left, top], [right, bottom]);
+        Self::sorted(self.tree.locate_in_envelope_intersecting(&envelope))
+    }
+
+    fn sorted<'a>(entries: impl Iterator<Item = &'a RadiantSpatialEntry>) -> Vec<(Uuid, Uuid)> {
+        let mut ids = entries
+            .map(|entry| (entry.artboard_id, entry.id))
+            .collect::<Vec<_>>();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        ids
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RadiantSpatialIndex;
+    use uuid::Uuid;
+
+    #[test]
+    fn nodes_are_inserted_moved_and_removed() {
+        let mut index = RadiantSpatialIndex::new();
+        let artboard_id = Uuid::new_v4();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        index.insert(first, artboard_id, [0.0, 0.0, 10.0, 10.0]);
+        index.insert(second, artboard_id, [20.0, 20.0, 30.0, 30.0]);
+        assert_eq!(index.len(), 2);
+        assert_eq!(
+            index.query_point([5.0, 5.0], 0.0),
+            vec![(artboard_id, first)]
+        );
+        assert_eq!(index.query_point([15.0, 15.0], 0.0), vec![]);
+        assert_eq!(index.query_point([15.0, 15.0], 5.0).len(), 2);
+
+        index.insert(first, artboard_id, [40.0, 40.0, 50.0, 50.0]);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.query_point([5.0, 5.0], 0.0), vec![]);
+        assert_eq!(
+            index.query_point([45.0, 45.0], 0.0),
+            vec![(artboard_id, first)]
+        );
+        assert_eq!(index.rect(first), Some([40.0, 40.0, 50.0, 50.0]));
+
+        index.remove(first);
+        assert!(!index.contains(first));
+        assert_eq!(index.query_point([45.0, 45.0], 0.0), vec![]);
+        assert_eq!(
+            index.query_rect([0.0, 0.0, 100.0, 100.0]),
+            vec![(artboard_id, second)]
+        );
+    }
+
+    #[test]
+    fn rect_queries_tell_intersecting_from_contained() {
+        let mut index = RadiantSpatialIndex::new();
+        let artboard_id = Uuid::new_v4();
+        let (inside, across) = (Uuid::new_v4(), Uuid::new_v4());
+        index.insert(inside, artboard_id, [10.0, 10.0, 20.0, 20.0]);
+        index.insert(across, artboard_id, [40.0, 40.0, 60.0, 60.0]);
+
+        let rect = [0.0, 0.0, 50.0, 50.0];
+        assert_eq!(index.query_rect(rect).len(), 2);
+        assert_eq!(index.query_contained(rect), vec![(artboard_id, inside)]);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    KeyCode, RadiantCursor, RadiantMouseButton, RadiantPointerEvent, RadiantSceneMessage,
+    RadiantTool,
+};
+use macro_magic::export_tokens;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[export_tokens]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RadiantAddLineMessage {
+    AddLine {
+        id: Option<uuid::Uuid>,
+        start: [f32; 2],
+        end: [f32; 2],
+    },
+}
+
+pub struct LineTool {
+    active_node_id: Option<Uuid>,
+    start_position: [f32; 2],
+}
+
+impl LineTool {
+    pub fn new() -> Self {
+        Self {
+            active_node_id: None,
+            start_position: [0.0, 0.0],
+        }
+    }
+}
+
+impl Default for LineTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: From<RadiantAddLineMessage> + From<RadiantSceneMessage>> RadiantTool<M> for LineTool {
+    fn name(&self) -> &str {
+        "line"
+    }
+
+    fn on_mouse_down(&mut self, _node_id: Option<Uuid>, event: &RadiantPointerEvent) -> Option<M> {
+        if event.button != RadiantMouseButton::Left {
+            return None;
+        }
+        let position = event.position;
+        let id = Uuid::new_v4();
+        let message = RadiantAddLineMessage::AddLine {
+            id: Some(id),
+            start: position,
+            end: position,
+        };
+        self.active_node_id = Some(id);
+        self.start_position = position;
+        Some(message.into())
+    }
+
+    fn on_mouse_move(&mut self, event: &RadiantPointerEvent) -> Option<M> {
+        let id = self.active_node_id?;
+        let mut position = event.position;
+        // Shift snaps the line to multiples of 45 degrees.
+        if event.modifiers.shift {
+            let delta = [
+                position[0] - self.start_position[0],
+                position[1] - self.start_position[1],
+            ];
+            let length = delta[0].hypot(delta[1]);
+            let step = std::f32::consts::FRAC_PI_4;
+            let angle = (delta[1].atan2(delta[0]) / step).round() * step;
+            position = [
+                self.start_position[0] + length * angle.cos(),
+                self.start_position[1] + length * angle.sin(),
+            ];
+        }
+        let message = RadiantSceneMessage::SetEndpoints {
+            id,
+            start: self.start_position,
+            end: position,
+        };
+        Some(message.into())
+    }
+
+    fn on_mouse_up(&mut self, _event: &RadiantPointerEvent) -> Option<M> {
+        self.active_node_id = None;
+        self.start_position = [0.0, 0.0];
+        None
+    }
+
+    fn on_key_down(&mut self, key: KeyCode) -> Option<M> {
+        if key == KeyCode::Escape {
+            return RadiantTool::<M>::cancel(self);
+        }
+        None
+    }
+
+    /// Removes the line being drawn.
+    fn cancel(&mut self) -> Option<M> {
+        let id = self.active_node_id.take()?;
+        self.start_position = [0.0, 0.0];
+        Some(RadiantSceneMessage::RemoveNode { id }.into())
+    }
+
+    fn cursor(&self) -> RadiantCursor {
+        RadiantCursor::Crosshair
+    }
+}
@@ -1,15 +1,18 @@
+pub mod line_tool;
 pub mod rectangle_tool;
 pub mod selection_tool;
 pub mod tool_manager;
 
+pub use line_tool::*;
 pub use rectangle_tool::*;
 pub use selection_tool::*;
 pub use tool_manager::*;
 
+use epaint::Shape;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum KeyCode {
     Backspace,
     Delete,
@@ -24,16 +27,126 @@ pub enum KeyCode {
     Char(String),
 }
 
+/// Modifier keys held during an input event.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct RadiantModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantMouseButton {
+    #[default]
+    Left,
+    Right,
+    Middle,
+    Other(u16),
+}
+
+/// Mouse, pen or touch input given to tools.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadiantPointerEvent {
+    pub position: [f32; 2],
+    /// Button pressed, or held while moving.
+    pub button: RadiantMouseButton,
+    pub modifiers: RadiantModifiers,
+    /// Number of clicks in quick succession this event belongs to, 2 for a double click.
+    pub click_count: u32,
+    /// Pressure between 0 and 1, always 1 for devices that do not report it.
+    pub pressure: f32,
+    /// Node a mouse down acts on, as it was before the press. Only set on mouse down.
+    pub target: Option<RadiantPointerTarget>,
+}
+
+/// Node under the pointer, or the selected node when over its interaction handles, with
+/// its transform.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadiantPointerTarget {
+    pub id: Uuid,
+    pub position: [f32; 2],
+    pub scale: [f32; 2],
+}
+
+impl RadiantPointerEvent {
+    pub fn new(position: [f32; 2]) -> Self {
+        Self {
+            position,
+            button: RadiantMouseButton::Left,
+            modifiers: RadiantModifiers::default(),
+            click_count: 1,
+            pressure: 1.0,
+            target: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RadiantGesture {
+    /// Mouse wheel or two-finger trackpad scroll by `delta` screen points.
+    Scroll { delta: [f32; 2] },
+    /// Pinch on a trackpad or a touch screen scaling by `factor`, while the fingers move by
+    /// `delta` screen points.
+    Pinch { factor: f32, delta: [f32; 2] },
+}
+
+/// Scroll or pinch given to tools before it moves the camera.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RadiantGestureEvent {
+    /// Document position of the pointer, or of the center of the fingers.
+    pub position: [f32; 2],
+    pub gesture: RadiantGesture,
+    pub modifiers: RadiantModifiers,
+}
+
+/// Cursor the view shows over the document while a tool is active.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RadiantCursor {
+    #[default]
+    Default,
+    Pointer,
+    Crosshair,
+    Move,
+    Text,
+    Grab,
+    Grabbing,
+    NotAllowed,
+}
+
 pub trait RadiantTool<M>: Send + Sync {
-    fn on_mouse_down(&mut self, _node_id: Option<Uuid>, _position: [f32; 2]) -> Option<M> {
+    /// Name the tool is looked up by, unique among the tools of a scene.
+    fn name(&self) -> &str;
+    fn on_activate(&mut self) {}
+    /// Called after `cancel` when another tool is activated.
+    fn on_deactivate(&mut self) {}
+    /// Abandons the interaction in progress, returning the message undoing what it did.
+    fn cancel(&mut self) -> Option<M> {
+        None
+    }
+    /// Shapes in document points drawn over the interactions, such as the preview of the
+    /// node being drawn. They are not part of the document and cannot be picked.
+    fn overlay(&self) -> Vec<Shape> {
+        Vec::new()
+    }
+    fn cursor(&self) -> RadiantCursor {
+        RadiantCursor::Default
+    }
+    fn on_mouse_down(&mut self, _node_id: Option<Uuid>, _event: &RadiantPointerEvent) -> Option<M> {
+        None
+    }
+    fn on_mouse_move(&mut self, _event: &RadiantPointerEvent) -> Option<M> {
         None
     }
-    fn on_mouse_move(&mut self, _position: [f32; 2]) -> Option<M> {
+    fn on_mouse_up(&mut self, _event: &RadiantPointerEvent) -> Option<M> {
         None
     }
-    fn on_mouse_up(&mut self, _position: [f32; 2]) -> Option<M> {
+    /// Gestures the tool does not consume move the camera.
+    fn on_gesture(&mut self, _event: &RadiantGestureEvent) -> Option<M> {
         None
     }
+    /// Keys the tool does not consume go to the selected node, then to the keymap.
     fn on_key_down(&mut self, _key: KeyCode) -> Option<M> {
         None
     }
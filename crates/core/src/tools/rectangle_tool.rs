@@ -1,8 +1,15 @@
-use crate::{RadiantSceneMessage, RadiantTool};
+use crate::{
+    KeyCode, RadiantCursor, RadiantMouseButton, RadiantPointerEvent, RadiantSceneMessage,
+    RadiantTool,
+};
+use epaint::{pos2, vec2, Color32, Rect, Shape, Stroke};
 use macro_magic::export_tokens;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Size of the rectangle added by clicking without dragging.
+const DEFAULT_SIZE: [f32; 2] = [100.0, 100.0];
+
 #[export_tokens]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RadiantRectangleMessage {
@@ -13,18 +20,21 @@ pub enum RadiantRectangleMessage {
     },
 }
 
+/// Draws a preview of the rectangle while dragging and adds it on mouse up.
 pub struct RectangleTool {
-    active_node_id: Option<Uuid>,
-    start_position: [f32; 2],
-    prev_position: [f32; 2],
+    /// Id of the rectangle being drawn, allocated when the drag starts.
+    id: Option<Uuid>,
+    start_position: Option<[f32; 2]>,
+    /// Position and size of the rectangle dragged out so far.
+    rect: Option<([f32; 2], [f32; 2])>,
 }
 
 impl RectangleTool {
     pub fn new() -> Self {
         Self {
-            active_node_id: None,
-            start_position: [0.0, 0.0],
-            prev_position: [0.0, 0.0],
+            id: None,
+            start_position: None,
+            rect: None,
         }
     }
 }
@@ -32,41 +42,86 @@ impl RectangleTool {
 impl<M: From<RadiantRectangleMessage> + From<RadiantSceneMessage>> RadiantTool<M>
     for RectangleTool
 {
-    fn on_mouse_down(&mut self, _node_id: Option<Uuid>, position: [f32; 2]) -> Option<M> {
-        let id = Uuid::new_v4();
+    fn name(&self) -> &str {
+        "rectangle"
+    }
+
+    fn on_mouse_down(&mut self, _node_id: Option<Uuid>, event: &RadiantPointerEvent) -> Option<M> {
+        if event.button == RadiantMouseButton::Left {
+            self.id = Some(Uuid::new_v4());
+            self.start_position = Some(event.position);
+            self.rect = None;
+        }
+        None
+    }
+
+    fn on_mouse_move(&mut self, event: &RadiantPointerEvent) -> Option<M> {
+        let start_position = self.start_position?;
+        let mut size = [
+            event.position[0] - start_position[0],
+            event.position[1] - start_position[1],
+        ];
+        // Shift draws a square, Alt draws from the center out.
+        if event.modifiers.shift {
+            let side = size[0].abs().max(size[1].abs());
+            size = [side.copysign(size[0]), side.copysign(size[1])];
+        }
+        let mut start = start_position;
+        if event.modifiers.alt {
+            start = [start[0] - size[0], start[1] - size[1]];
+            size = [size[0] * 2.0, size[1] * 2.0];
+        }
+        let end = [start[0] + size[0], start[1] + size[1]];
+        self.rect = Some((
+            [start[0].min(end[0]), start[1].min(end[1])],
+            [size[0].abs(), size[1].abs()],
+        ));
+        None
+    }
+
+    fn on_mouse_up(&mut self, _event: &RadiantPointerEvent) -> Option<M> {
+        let start_position = self.start_position.take()?;
+        let id = self.id.take();
+        let (position, scale) = self
+            .rect
+            .take()
+            .filter(|(_, scale)| scale[0] > 0.0 && scale[1] > 0.0)
+            .unwrap_or((start_position, DEFAULT_SIZE));
         let message = RadiantRectangleMessage::AddRectangle {
-            id: Some(id),
+            id,
             position,
-            scale: [10.0, 10.0],
+            scale,
         };
-        self.active_node_id = Some(id);
-        self.start_position = position;
-        self.prev_position = position;
         Some(message.into())
     }
 
-    fn on_mouse_move(&mut self, position: [f32; 2]) -> Option<M> {
-        let result = if let Some(id) = self.active_node_id {
-            let message = RadiantSceneMessage::TransformNode {
-                id: id,
-                position: [0.0, 0.0],
-                scale: [
-                    position[0] - self.prev_position[0],
-                    position[1] - self.prev_position[1],
-                ],
-            };
-            Some(message.into())
-        } else {
-            None
-        };
-        self.prev_position = position;
-        result
+    fn on_key_down(&mut self, key: KeyCode) -> Option<M> {
+        // Escape also goes on to the keymap, which clears the selection.
+        if key == KeyCode::Escape {
+            return RadiantTool::<M>::cancel(self);
+        }
+        None
     }
 
-    fn on_mouse_up(&mut self, _position: [f32; 2]) -> Option<M> {
-        self.active_node_id = None;
-        self.start_position = [0.0, 0.0];
-        self.prev_position = [0.0, 0.0];
+    fn cancel(&mut self) -> Option<M> {
+        self.id = None;
+        self.start_position = None;
+        self.rect = None;
         None
     }
+
+    fn overlay(&self) -> Vec<Shape> {
+        let Some((position, scale)) = self.rect else {
+            return Vec::new();
+        };
+        let rect = Rect::from_min_size(pos2(position[0], position[1]), vec2(scale[0], scale[1]));
+        vec![
+            Shape::rect_filled(rect, 0.0, Color32::from_black_alpha(32)),
+            Shape::rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::BLUE)),
+        ]
+    }
+
+    fn cursor(&self) -> RadiantCursor {
+        RadiantCursor::Crosshair
+    }
 }
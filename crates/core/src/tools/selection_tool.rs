@@ -1,36 +1,79 @@
 use uuid::Uuid;
 
-use crate::{RadiantSceneMessage, RadiantTool};
+use crate::{
+    is_interaction_handle, KeyCode, RadiantCursor, RadiantMouseButton, RadiantPointerEvent,
+    RadiantPointerTarget, RadiantSceneMessage, RadiantTool,
+};
 
 pub struct SelectionTool {
     active_node_id: Option<Uuid>,
+    /// Node the drag moves or resizes, as it was when the drag started.
+    original: Option<RadiantPointerTarget>,
     prev_position: [f32; 2],
     is_mouse_down: bool,
+    duplicate: bool,
+    /// Whether the dragged node is a copy made by this drag.
+    duplicated: bool,
 }
 
 impl SelectionTool {
     pub fn new() -> Self {
         Self {
             active_node_id: None,
+            original: None,
             prev_position: [0.0, 0.0],
             is_mouse_down: false,
+            duplicate: false,
+            duplicated: false,
         }
     }
 }
 
 impl<M: From<RadiantSceneMessage>> RadiantTool<M> for SelectionTool {
-    fn on_mouse_down(&mut self, node_id: Option<Uuid>, position: [f32; 2]) -> Option<M> {
-        self.prev_position = position;
+    fn name(&self) -> &str {
+        "select"
+    }
+
+    fn on_mouse_down(&mut self, node_id: Option<Uuid>, event: &RadiantPointerEvent) -> Option<M> {
+        match event.button {
+            RadiantMouseButton::Left => {}
+            RadiantMouseButton::Right => {
+                return Some(
+                    RadiantSceneMessage::OpenContextMenu {
+                        id: node_id,
+                        position: event.position,
+                    }
+                    .into(),
+                );
+            }
+            _ => return None,
+        }
+        if let (Some(id), 2) = (node_id, event.click_count) {
+            return Some(RadiantSceneMessage::EditNode { id }.into());
+        }
+        self.original = event.target;
+        self.prev_position = event.position;
         self.is_mouse_down = true;
         self.active_node_id = node_id;
+        // The copy is made once the mouse moves, so that Alt-clicking only selects. Handles
+        // use Alt to resize from the center instead.
+        self.duplicate =
+            event.modifiers.alt && node_id.is_some_and(|id| !is_interaction_handle(id));
         Some(RadiantSceneMessage::SelectNode { id: node_id }.into())
     }
 
-    fn on_mouse_move(&mut self, position: [f32; 2]) -> Option<M> {
+    fn on_mouse_move(&mut self, event: &RadiantPointerEvent) -> Option<M> {
         if !self.is_mouse_down {
             return None;
         }
+        let position = event.position;
         let result = if let Some(id) = self.active_node_id {
+            if std::mem::take(&mut self.duplicate) {
+                let new_id = Uuid::new_v4();
+                self.active_node_id = Some(new_id);
+                self.duplicated = true;
+                return Some(RadiantSceneMessage::DuplicateNode { id, new_id }.into());
+            }
             let message = RadiantSceneMessage::TransformNode {
                 id: id,
                 position: [
@@ -47,14 +90,48 @@ impl<M: From<RadiantSceneMessage>> RadiantTool<M> for SelectionTool {
         result
     }
 
-    fn on_mouse_up(&mut self, _position: [f32; 2]) -> Option<M> {
+    fn on_mouse_up(&mut self, _event: &RadiantPointerEvent) -> Option<M> {
         self.active_node_id = None;
+        self.original = None;
         self.is_mouse_down = false;
+        self.duplicate = false;
+        self.duplicated = false;
         self.prev_position = [0.0, 0.0];
         None
     }
 
-    fn on_key_down(&mut self, key: crate::KeyCode) -> Option<M> {
-        return Some(RadiantSceneMessage::HandleKey { id: None, key }.into());
+    fn on_key_down(&mut self, key: KeyCode) -> Option<M> {
+        if key == KeyCode::Escape {
+            return RadiantTool::<M>::cancel(self);
+        }
+        None
+    }
+
+    /// Restores the transform the dragged node had when the drag started, or removes the
+    /// copy made by the drag.
+    fn cancel(&mut self) -> Option<M> {
+        let is_mouse_down = std::mem::take(&mut self.is_mouse_down);
+        self.duplicate = false;
+        let duplicated = std::mem::take(&mut self.duplicated);
+        let original = self.original.take();
+        let id = self.active_node_id.take().filter(|_| is_mouse_down)?;
+        if duplicated {
+            return Some(RadiantSceneMessage::RemoveNode { id }.into());
+        }
+        let original = original?;
+        let message = RadiantSceneMessage::SetTransform {
+            id: original.id,
+            position: original.position,
+            scale: original.scale,
+        };
+        Some(message.into())
+    }
+
+    fn cursor(&self) -> RadiantCursor {
+        if self.is_mouse_down && self.active_node_id.is_some() {
+            RadiantCursor::Move
+        } else {
+            RadiantCursor::Default
+        }
     }
 }
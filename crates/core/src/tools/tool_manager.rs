@@ -20,6 +20,32 @@ impl<M> RadiantToolManager<M> {
         self.tools.insert(tool_id, tool);
     }
 
+    /// Registers `tool` under the id following the highest one in use.
+    pub fn add_tool<T: RadiantTool<M> + 'static>(&mut self, tool: Box<T>) -> ToolId {
+        let tool_id = self.tools.keys().next_back().map_or(0, |id| id + 1);
+        self.register_tool(tool_id, tool);
+        tool_id
+    }
+
+    pub fn tool(&self, id: ToolId) -> Option<&dyn RadiantTool<M>> {
+        self.tools.get(&id).map(|tool| tool.as_ref())
+    }
+
+    pub fn tool_mut(&mut self, id: ToolId) -> Option<&mut dyn RadiantTool<M>> {
+        match self.tools.get_mut(&id) {
+            Some(tool) => Some(tool.as_mut()),
+            None => None,
+        }
+    }
+
+    /// Id of the tool registered under `name`.
+    pub fn tool_id(&self, name: &str) -> Option<ToolId> {
+        self.tools
+            .iter()
+            .find(|(_, tool)| tool.name() == name)
+            .map(|(id, _)| *id)
+    }
+
     pub fn active_tool(&mut self) -> &mut dyn RadiantTool<M> {
         self.tools
             .get_mut(&self.active_tool_id)
@@ -27,9 +53,20 @@ impl<M> RadiantToolManager<M> {
             .as_mut()
     }
 
-    pub fn activate_tool(&mut self, id: u32) {
-        if self.tools.len() > id as usize {
-            self.active_tool_id = id;
+    /// Cancels and deactivates the active tool, then activates the tool registered under
+    /// `id`. Returns the message undoing what the cancelled tool did.
+    pub fn activate_tool(&mut self, id: ToolId) -> Option<M> {
+        if id == self.active_tool_id || !self.tools.contains_key(&id) {
+            return None;
         }
+        let message = self.cancel();
+        self.active_tool().on_deactivate();
+        self.active_tool_id = id;
+        self.active_tool().on_activate();
+        message
+    }
+
+    pub fn cancel(&mut self) -> Option<M> {
+        self.active_tool().cancel()
     }
 }
@@ -0,0 +1,136 @@
+use epaint::{pos2, Pos2, Rect, Rounding};
+
+/// Distance from `point` to the segment between `start` and `end`.
+pub fn distance_to_segment(point: Pos2, start: Pos2, end: Pos2) -> f32 {
+    let segment = end - start;
+    let length_sq = segment.length_sq();
+    if length_sq <= f32::EPSILON {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_sq).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
+
+/// Distance from `point` to the polyline through `points`, closed back to the first point
+/// when `closed` is set.
+pub fn distance_to_polyline(point: Pos2, points: &[Pos2], closed: bool) -> f32 {
+    let segments = points.windows(2).map(|pair| (pair[0], pair[1]));
+    let closing = match (closed, points.first(), points.last()) {
+        (true, Some(first), Some(last)) if points.len() > 2 => Some((*last, *first)),
+        _ => None,
+    };
+    segments
+        .chain(closing)
+        .map(|(start, end)| distance_to_segment(point, start, end))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Whether `point` is inside the polygon through `points`, following the even-odd rule.
+pub fn polygon_contains(point: Pos2, points: &[Pos2]) -> bool {
+    let mut inside = false;
+    let mut previous = match points.last() {
+        Some(last) => *last,
+        None => return false,
+    };
+    for current in points {
+        if (current.y > point.y) != (previous.y > point.y)
+            && point.x
+                < (previous.x - current.x) * (point.y - current.y) / (previous.y - current.y)
+                    + current.x
+        {
+            inside = !inside;
+        }
+        previous = *current;
+    }
+    inside
+}
+
+/// Whether `point` is inside `rect` with its corners rounded by `rounding`.
+pub fn rounded_rect_contains(point: Pos2, rect: Rect, rounding: Rounding) -> bool {
+    if !rect.contains(point) {
+        return false;
+    }
+    let max_radius = rect.width().min(rect.height()) / 2.0;
+    let corners = [
+        (rounding.nw, pos2(rect.min.x, rect.min.y), [1.0, 1.0]),
+        (rounding.ne, pos2(rect.max.x, rect.min.y), [-1.0, 1.0]),
+        (rounding.se, pos2(rect.max.x, rect.max.y), [-1.0, -1.0]),
+        (rounding.sw, pos2(rect.min.x, rect.max.y), [1.0, -1.0]),
+    ];
+    corners.into_iter().all(|(radius, corner, [x, y])| {
+        let radius = radius.min(max_radius);
+        if radius <= 0.0 {
+            return true;
+        }
+        let center = pos2(corner.x + x * radius, corner.y + y * radius);
+        let outside_corner = (point.x - center.x) * x < 0.0 && (point.y - center.y) * y < 0.0;
+        !outside_corner || point.distance(center) <= radius
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        distance_to_polyline, distance_to_segment, polygon_contains, rounded_rect_contains,
+    };
+    use epaint::{pos2, Rect, Rounding};
+
+    #[test]
+    fn distance_to_segment_clamps_to_the_ends() {
+        let (start, end) = (pos2(0.0, 0.0), pos2(10.0, 0.0));
+        assert_eq!(distance_to_segment(pos2(5.0, 3.0), start, end), 3.0);
+        assert_eq!(distance_to_segment(pos2(-3.0, 4.0), start, end), 5.0);
+        assert_eq!(distance_to_segment(pos2(13.0, -4.0), start, end), 5.0);
+        assert_eq!(distance_to_segment(pos2(3.0, 4.0), start, start), 5.0);
+    }
+
+    #[test]
+    fn distance_to_polyline_closes_when_asked() {
+        let points = [pos2(0.0, 0.0), pos2(10.0, 0.0), pos2(10.0, 10.0)];
+        assert_eq!(distance_to_polyline(pos2(2.0, 4.0), &points, false), 4.0);
+        assert!(distance_to_polyline(pos2(2.0, 4.0), &points, true) < 2.0);
+    }
+
+    #[test]
+    fn polygon_contains_follows_even_odd_rule() {
+        let square = [
+            pos2(0.0, 0.0),
+            pos2(10.0, 0.0),
+            pos2(10.0, 10.0),
+            pos2(0.0, 10.0),
+        ];
+        assert!(polygon_contains(pos2(5.0, 5.0), &square));
+        assert!(!polygon_contains(pos2(15.0, 5.0), &square));
+        assert!(!polygon_contains(pos2(5.0, 5.0), &[]));
+
+        // The pentagram's center is enclosed twice, so it's outside.
+        let star = [
+            pos2(50.0, 0.0),
+            pos2(80.0, 100.0),
+            pos2(0.0, 35.0),
+            pos2(100.0, 35.0),
+            pos2(20.0, 100.0),
+        ];
+        assert!(!polygon_contains(pos2(50.0, 55.0), &star));
+        assert!(polygon_contains(pos2(50.0, 20.0), &star));
+    }
+
+    #[test]
+    fn rounded_rect_excludes_corners() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 50.0));
+        let rounding = Rounding {
+            nw: 20.0,
+            ne: 0.0,
+            sw: 0.0,
+            se: 100.0,
+        };
+        assert!(rounded_rect_contains(pos2(50.0, 25.0), rect, rounding));
+        assert!(!rounded_rect_contains(pos2(2.0, 2.0), rect, rounding));
+        assert!(rounded_rect_contains(pos2(20.0, 2.0), rect, rounding));
+        assert!(rounded_rect_contains(pos2(99.0, 1.0), rect, rounding));
+        assert!(!rounded_rect_contains(pos2(110.0, 25.0), rect, rounding));
+        // Radii are limited to half of the shorter side.
+        assert!(!rounded_rect_contains(pos2(98.0, 48.0), rect, rounding));
+        assert!(rounded_rect_contains(pos2(70.0, 40.0), rect, rounding));
+    }
+}
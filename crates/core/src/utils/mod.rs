@@ -1,7 +1,9 @@
 pub mod vec3;
 pub mod atomic;
 pub mod observer;
+pub mod hit_test;
 
 pub use vec3::*;
 pub use atomic::*;
 pub use observer::*;
+pub use hit_test::*;
@@ -1,14 +1,17 @@
 use epaint::{
-    pos2, ClippedPrimitive, ClippedShape, Color32, Rect, TessellationOptions, TextureHandle,
+    pos2, ClippedPrimitive, Color32, Mesh, Rect, RectShape, Rounding, Shape, TextureHandle,
 };
 use radiantkit_core::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3,
+    BaseNode, RadiantNode, RadiantShapes, RadiantTessellatable, ScreenDescriptor, Vec3,
+    PICKING_COLOR,
 };
+use radiantkit_macros::RadiantTessellatable;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, RadiantTessellatable)]
+#[radiant_node(build_shapes = build_shapes)]
 pub struct RadiantImageNode {
     pub base: BaseNode,
     #[serde(skip)]
@@ -39,92 +42,51 @@ impl RadiantImageNode {
         }
     }
 
-    fn tessellate(&mut self, screen_descriptor: &ScreenDescriptor) {
-        if !self.base.needs_tessellation {
-            return;
-        }
-        self.base.needs_tessellation = false;
-
-        let pixels_per_point = screen_descriptor.pixels_per_point;
-        let position = self.base.transform.position();
-        let scale = self.base.transform.scale();
-
-        let rect = epaint::Rect::from_two_pos(
-            position.into(),
-            Vec3::new_with_added(&position, &scale).into(),
-        );
-        let rounding = epaint::Rounding::default();
-
-        let mut mesh = epaint::Mesh::with_texture(self.texture_handle.clone().unwrap().id());
-        let uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
-        mesh.add_rect_with_uv(rect, uv, self.base.color.fill_color());
-        let shapes = vec![ClippedShape(Rect::EVERYTHING, epaint::Shape::Mesh(mesh))];
-        self.base.primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
-
-        let color = get_color_for_node(self.base.id);
-        let rect_shape = epaint::RectShape::filled(rect, rounding, color);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::Rect(rect_shape),
-        )];
-        self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
-    }
-}
-
-impl RadiantTessellatable for RadiantImageNode {
-    fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
-        self.tessellate(screen_descriptor);
-    }
-
-    fn detach(&mut self) {
-        self.base.primitives.clear();
-        self.base.selection_primitives.clear();
-    }
-
-    fn set_needs_tessellation(&mut self, notify: bool) {
+    fn build_shapes(&self) -> RadiantShapes {
         let position = self.base.transform.position();
         let scale = self.base.transform.scale();
 
-        let rect = epaint::Rect::from_min_max(
+        let rect = Rect::from_two_pos(
             position.into(),
             Vec3::new_with_added(&position, &scale).into(),
         );
-        self.base.bounding_rect = [
-            rect.left_top().x,
-            rect.left_top().y,
-            rect.right_bottom().x,
-            rect.right_bottom().y,
+        let outline = vec![
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
         ];
-
-        self.base.set_needs_tessellation();
-        if notify {
-            self.base.notify(serde_json::to_string(self).unwrap());
+        let stroke_color = self.base.color.stroke_color();
+
+        // The image is tinted with the fill of the node, gradients included.
+        let mut fill = Vec::new();
+        if let Some(texture_handle) = &self.texture_handle {
+            let mut mesh = Mesh::with_texture(texture_handle.id());
+            let uv = Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0));
+            mesh.add_rect_with_uv(rect, uv, Color32::WHITE);
+            fill.push(Shape::Mesh(mesh));
         }
-    }
+        let shapes = self.base.stroke().shapes(&outline, true, stroke_color);
 
-    fn tessellate(
-        &mut self,
-        selection: bool,
-        screen_descriptor: &ScreenDescriptor,
-        _fonts_manager: &epaint::text::Fonts,
-    ) -> Vec<ClippedPrimitive> {
-        self.tessellate(screen_descriptor);
-        if selection {
-            self.base.selection_primitives.clone()
+        let color = PICKING_COLOR;
+        let stroke_shapes = if stroke_color == Color32::TRANSPARENT {
+            Vec::new()
         } else {
-            self.base.primitives.clone()
+            self.base.stroke().shapes(&outline, true, color)
+        };
+        let selection = std::iter::once(Shape::Rect(RectShape::filled(
+            rect,
+            Rounding::default(),
+            color,
+        )))
+        .chain(stroke_shapes)
+        .collect();
+
+        RadiantShapes {
+            bounding_rect: rect,
+            fill,
+            shapes,
+            selection,
         }
     }
 }
@@ -134,7 +96,15 @@ impl RadiantNode for RadiantImageNode {
         &self.base
     }
 
+    fn node_type(&self) -> &str {
+        "image"
+    }
+
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
+
+    fn replace(&mut self, node: &str) {
+        self.replace_keeping_observers(node);
+    }
 }
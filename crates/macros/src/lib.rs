@@ -56,6 +56,141 @@ fn derive_tessellatable_internal(item: TokenStream2) -> syn::Result<TokenStream2
     Ok(res)
 }
 
+/// Plumbing of a struct node with a `base: BaseNode` field, built from the function named by
+/// `#[radiant_node(build_shapes = ...)]`, which returns the `RadiantShapes` of the node.
+fn derive_tessellatable_struct_internal(item: syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let name = item.ident.clone();
+    let syn::Data::Struct(data) = &item.data else {
+        return Err(syn::Error::new_spanned(&item, "expected a struct"));
+    };
+
+    let mut build_shapes = None;
+    for attr in item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("radiant_node"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("build_shapes") {
+                build_shapes = Some(meta.value()?.parse::<syn::Ident>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported radiant_node attribute"))
+            }
+        })?;
+    }
+    let Some(build_shapes) = build_shapes else {
+        return Err(syn::Error::new_spanned(
+            &item,
+            "missing #[radiant_node(build_shapes = ...)] attribute",
+        ));
+    };
+
+    // Fields serde skips are not part of the serialized node, so they are kept.
+    let replaced_fields = data
+        .fields
+        .iter()
+        .filter(|field| !is_skipped_by_serde(field))
+        .filter_map(|field| field.ident.clone())
+        .collect::<Vec<_>>();
+
+    let res = quote! {
+        impl RadiantTessellatable for #name {
+            fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
+                if self.base.needs_tessellation {
+                    // Shapes built by `set_needs_tessellation` are only tessellated once.
+                    let shapes = match self.base.shapes.take() {
+                        Some(shapes) => shapes,
+                        None => self.#build_shapes(),
+                    };
+                    self.base
+                        .tessellate_shapes(shapes, screen_descriptor.pixels_per_point);
+                }
+            }
+
+            fn detach(&mut self) {
+                self.base.primitives.clear();
+                self.base.selection_primitives.clear();
+            }
+
+            fn set_needs_tessellation(&mut self, notify: bool) {
+                self.base.set_needs_tessellation();
+                let shapes = self.#build_shapes();
+                let rect = shapes.bounding_rect;
+                self.base.bounding_rect = [rect.min.x, rect.min.y, rect.max.x, rect.max.y];
+                self.base.shapes = Some(shapes);
+
+                if notify {
+                    self.base.notify(serde_json::to_string(self).unwrap());
+                }
+            }
+
+            fn tessellate(
+                &mut self,
+                selection: bool,
+                screen_descriptor: &ScreenDescriptor,
+                _fonts_manager: &epaint::text::Fonts,
+            ) -> Vec<ClippedPrimitive> {
+                RadiantTessellatable::attach(self, screen_descriptor);
+                if selection {
+                    self.base.selection_primitives.clone()
+                } else {
+                    self.base.primitives.clone()
+                }
+            }
+        }
+
+        impl #name {
+            /// Takes the fields of the node serialized in `node`, keeping the observers of
+            /// the node and the fields serde skips.
+            fn replace_keeping_observers(&mut self, node: &str) {
+                let node: Self = match serde_json::from_str(node) {
+                    Ok(node) => node,
+                    Err(err) => {
+                        log::error!("Failed to replace node {}: {}", self.base.id, err);
+                        return;
+                    }
+                };
+                let observers = std::mem::take(&mut self.base.observers);
+                #(
+                    self.#replaced_fields = node.#replaced_fields;
+                )*
+                self.base.observers = observers;
+                self.set_needs_tessellation(false);
+            }
+        }
+    };
+    Ok(res)
+}
+
+/// Whether serde leaves the field out of the serialized node or does not read it back, with
+/// `skip`, `skip_serializing` or `skip_deserializing`.
+fn is_skipped_by_serde(field: &syn::Field) -> bool {
+    let mut skipped = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        // Other serde attributes are not ours to validate.
+        let _ = attr.parse_nested_meta(|meta| {
+            if ["skip", "skip_serializing", "skip_deserializing"]
+                .iter()
+                .any(|skip| meta.path.is_ident(skip))
+            {
+                skipped = true;
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        });
+    }
+    skipped
+}
+
 fn derive_node_internal(item: TokenStream2) -> syn::Result<TokenStream2> {
     let item = syn::parse2::<syn::ItemEnum>(item)?;
 
@@ -74,7 +209,7 @@ fn derive_node_internal(item: TokenStream2) -> syn::Result<TokenStream2> {
 
     let res = quote! {
         impl RadiantNode for #name {
-            fn base(&self) -> &radiantkit_core::BaseNode {
+            fn base(&self) -> &crate::BaseNode {
                 match self {
                     #(
                         #name::#node_names(node) => node.base(),
@@ -82,7 +217,7 @@ fn derive_node_internal(item: TokenStream2) -> syn::Result<TokenStream2> {
                 }
             }
 
-            fn base_mut(&mut self) -> &mut radiantkit_core::BaseNode {
+            fn base_mut(&mut self) -> &mut crate::BaseNode {
                 match self {
                     #(
                         #name::#node_names(node) => node.base_mut(),
@@ -90,6 +225,22 @@ fn derive_node_internal(item: TokenStream2) -> syn::Result<TokenStream2> {
                 }
             }
 
+            fn as_any(&self) -> &dyn std::any::Any {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.as_any(),
+                    )*
+                }
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.as_any_mut(),
+                    )*
+                }
+            }
+
             fn get_id(&self) -> uuid::Uuid {
                 match self {
                     #(
@@ -106,6 +257,22 @@ fn derive_node_internal(item: TokenStream2) -> syn::Result<TokenStream2> {
                 }
             }
 
+            fn node_type(&self) -> &str {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.node_type(),
+                    )*
+                }
+            }
+
+            fn layer(&self) -> crate::RadiantLayer {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.layer(),
+                    )*
+                }
+            }
+
             fn get_bounding_rect(&self) -> [f32; 4] {
                 match self {
                     #(
@@ -114,6 +281,22 @@ fn derive_node_internal(item: TokenStream2) -> syn::Result<TokenStream2> {
                 }
             }
 
+            fn get_visual_rect(&self) -> [f32; 4] {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.get_visual_rect(),
+                    )*
+                }
+            }
+
+            fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<uuid::Uuid> {
+                match self {
+                    #(
+                        #name::#node_names(node) => node.hit_test(position, tolerance),
+                    )*
+                }
+            }
+
             fn handle_key_down(&mut self, key: crate::KeyCode) -> bool {
                 match self {
                     #(
@@ -365,9 +548,19 @@ fn combine_response_internal(
     Ok(res)
 }
 
-#[proc_macro_derive(RadiantTessellatable)]
+/// Derives `RadiantTessellatable` for an enum of nodes by delegating to the variants, or for
+/// a struct node from its `#[radiant_node(build_shapes = ...)]` function.
+#[proc_macro_derive(RadiantTessellatable, attributes(radiant_node))]
 pub fn derive_tessellatable(item: TokenStream) -> TokenStream {
-    let res = match derive_tessellatable_internal(item.into()) {
+    let input = match syn::parse::<syn::DeriveInput>(item.clone()) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let res = match input.data {
+        syn::Data::Struct(_) => derive_tessellatable_struct_internal(input),
+        _ => derive_tessellatable_internal(item.into()),
+    };
+    let res = match res {
         Ok(res) => res,
         Err(err) => err.to_compile_error(),
     };
@@ -1,12 +1,15 @@
-use epaint::{ClippedPrimitive, ClippedShape, Rect, TessellationOptions};
+use epaint::{ClippedPrimitive, Color32, PathShape, Rect, Shape, Stroke};
 use radiantkit_core::{
-    get_color_for_node, BaseNode, RadiantNode, RadiantTessellatable, ScreenDescriptor, Vec3,
+    distance_to_polyline, polygon_contains, BaseNode, RadiantNode, RadiantShapes,
+    RadiantTessellatable, ScreenDescriptor, Vec3, PICKING_COLOR,
 };
+use radiantkit_macros::RadiantTessellatable;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, RadiantTessellatable)]
+#[radiant_node(build_shapes = build_shapes)]
 pub struct RadiantPathNode {
     pub base: BaseNode,
 }
@@ -17,103 +20,45 @@ impl RadiantPathNode {
         Self { base }
     }
 
-    fn tessellate(&mut self, pixels_per_point: f32) {
-        if !self.base.needs_tessellation {
-            return;
-        }
-        self.base.needs_tessellation = false;
-
+    fn points(&self) -> Vec<epaint::Pos2> {
         let position = self.base.transform.position();
         let scale = self.base.transform.scale();
 
-        let points = vec![
+        vec![
             position.into(),
             epaint::Pos2::new(position.x + scale.x + 200.0, position.y + scale.y + 200.0),
             epaint::Pos2::new(position.x + scale.x, position.y + scale.y + 400.0),
             epaint::Pos2::new(position.x - 200.0, position.y + 200.0),
-        ];
-
-        let color = epaint::Color32::LIGHT_RED;
-        let stroke = epaint::Stroke::new(1.0, color);
-        let path_shape = epaint::PathShape::convex_polygon(points.clone(), color, stroke);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::Path(path_shape),
-        )];
-        self.base.primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
-
-        let color = get_color_for_node(self.base.id);
-        let stroke = epaint::Stroke::new(1.0, color);
-        let path_shape = epaint::PathShape::convex_polygon(points, color, stroke);
-        let shapes = vec![ClippedShape(
-            Rect::EVERYTHING,
-            epaint::Shape::Path(path_shape),
-        )];
-        self.base.selection_primitives = epaint::tessellator::tessellate_shapes(
-            pixels_per_point,
-            TessellationOptions::default(),
-            [1, 1],
-            vec![],
-            shapes,
-        );
-    }
-}
-
-impl RadiantTessellatable for RadiantPathNode {
-    fn attach(&mut self, screen_descriptor: &ScreenDescriptor) {
-        self.tessellate(screen_descriptor.pixels_per_point);
-    }
-
-    fn detach(&mut self) {
-        self.base.primitives.clear();
-        self.base.selection_primitives.clear();
+        ]
     }
 
-    fn set_needs_tessellation(&mut self, notify: bool) {
-        let position = self.base.transform.position();
-        let scale = self.base.transform.scale();
-
-        let points = vec![
-            position.into(),
-            epaint::Pos2::new(position.x + scale.x + 200.0, position.y + scale.y + 200.0),
-            epaint::Pos2::new(position.x + scale.x, position.y + scale.y + 400.0),
-            epaint::Pos2::new(position.x - 200.0, position.y + 200.0),
-        ];
+    fn build_shapes(&self) -> RadiantShapes {
+        let points = self.points();
+        let stroke_color = self.base.color.stroke_color();
 
-        let color = epaint::Color32::LIGHT_RED;
-        let stroke = epaint::Stroke::new(1.0, color);
-        let path_shape = epaint::PathShape::convex_polygon(points.clone(), color, stroke);
-        let bounding_rect = path_shape.visual_bounding_rect();
-        self.base.bounding_rect = [
-            bounding_rect.min.x,
-            bounding_rect.min.y,
-            bounding_rect.max.x,
-            bounding_rect.max.y,
-        ];
-
-        self.base.set_needs_tessellation();
-        if notify {
-            self.base.notify(serde_json::to_string(self).unwrap());
-        }
-    }
+        let fill = vec![Shape::Path(PathShape::convex_polygon(
+            points.clone(),
+            Color32::WHITE,
+            Stroke::NONE,
+        ))];
+        let shapes = self.base.stroke().shapes(&points, true, stroke_color);
 
-    fn tessellate(
-        &mut self,
-        selection: bool,
-        screen_descriptor: &ScreenDescriptor,
-        _fonts_manager: &epaint::text::Fonts,
-    ) -> Vec<ClippedPrimitive> {
-        self.tessellate(screen_descriptor.pixels_per_point);
-        if selection {
-            self.base.selection_primitives.clone()
+        let color = PICKING_COLOR;
+        let stroke_shapes = if stroke_color == Color32::TRANSPARENT {
+            Vec::new()
         } else {
-            self.base.primitives.clone()
+            self.base.stroke().shapes(&points, true, color)
+        };
+        let path_shape = PathShape::convex_polygon(points.clone(), color, Stroke::new(1.0, color));
+        let selection = std::iter::once(Shape::Path(path_shape))
+            .chain(stroke_shapes)
+            .collect();
+
+        RadiantShapes {
+            bounding_rect: Rect::from_points(&points),
+            fill,
+            shapes,
+            selection,
         }
     }
 }
@@ -123,7 +68,27 @@ impl RadiantNode for RadiantPathNode {
         &self.base
     }
 
+    fn node_type(&self) -> &str {
+        "path"
+    }
+
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
+
+    fn hit_test(&self, position: [f32; 2], tolerance: f32) -> Option<Uuid> {
+        let points = self.points();
+        let position = position.into();
+        let fill_hit =
+            self.base.fill_paint().is_visible() && polygon_contains(position, &points);
+        let stroke_hit = self.base.color.stroke_color().a() > 0
+            && distance_to_polyline(position, &points, true)
+                <= self.base.stroke().width() / 2.0 + tolerance;
+        let hit = fill_hit || stroke_hit;
+        hit.then_some(self.base.id)
+    }
+
+    fn replace(&mut self, node: &str) {
+        self.replace_keeping_observers(node);
+    }
 }
@@ -1,12 +1,12 @@
 use epaint::emath::NumExt;
 use epaint::{
     text::{LayoutJob, TextFormat},
-    ClippedPrimitive, ClippedShape, Color32, FontFamily, FontId, Fonts, Rect, TessellationOptions,
+    ClippedPrimitive, ClippedShape, FontId, Fonts, Rect, TessellationOptions,
 };
 use once_cell::sync::Lazy;
 use radiantkit_core::{
-    get_color_for_node, BaseNode, RadiantLineNode, RadiantNode, RadiantTessellatable,
-    ScreenDescriptor,
+    BaseNode, RadiantLineNode, RadiantNode, RadiantTessellatable, RadiantTextStyle,
+    ScreenDescriptor, TextComponent, PICKING_COLOR,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -16,12 +16,41 @@ use crate::RadiantTextMessage;
 
 const CURSOR_NODE_ID: Lazy<Uuid> = Lazy::new(|| Uuid::new_v4());
 
+/// Node displaying a text, which it carries with its style in a [`TextComponent`].
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "RadiantTextNodeData")]
 pub struct RadiantTextNode {
     pub base: BaseNode,
-    pub text: String,
     #[serde(skip)]
-    pub cursor_node: RadiantLineNode,
+    pub cursor_node: Box<RadiantLineNode>,
+}
+
+/// Serialized form of a text node, which may hold the text of older documents.
+#[derive(Deserialize)]
+struct RadiantTextNodeData {
+    base: BaseNode,
+    /// Text of nodes saved before it was carried in the text component.
+    #[serde(default)]
+    text: Option<String>,
+    /// Style of nodes saved before it was carried in the text component.
+    #[serde(default)]
+    style: Option<RadiantTextStyle>,
+}
+
+impl From<RadiantTextNodeData> for RadiantTextNode {
+    fn from(data: RadiantTextNodeData) -> Self {
+        let mut node = Self {
+            base: data.base,
+            cursor_node: Box::default(),
+        };
+        if let Some(text) = data.text {
+            node.text_mut().set_text(text);
+        }
+        if let Some(style) = data.style {
+            node.text_mut().set_style(style);
+        }
+        node
+    }
 }
 
 impl Debug for RadiantTextNode {
@@ -34,14 +63,20 @@ impl Debug for RadiantTextNode {
 
 impl RadiantTextNode {
     pub fn new(id: Uuid, text: String, position: [f32; 2], scale: [f32; 2]) -> Self {
-        let base = BaseNode::new(id, position.into(), scale.into());
-        let cursor_node = RadiantLineNode::new(*CURSOR_NODE_ID, [0.0, 0.0], [0.0, 0.0]);
+        let mut base = BaseNode::new(id, position.into(), scale.into());
+        base.components.insert(TextComponent::new(text));
+        let cursor_node = Box::new(RadiantLineNode::new(*CURSOR_NODE_ID, [0.0, 0.0], [0.0, 0.0]));
 
-        Self {
-            base,
-            text,
-            cursor_node,
-        }
+        Self { base, cursor_node }
+    }
+
+    pub fn text(&self) -> &TextComponent {
+        static DEFAULT: Lazy<TextComponent> = Lazy::new(TextComponent::default);
+        self.base.get_component().unwrap_or(&DEFAULT)
+    }
+
+    pub fn text_mut(&mut self) -> &mut TextComponent {
+        self.base.components.get_or_insert_default()
     }
 
     fn tessellate(&mut self, screen_descriptor: &ScreenDescriptor, fonts: &Fonts) {
@@ -54,16 +89,17 @@ impl RadiantTextNode {
         let position = self.base.transform.position();
         // let scale = self.transform.scale();
 
-        let font_id = FontId::new(24.0, FontFamily::Proportional);
+        let style = *self.text().style();
+        let font_id = FontId::new(style.font_size, style.font_family.into());
         let row_height = fonts.row_height(&font_id);
 
         let mut job = LayoutJob::default();
         job.append(
-            &self.text,
+            self.text().text(),
             0.0,
             TextFormat {
                 font_id,
-                color: Color32::WHITE,
+                color: style.color,
                 ..Default::default()
             },
         );
@@ -125,6 +161,10 @@ impl RadiantTextNode {
             prepared_discs,
             shapes,
         );
+        // The glyphs are drawn in the color of the style unless the fill is a gradient.
+        if let Some(paint) = &self.base.fill {
+            paint.apply(&mut self.base.primitives, rect);
+        }
 
         if self.base.selection.is_selected() {
             self.base
@@ -132,7 +172,7 @@ impl RadiantTextNode {
                 .append(&mut self.cursor_node.tessellate(false, screen_descriptor, fonts));
         }
 
-        let fill_color = get_color_for_node(self.base.id);
+        let fill_color = PICKING_COLOR;
         let rect_shape = epaint::RectShape::filled(rect, rounding, fill_color);
         let shapes = vec![ClippedShape(
             Rect::EVERYTHING,
@@ -148,6 +188,8 @@ impl RadiantTextNode {
     }
 }
 
+// Not derived from a `build_shapes` function, since laying out the text needs the fonts,
+// which are only given to `tessellate`.
 impl RadiantTessellatable for RadiantTextNode {
     fn attach(&mut self, _screen_descriptor: &ScreenDescriptor) {}
 
@@ -184,6 +226,10 @@ impl RadiantNode for RadiantTextNode {
         &self.base
     }
 
+    fn node_type(&self) -> &str {
+        "text"
+    }
+
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
@@ -191,19 +237,19 @@ impl RadiantNode for RadiantTextNode {
     fn handle_key_down(&mut self, key: radiantkit_core::KeyCode) -> bool {
         let did_update = match key {
             radiantkit_core::KeyCode::Backspace => {
-                self.text.pop();
+                self.text_mut().text_mut().pop();
                 true
             }
             radiantkit_core::KeyCode::Enter => {
-                self.text.push('\n');
+                self.text_mut().text_mut().push('\n');
                 true
             }
             radiantkit_core::KeyCode::Space => {
-                self.text.push(' ');
+                self.text_mut().text_mut().push(' ');
                 true
             }
             radiantkit_core::KeyCode::Char(c) => {
-                self.text.push_str(&c);
+                self.text_mut().text_mut().push_str(&c);
                 true
             }
             _ => false,
@@ -219,7 +265,7 @@ impl RadiantTextNode {
     pub fn handle_message(&mut self, message: RadiantTextMessage) -> bool {
         match message {
             RadiantTextMessage::SetText { text, .. } => {
-                self.text = text;
+                self.text_mut().set_text(text);
                 self.set_needs_tessellation(true);
                 true
             }
@@ -172,7 +172,7 @@ impl RadiantTessellatable for RadiantVideoNode {
             rect.right_bottom().y,
         ];
 
-        self.base.needs_tessellation = true;
+        self.base.set_needs_tessellation();
     }
 
     fn tessellate(
@@ -195,6 +195,10 @@ impl RadiantNode for RadiantVideoNode {
         &self.base
     }
 
+    fn node_type(&self) -> &str {
+        "video"
+    }
+
     fn base_mut(&mut self) -> &mut BaseNode {
         &mut self.base
     }
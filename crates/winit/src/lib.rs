@@ -1,11 +1,15 @@
 use radiantkit_core::{
-    KeyCode, RadiantNode, RadiantScene, RadiantSceneMessage, Runtime, ScreenDescriptor, Vec3, View,
+    KeyCode, RadiantCursor, RadiantGesture, RadiantGestureEvent, RadiantModifiers,
+    RadiantMouseButton, RadiantNode, RadiantPointerEvent, RadiantScene, RadiantSceneMessage,
+    Runtime, ScreenDescriptor, Vec3, View,
 };
 use winit::event_loop::EventLoop;
-use winit::window::{Window, WindowBuilder};
+use winit::window::{CursorIcon, Window, WindowBuilder};
 use winit::{event::*, event_loop::ControlFlow};
 
+use std::collections::BTreeMap;
 use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use uuid::Uuid;
 use winit::dpi::PhysicalSize;
 pub use winit::event::Event::RedrawRequested;
 
@@ -22,10 +26,33 @@ pub struct RadiantView<M, N: RadiantNode> {
 
     pub scene: Arc<RwLock<RadiantScene<M, N>>>,
 
-    mouse_position: [f32; 2],
+    /// Position of the mouse in physical pixels from the top left corner of the window.
+    cursor_position: [f32; 2],
     mouse_dragging: bool,
+    mouse_button: RadiantMouseButton,
+    pressure: f32,
+    /// Time in milliseconds, position and button of the last click, and how many clicks
+    /// in a row it ended.
+    last_click: Option<(f64, [f32; 2], RadiantMouseButton)>,
+    click_count: u32,
+    /// Positions in physical pixels of the fingers on a touch screen.
+    touches: BTreeMap<u64, [f32; 2]>,
+    /// Whether several fingers touched the screen since the last time none did, in which
+    /// case they pan and zoom instead of acting as a mouse.
+    touch_gesture: bool,
+    /// Cursor asked for by the active tool.
+    cursor: RadiantCursor,
 }
 
+/// Longest time in milliseconds between the clicks of a double click.
+const DOUBLE_CLICK_INTERVAL: f64 = 500.0;
+/// Farthest distance in points between the clicks of a double click.
+const DOUBLE_CLICK_DISTANCE: f32 = 4.0;
+/// Points scrolled by a line of mouse wheel scrolling.
+const SCROLL_LINE_HEIGHT: f32 = 50.0;
+/// Zoom factor of scrolling by a point while holding Ctrl or Cmd.
+const SCROLL_ZOOM_SPEED: f32 = 1.01;
+
 impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode>
     RadiantView<M, N>
 {
@@ -167,6 +194,8 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
         let screen_descriptor = ScreenDescriptor {
             size_in_pixels: [size.width, size.height],
             pixels_per_point: window.scale_factor() as f32,
+            offset: [0.0, 0.0],
+            zoom: 1.0,
         };
 
         let scene = RadiantScene::new(config, surface, device, queue, screen_descriptor);
@@ -177,8 +206,15 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
 
             size,
             scene: Arc::new(RwLock::new(scene)),
-            mouse_position: [0.0, 0.0],
+            cursor_position: [0.0, 0.0],
             mouse_dragging: false,
+            mouse_button: RadiantMouseButton::Left,
+            pressure: 1.0,
+            last_click: None,
+            click_count: 0,
+            touches: BTreeMap::new(),
+            touch_gesture: false,
+            cursor: RadiantCursor::Default,
         }
     }
 
@@ -199,84 +235,87 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
             } if window_id == &self.window.id() => {
                 if !self.input(event) {
                     match event {
-                        WindowEvent::CloseRequested
-                        | WindowEvent::KeyboardInput {
-                            input:
-                                KeyboardInput {
-                                    state: ElementState::Pressed,
-                                    virtual_keycode: Some(VirtualKeyCode::Escape),
-                                    ..
-                                },
-                            ..
-                        } => *control_flow = ControlFlow::Exit,
+                        WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                         WindowEvent::Resized(physical_size) => {
                             self.resize(*physical_size);
                         }
                         WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
                             self.resize(**new_inner_size);
                         }
+                        WindowEvent::ModifiersChanged(modifiers) => {
+                            self.scene_mut().modifiers = RadiantModifiers {
+                                shift: modifiers.shift(),
+                                ctrl: modifiers.ctrl(),
+                                alt: modifiers.alt(),
+                                meta: modifiers.logo(),
+                            };
+                        }
                         WindowEvent::MouseInput { state, button, .. } => {
-                            if button == &MouseButton::Left {
-                                let is_pressed = *state == ElementState::Pressed;
+                            let is_pressed = *state == ElementState::Pressed;
+                            self.mouse_button = to_mouse_button(button);
+                            // Only the primary button drags, so that hovering goes on while
+                            // other buttons are held.
+                            if self.mouse_button == RadiantMouseButton::Left {
                                 self.mouse_dragging = is_pressed;
-                                if is_pressed {
-                                    return self.on_mouse_down(self.mouse_position);
-                                    //     self.window.request_redraw();
-                                    // }
-                                } else {
-                                    return self.on_mouse_up(self.mouse_position);
-                                    //     self.window.request_redraw();
-                                    // }
-                                }
+                            }
+                            self.pressure = 1.0;
+                            if is_pressed {
+                                return self.on_mouse_down(self.mouse_position());
+                                //     self.window.request_redraw();
+                                // }
+                            } else {
+                                return self.on_mouse_up(self.mouse_position());
+                                //     self.window.request_redraw();
+                                // }
                             }
                         }
                         WindowEvent::CursorMoved { position, .. } => {
-                            let ScreenDescriptor {
-                                pixels_per_point, ..
-                            } = self.scene().screen_descriptor;
-                            let current_position = [
-                                position.x as f32 / pixels_per_point,
-                                position.y as f32 / pixels_per_point,
-                            ];
-                            self.mouse_position = current_position;
-                            return self.on_mouse_move(self.mouse_position);
+                            self.cursor_position = [position.x as f32, position.y as f32];
+                            return self.on_mouse_move(self.mouse_position());
                             //     self.window.request_redraw();
                             // }
                         }
+                        WindowEvent::CursorLeft { .. } => {
+                            return self.on_hover(None);
+                        }
+                        WindowEvent::MouseWheel { delta, .. } => {
+                            let delta = match delta {
+                                MouseScrollDelta::LineDelta(x, y) => {
+                                    [x * SCROLL_LINE_HEIGHT, y * SCROLL_LINE_HEIGHT]
+                                }
+                                MouseScrollDelta::PixelDelta(delta) => {
+                                    let scale_factor = self.window.scale_factor();
+                                    [
+                                        (delta.x / scale_factor) as f32,
+                                        (delta.y / scale_factor) as f32,
+                                    ]
+                                }
+                            };
+                            return self.on_gesture(
+                                self.mouse_position(),
+                                RadiantGesture::Scroll { delta },
+                            );
+                        }
+                        WindowEvent::TouchpadMagnify { delta, .. } => {
+                            let gesture = RadiantGesture::Pinch {
+                                factor: 1.0 + *delta as f32,
+                                delta: [0.0, 0.0],
+                            };
+                            return self.on_gesture(self.mouse_position(), gesture);
+                        }
                         WindowEvent::Touch(Touch {
-                            location, phase, ..
+                            location,
+                            phase,
+                            force,
+                            id,
+                            ..
                         }) => {
-                            let ScreenDescriptor {
-                                pixels_per_point, ..
-                            } = self.scene().screen_descriptor;
                             let window_origin = self.window.outer_position().unwrap_or_default();
-                            let current_position = [
-                                (location.x as f32 - window_origin.x as f32) / pixels_per_point,
-                                (location.y as f32 - window_origin.y as f32) / pixels_per_point,
+                            let position = [
+                                location.x as f32 - window_origin.x as f32,
+                                location.y as f32 - window_origin.y as f32,
                             ];
-                            self.mouse_position = current_position;
-                            match phase {
-                                TouchPhase::Started => {
-                                    return self.on_mouse_down(self.mouse_position);
-                                    //     self.window.request_redraw();
-                                    // }
-                                }
-                                TouchPhase::Moved => {
-                                    return self.on_mouse_move(self.mouse_position);
-                                    //     self.window.request_redraw();
-                                    // }
-                                }
-                                TouchPhase::Ended => {
-                                    return self.on_mouse_up(self.mouse_position);
-                                    //     self.window.request_redraw();
-                                    // }
-                                }
-                                TouchPhase::Cancelled => {
-                                    return self.on_mouse_up(self.mouse_position);
-                                    //     self.window.request_redraw();
-                                    // }
-                                }
-                            }
+                            return self.on_touch(*id, *phase, position, *force);
                         }
                         WindowEvent::KeyboardInput { input, .. } => {
                             if input.state == ElementState::Pressed {
@@ -288,6 +327,7 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
                 }
             }
             Event::RedrawRequested(window_id) if window_id == &self.window.id() => {
+                self.update_cursor();
                 let size = self.size;
                 let needs_resize = match self.scene_mut().render() {
                     Ok(_) => false,
@@ -318,36 +358,256 @@ impl<M: From<RadiantSceneMessage> + TryInto<RadiantSceneMessage>, N: RadiantNode
     RadiantView<M, N>
 {
     pub fn on_mouse_down(&mut self, position: [f32; 2]) -> Option<M> {
-        let id = pollster::block_on(self.scene_mut().select(position));
+        self.count_click(position);
+        let mut event = self.pointer_event(position);
+        let id = self.scene().select(position);
+        event.target = id.and_then(|id| self.scene().pointer_target(id));
         self.scene_mut()
             .tool_manager
             .active_tool()
-            .on_mouse_down(id, position)
+            .on_mouse_down(id, &event)
     }
 
     pub fn on_mouse_move(&mut self, position: [f32; 2]) -> Option<M> {
-        self.scene_mut()
+        let event = self.pointer_event(position);
+        let message = self
+            .scene_mut()
             .tool_manager
             .active_tool()
-            .on_mouse_move(position)
+            .on_mouse_move(&event);
+        if message.is_some() || self.mouse_dragging {
+            return message;
+        }
+        let id = self.scene().node_at(position);
+        self.on_hover(id)
+    }
+
+    fn on_hover(&mut self, id: Option<Uuid>) -> Option<M> {
+        if self.scene().hovered_node_id == id {
+            return None;
+        }
+        Some(RadiantSceneMessage::HoverNode { id }.into())
     }
 
     pub fn on_mouse_up(&mut self, position: [f32; 2]) -> Option<M> {
+        let event = self.pointer_event(position);
         self.scene_mut()
             .tool_manager
             .active_tool()
-            .on_mouse_up(position)
+            .on_mouse_up(&event)
     }
 
-    pub fn on_key_down(&mut self, input: &Option<VirtualKeyCode>) -> Option<M> {
-        if let Some(keycode) = input {
-            self.scene_mut()
-                .tool_manager
-                .active_tool()
-                .on_key_down(to_keycode(keycode))
-        } else {
-            None
+    /// Document position of the mouse.
+    fn mouse_position(&self) -> [f32; 2] {
+        self.scene()
+            .screen_descriptor
+            .position_in_points(self.cursor_position)
+    }
+
+    pub fn on_gesture(&mut self, position: [f32; 2], gesture: RadiantGesture) -> Option<M> {
+        let modifiers = self.modifiers();
+        let event = RadiantGestureEvent {
+            position,
+            gesture,
+            modifiers,
+        };
+        let message = self
+            .scene_mut()
+            .tool_manager
+            .active_tool()
+            .on_gesture(&event);
+        if message.is_some() {
+            return message;
+        }
+        let message = match gesture {
+            // Trackpad pinches also come as scrolls with Ctrl in browsers.
+            RadiantGesture::Scroll { delta } if modifiers.ctrl || modifiers.meta => {
+                RadiantSceneMessage::ZoomCamera {
+                    factor: SCROLL_ZOOM_SPEED.powf(delta[1]),
+                    position: Some(position),
+                }
+            }
+            // Shift scrolls sideways with wheels that only scroll vertically.
+            RadiantGesture::Scroll { delta } if modifiers.shift && delta[0] == 0.0 => {
+                RadiantSceneMessage::PanCamera {
+                    delta: [delta[1], 0.0],
+                }
+            }
+            RadiantGesture::Scroll { delta } => RadiantSceneMessage::PanCamera { delta },
+            RadiantGesture::Pinch { factor, delta } => {
+                let mut camera = self.scene().camera;
+                camera.zoom_at(factor, position);
+                camera.pan(delta);
+                RadiantSceneMessage::SetCamera {
+                    offset: camera.offset,
+                    zoom: camera.zoom,
+                }
+            }
+        };
+        Some(message.into())
+    }
+
+    /// Acts as a mouse for a single finger, and pans and zooms with two.
+    fn on_touch(
+        &mut self,
+        id: u64,
+        phase: TouchPhase,
+        position: [f32; 2],
+        force: Option<Force>,
+    ) -> Option<M> {
+        let previous = self.touch_gesture_points();
+        match phase {
+            TouchPhase::Started | TouchPhase::Moved => {
+                self.touches.insert(id, position);
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.remove(&id);
+            }
+        }
+
+        if self.touches.len() > 1 && !self.touch_gesture {
+            self.touch_gesture = true;
+            // The first finger stops dragging as soon as the second one touches.
+            if self.mouse_dragging {
+                self.mouse_dragging = false;
+                return self.on_mouse_up(self.mouse_position());
+            }
+            return None;
+        }
+        if self.touch_gesture {
+            if self.touches.is_empty() {
+                self.touch_gesture = false;
+            }
+            let ((start, start_distance), (end, end_distance)) =
+                previous.zip(self.touch_gesture_points())?;
+            if phase != TouchPhase::Moved || start_distance <= 0.0 {
+                return None;
+            }
+            let scale_factor = self.window.scale_factor() as f32;
+            let gesture = RadiantGesture::Pinch {
+                factor: end_distance / start_distance,
+                delta: [
+                    (end[0] - start[0]) / scale_factor,
+                    (end[1] - start[1]) / scale_factor,
+                ],
+            };
+            let position = self.scene().screen_descriptor.position_in_points(start);
+            return self.on_gesture(position, gesture);
         }
+
+        self.cursor_position = position;
+        self.mouse_button = RadiantMouseButton::Left;
+        self.pressure = force.map_or(1.0, |force| force.normalized() as f32);
+        match phase {
+            TouchPhase::Started => {
+                self.mouse_dragging = true;
+                self.on_mouse_down(self.mouse_position())
+            }
+            TouchPhase::Moved => self.on_mouse_move(self.mouse_position()),
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.mouse_dragging = false;
+                self.on_mouse_up(self.mouse_position())
+            }
+        }
+    }
+
+    /// Center of the first two fingers and the distance between them, in physical pixels.
+    fn touch_gesture_points(&self) -> Option<([f32; 2], f32)> {
+        let mut touches = self.touches.values();
+        let (a, b) = touches.next().zip(touches.next())?;
+        let center = [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0];
+        Some((center, (b[0] - a[0]).hypot(b[1] - a[1])))
+    }
+
+    /// Modifier keys held, kept by the scene which also needs them.
+    fn modifiers(&self) -> RadiantModifiers {
+        self.scene().modifiers
+    }
+
+    fn pointer_event(&self, position: [f32; 2]) -> RadiantPointerEvent {
+        RadiantPointerEvent {
+            position,
+            button: self.mouse_button,
+            modifiers: self.modifiers(),
+            click_count: self.click_count,
+            pressure: self.pressure,
+            target: None,
+        }
+    }
+
+    fn update_cursor(&mut self) {
+        let cursor = self.scene_mut().tool_manager.active_tool().cursor();
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            self.window.set_cursor_icon(to_cursor_icon(cursor));
+        }
+    }
+
+    fn count_click(&mut self, position: [f32; 2]) {
+        let time = now();
+        let is_repeat = self.last_click.is_some_and(|(last_time, last_position, button)| {
+            button == self.mouse_button
+                && time - last_time <= DOUBLE_CLICK_INTERVAL
+                && (position[0] - last_position[0]).hypot(position[1] - last_position[1])
+                    <= DOUBLE_CLICK_DISTANCE
+        });
+        self.click_count = if is_repeat { self.click_count + 1 } else { 1 };
+        self.last_click = Some((time, position, self.mouse_button));
+    }
+
+    pub fn on_key_down(&mut self, input: &Option<VirtualKeyCode>) -> Option<M> {
+        let key = to_keycode(input.as_ref()?);
+        let message = self
+            .scene_mut()
+            .tool_manager
+            .active_tool()
+            .on_key_down(key.clone());
+        message.or_else(|| {
+            Some(
+                RadiantSceneMessage::HandleKey {
+                    id: None,
+                    key,
+                    modifiers: self.modifiers(),
+                }
+                .into(),
+            )
+        })
+    }
+}
+
+/// Current time in milliseconds.
+fn now() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0.0, |duration| duration.as_secs_f64() * 1000.0)
+    }
+}
+
+fn to_mouse_button(button: &MouseButton) -> RadiantMouseButton {
+    match button {
+        MouseButton::Left => RadiantMouseButton::Left,
+        MouseButton::Right => RadiantMouseButton::Right,
+        MouseButton::Middle => RadiantMouseButton::Middle,
+        MouseButton::Other(button) => RadiantMouseButton::Other(*button),
+    }
+}
+
+fn to_cursor_icon(cursor: RadiantCursor) -> CursorIcon {
+    match cursor {
+        RadiantCursor::Default => CursorIcon::Default,
+        RadiantCursor::Pointer => CursorIcon::Hand,
+        RadiantCursor::Crosshair => CursorIcon::Crosshair,
+        RadiantCursor::Move => CursorIcon::Move,
+        RadiantCursor::Text => CursorIcon::Text,
+        RadiantCursor::Grab => CursorIcon::Grab,
+        RadiantCursor::Grabbing => CursorIcon::Grabbing,
+        RadiantCursor::NotAllowed => CursorIcon::NotAllowed,
     }
 }
 
@@ -363,6 +623,9 @@ fn to_keycode(keycode: &VirtualKeyCode) -> KeyCode {
         VirtualKeyCode::Left => KeyCode::ArrowLeft,
         VirtualKeyCode::Right => KeyCode::ArrowRight,
         VirtualKeyCode::Up => KeyCode::ArrowUp,
+        VirtualKeyCode::Equals => KeyCode::Char("=".to_string()),
+        VirtualKeyCode::Plus | VirtualKeyCode::NumpadAdd => KeyCode::Char("+".to_string()),
+        VirtualKeyCode::Minus | VirtualKeyCode::NumpadSubtract => KeyCode::Char("-".to_string()),
         VirtualKeyCode::Key0 => KeyCode::Char("0".to_string()),
         VirtualKeyCode::Key1 => KeyCode::Char("1".to_string()),
         VirtualKeyCode::Key2 => KeyCode::Char("2".to_string()),
@@ -1,4 +1,7 @@
-use radiantkit_core::{RadiantSceneMessage, RadiantSceneResponse};
+use radiantkit_core::{
+    RadiantCustomMessage, RadiantInstanceMessage, RadiantLineMessage, RadiantSceneMessage,
+    RadiantSceneResponse,
+};
 use radiantkit_macros::{combine_enum, combine_response, nested_message};
 use radiantkit_text::RadiantTextMessage;
 
@@ -7,6 +10,9 @@ use serde::{Deserialize, Serialize};
 
 #[nested_message]
 #[combine_enum(radiantkit_core::RadiantRectangleMessage)]
+#[combine_enum(radiantkit_core::RadiantAddLineMessage)]
+#[combine_enum(radiantkit_core::RadiantAddInstanceMessage)]
+#[combine_enum(radiantkit_core::RadiantAddCustomNodeMessage)]
 #[combine_enum(radiantkit_image::RadiantImageMessage)]
 #[combine_enum(radiantkit_text::RadiantAddTextMessage)]
 #[cfg_attr(
@@ -17,6 +23,9 @@ use serde::{Deserialize, Serialize};
 pub enum RadiantMessage {
     SceneMessage(RadiantSceneMessage),
     TextMessage(RadiantTextMessage),
+    LineMessage(RadiantLineMessage),
+    InstanceMessage(RadiantInstanceMessage),
+    CustomMessage(RadiantCustomMessage),
 }
 
 #[combine_response(radiantkit_core::RadiantSceneResponse<RadiantMessage, RadiantNodeType>)]
@@ -1,6 +1,7 @@
 use epaint::ClippedPrimitive;
 use radiantkit_core::{
-    RadiantGroupNode, RadiantNode, RadiantRectangleNode, RadiantTessellatable, ScreenDescriptor,
+    RadiantCustomNode, RadiantGroupNode, RadiantInstanceNode, RadiantLineNode, RadiantNode,
+    RadiantRectangleNode, RadiantTessellatable, ScreenDescriptor,
 };
 use radiantkit_image::RadiantImageNode;
 use radiantkit_macros::{RadiantNode, RadiantTessellatable};
@@ -12,9 +13,13 @@ use serde::{Deserialize, Serialize};
 pub enum RadiantNodeType {
     Artboard(RadiantGroupNode<RadiantNodeType>),
     Rectangle(RadiantRectangleNode),
+    Line(RadiantLineNode),
     Path(RadiantPathNode),
     Image(RadiantImageNode),
     Text(RadiantTextNode),
+    Instance(RadiantInstanceNode<RadiantNodeType>),
+    /// Node of a type registered by another crate.
+    Custom(RadiantCustomNode),
     #[cfg(all(not(target_arch = "wasm32"), feature = "video"))]
     Video(radiantkit_video::RadiantVideoNode),
 }
@@ -4,8 +4,10 @@ use crate::{RadiantMessage, RadiantNodeType, RadiantResponse, RadiantToolType};
 use parking_lot::RwLockWriteGuard;
 use radiantkit_collaboration::Collaborator;
 use radiantkit_core::{
-    RadiantRectangleNode, RadiantSceneMessage, RadiantSceneResponse, RadiantTessellatable,
-    RectangleTool, Runtime, Vec3, View,
+    LineTool, RadiantInstanceNode, RadiantKeyChord, RadiantLineNode,
+    RadiantNode, RadiantRectangleNode, RadiantSceneMessage, RadiantSceneResponse,
+    RadiantTessellatable, RadiantTool, RadiantTypedComponent, RadiantTypedNode, RectangleTool,
+    Runtime, ToolId, Vec3, View,
 };
 use radiantkit_image::{image_loader, RadiantImageNode};
 use radiantkit_text::RadiantTextNode;
@@ -23,6 +25,15 @@ impl RadiantRuntime {
             RadiantToolType::Rectangle as u32,
             Box::new(RectangleTool::new()),
         );
+        view.scene_mut()
+            .tool_manager
+            .register_tool(RadiantToolType::Line as u32, Box::new(LineTool::new()));
+        for (key, tool) in [("r", RadiantToolType::Rectangle), ("l", RadiantToolType::Line)] {
+            view.scene_mut().keymap.bind(
+                RadiantKeyChord::char(key),
+                RadiantSceneMessage::SelectTool { id: tool as u32 },
+            );
+        }
         if collaborate {
             let doc = Arc::downgrade(&view.scene_mut().document.clone());
             if let Ok(collaborator) = Collaborator::new(client_id, doc).await {
@@ -33,6 +44,29 @@ impl RadiantRuntime {
         }
         Self { view }
     }
+
+    /// Registers a node type of another crate, so that documents can hold its nodes.
+    pub fn register_node_type<T: RadiantTypedNode>(&mut self) {
+        self.view
+            .scene_mut()
+            .document_mut()
+            .registry
+            .register_node_type::<T>();
+    }
+
+    /// Registers a component type of another crate, so that nodes can carry its components.
+    pub fn register_component_type<T: RadiantTypedComponent>(&mut self) {
+        self.view
+            .scene_mut()
+            .document_mut()
+            .register_component_type::<T>();
+    }
+
+    /// Registers a tool of another crate under the first free id, which is returned. The
+    /// tool can also be activated by name.
+    pub fn register_tool<T: RadiantTool<RadiantMessage> + 'static>(&mut self, tool: T) -> ToolId {
+        self.view.scene_mut().tool_manager.add_tool(Box::new(tool))
+    }
 }
 
 impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRuntime {
@@ -47,6 +81,10 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
     }
 
     fn handle_message(&mut self, message: RadiantMessage) -> Option<RadiantResponse> {
+        // Scene messages begin their own undo steps.
+        if !matches!(message, RadiantMessage::SceneMessage(_)) {
+            self.view.scene_mut().document_mut().begin_edit(None);
+        }
         match message {
             RadiantMessage::SceneMessage(message) => {
                 let response = self.view.scene_mut().handle_message(message);
@@ -84,6 +122,67 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
                         .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
                 }
             }
+            RadiantMessage::LineMessage(message) => {
+                let id = message.id();
+                let scene = self.view.scene_mut();
+                let mut document = scene.document.try_write()?;
+                let node = document.get_node_mut(id)?;
+                let Ok(mut line_node) = RwLockWriteGuard::try_map(node, |node| match node {
+                    RadiantNodeType::Line(line_node) => { Some(line_node) },
+                    _ => { None }
+                }) else {
+                    return None;
+                };
+                line_node.handle_message(message);
+            }
+            RadiantMessage::InstanceMessage(message) => {
+                let id = message.id();
+                let scene = self.view.scene_mut();
+                let mut document = scene.document.try_write()?;
+                let node = document.get_node_mut(id)?;
+                let Ok(mut instance_node) = RwLockWriteGuard::try_map(node, |node| match node {
+                    RadiantNodeType::Instance(instance_node) => { Some(instance_node) },
+                    _ => { None }
+                }) else {
+                    return None;
+                };
+                instance_node.handle_message(message);
+            }
+            RadiantMessage::CustomMessage(message) => {
+                let id = message.id;
+                let update_interactions;
+                {
+                    let scene = self.view.scene_mut();
+                    let mut document = scene.document.try_write()?;
+                    let node = document.get_node_mut(id)?;
+                    let Ok(mut custom_node) = RwLockWriteGuard::try_map(node, |node| match node {
+                        RadiantNodeType::Custom(custom_node) => Some(custom_node),
+                        _ => None,
+                    }) else {
+                        return None;
+                    };
+                    update_interactions = custom_node.handle_message(message.message);
+                }
+                if update_interactions {
+                    return self
+                        .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
+                }
+            }
+            RadiantMessage::AddCustomNode { id, type_tag, node } => {
+                let registry = self.view.scene().document().registry.clone();
+                let mut node = match registry.custom_node(&type_tag, node) {
+                    Ok(node) => node,
+                    Err(error) => {
+                        log::error!("Failed to add custom node: {}", error);
+                        return None;
+                    }
+                };
+                let id = id.unwrap_or(Uuid::new_v4());
+                node.set_id(id);
+                self.view.scene_mut().add(node.into());
+                return self
+                    .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
+            }
             RadiantMessage::AddRectangle {
                 id,
                 position,
@@ -95,6 +194,31 @@ impl Runtime<'_, RadiantMessage, RadiantNodeType, RadiantResponse> for RadiantRu
                 return self
                     .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
             }
+            RadiantMessage::AddLine { id, start, end } => {
+                let id = id.unwrap_or(Uuid::new_v4());
+                let node = RadiantLineNode::new(id, start, end);
+                self.view.scene_mut().add(node.into());
+                return self
+                    .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
+            }
+            RadiantMessage::AddInstance {
+                id,
+                symbol_id,
+                position,
+            } => {
+                let symbol = self
+                    .view
+                    .scene()
+                    .document()
+                    .symbols
+                    .get(&symbol_id)?
+                    .clone();
+                let id = id.unwrap_or(Uuid::new_v4());
+                let node = RadiantInstanceNode::new(id, symbol, position);
+                self.view.scene_mut().add(node.into());
+                return self
+                    .handle_message(RadiantSceneMessage::SelectNode { id: Some(id) }.into());
+            }
             RadiantMessage::AddImage { path, name } => {
                 let screen_descriptor = self.view.scene().screen_descriptor;
                 let texture_manager = self.view.scene_mut().texture_manager.clone();
@@ -2,4 +2,5 @@
 pub enum RadiantToolType {
     Select = 0, // Default
     Rectangle = 1,
+    Line = 2,
 }
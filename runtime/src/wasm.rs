@@ -1,4 +1,4 @@
-use crate::{RadiantRuntime, Runtime, Vec3};
+use crate::{RadiantKeymap, RadiantRuntime, Runtime, Vec3, View};
 use std::sync::{Arc, RwLock};
 use wasm_bindgen::prelude::*;
 
@@ -57,4 +57,33 @@ impl RadiantKitAppController {
             log::error!("Couldn't deserialize message {:?}", message);
         }
     }
+
+    pub fn keymap(&self) -> JsValue {
+        let Ok(runtime) = self.runtime.read() else {
+            return JsValue::null();
+        };
+        let keymap = runtime.view().scene().keymap.clone();
+        serde_wasm_bindgen::to_value(&keymap).unwrap_or(JsValue::null())
+    }
+
+    #[wasm_bindgen(js_name = layerTree)]
+    pub fn layer_tree(&self) -> JsValue {
+        let Ok(runtime) = self.runtime.read() else {
+            return JsValue::null();
+        };
+        let layer_tree = runtime.view().scene().document().layer_tree();
+        serde_wasm_bindgen::to_value(&layer_tree).unwrap_or(JsValue::null())
+    }
+
+    #[wasm_bindgen(js_name = setKeymap)]
+    pub fn set_keymap(&mut self, keymap: JsValue) {
+        match serde_wasm_bindgen::from_value::<RadiantKeymap>(keymap.clone()) {
+            Ok(keymap) => {
+                if let Ok(mut runtime) = self.runtime.write() {
+                    runtime.view_mut().scene_mut().keymap = keymap;
+                }
+            }
+            Err(_) => log::error!("Couldn't deserialize keymap {:?}", keymap),
+        }
+    }
 }
@@ -36,6 +36,96 @@ export class RadiantKitController {
         });
     }
 
+    /**
+     * Activates the tool registered under the provided name.
+     *
+     * @param name the name of the tool, such as "select", "rectangle" or "line".
+     */
+    activateToolByName(name: string) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SelectToolByName: {
+                    name,
+                },
+            },
+        });
+    }
+
+    /**
+     * Abandons what the active tool is doing, such as the node being drawn.
+     */
+    cancelTool() {
+        this._controller.handleMessage({
+            SceneMessage: {
+                CancelTool: {},
+            },
+        });
+    }
+
+    /**
+     * Returns the artboards with their nodes, each with its id, name, type,
+     * visibility, lock and children from the topmost down.
+     */
+    layerTree(): any {
+        return this._controller.layerTree();
+    }
+
+    /**
+     * Returns the key bindings, each a chord with the message it sends.
+     */
+    keymap(): any {
+        return this._controller.keymap();
+    }
+
+    /**
+     * Replaces the key bindings.
+     *
+     * @param keymap the bindings, as returned by `keymap`.
+     */
+    setKeymap(keymap: any) {
+        this._controller.setKeymap(keymap);
+    }
+
+    /**
+     * Moves the document on screen.
+     *
+     * @param delta the distance to move by, in screen points.
+     */
+    panCamera(delta: number[]) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                PanCamera: {
+                    delta,
+                },
+            },
+        });
+    }
+
+    /**
+     * Scales the zoom around a document position, or around the center of the canvas.
+     */
+    zoomCamera(factor: number, position: number[] | null = null) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                ZoomCamera: {
+                    factor,
+                    position,
+                },
+            },
+        });
+    }
+
+    setCamera(offset: number[], zoom: number) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetCamera: {
+                    offset,
+                    zoom,
+                },
+            },
+        });
+    }
+
     addRectangle(position: number[] = [100, 100], scale: number[] = [100, 100]) {
         this._controller.handleMessage({
             AddRectangle: {
@@ -45,6 +135,15 @@ export class RadiantKitController {
         });
     }
 
+    addLine(start: number[] = [100, 100], end: number[] = [200, 200]) {
+        this._controller.handleMessage({
+            AddLine: {
+                start,
+                end,
+            },
+        });
+    }
+
     addImage(path: string, name: string = "", position: number[] = [100, 100], scale: number[] =[100, 100]) {
         this._controller.handleMessage({
             AddImage: {
@@ -64,6 +163,34 @@ export class RadiantKitController {
         document.getElementById("radiantkit-canvas")?.focus();
     }
 
+    /**
+     * Adds a node of a type registered by another crate.
+     *
+     * @param typeTag the tag the node type is registered under.
+     * @param node the fields of the node.
+     */
+    addCustomNode(typeTag: string, node: any, id?: string) {
+        this._controller.handleMessage({
+            AddCustomNode: {
+                id,
+                type_tag: typeTag,
+                node,
+            },
+        });
+    }
+
+    /**
+     * Sends a message to a node of a type registered by another crate.
+     */
+    sendCustomMessage(nodeId: string, message: any) {
+        this._controller.handleMessage({
+            CustomMessage: {
+                id: nodeId,
+                message,
+            },
+        });
+    }
+
     setTransform(nodeId: string, position: number[], scale: number[]) {
         this._controller.handleMessage({
             SceneMessage: {
@@ -76,6 +203,17 @@ export class RadiantKitController {
         });
     }
 
+    setCornerRadius(nodeId: string, cornerRadius: number[]) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetCornerRadius: {
+                    id: nodeId,
+                    corner_radius: cornerRadius,
+                },
+            },
+        });
+    }
+
     setFillColor(nodeId: string, color: number[]) {
         this._controller.handleMessage({
             SceneMessage: {
@@ -87,6 +225,17 @@ export class RadiantKitController {
         });
     }
 
+    setFill(nodeId: string, paint: object) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetFill: {
+                    id: nodeId,
+                    paint,
+                },
+            },
+        });
+    }
+
     setStrokeColor(nodeId: string, color: number[]) {
         this._controller.handleMessage({
             SceneMessage: {
@@ -98,6 +247,331 @@ export class RadiantKitController {
         });
     }
 
+    setStroke(nodeId: string, stroke: { width?: number, alignment?: string, dash_pattern?: number[], cap?: string, join?: string }) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetStroke: {
+                    id: nodeId,
+                    stroke,
+                },
+            },
+        });
+    }
+
+    /**
+     * Sets a component of a node, such as its "metadata".
+     *
+     * @param typeTag the tag the component type is registered under.
+     * @param value the serialized component.
+     */
+    setComponent(nodeId: string, typeTag: string, value: any) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetComponent: {
+                    id: nodeId,
+                    type_tag: typeTag,
+                    value,
+                },
+            },
+        });
+    }
+
+    removeComponent(nodeId: string, typeTag: string) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                RemoveComponent: {
+                    id: nodeId,
+                    type_tag: typeTag,
+                },
+            },
+        });
+    }
+
+    renameNode(nodeId: string, name: string) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                RenameNode: {
+                    id: nodeId,
+                    name,
+                },
+            },
+        });
+    }
+
+    setVisible(nodeId: string, visible: boolean) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetVisible: {
+                    id: nodeId,
+                    visible,
+                },
+            },
+        });
+    }
+
+    setLocked(nodeId: string, locked: boolean) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetLocked: {
+                    id: nodeId,
+                    locked,
+                },
+            },
+        });
+    }
+
+    setOpacity(nodeId: string, opacity: number) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetOpacity: {
+                    id: nodeId,
+                    opacity,
+                },
+            },
+        });
+    }
+
+    setBlendMode(nodeId: string, blendMode: "Normal" | "Multiply" | "Screen" | "Overlay") {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetBlendMode: {
+                    id: nodeId,
+                    blend_mode: blendMode,
+                },
+            },
+        });
+    }
+
+    setEffects(nodeId: string, effects: object[]) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetEffects: {
+                    id: nodeId,
+                    effects,
+                },
+            },
+        });
+    }
+
+    setMask(nodeId: string, mask: boolean) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetMask: {
+                    id: nodeId,
+                    mask,
+                },
+            },
+        });
+    }
+
+    setClipContent(artboardId: string, clipContent: boolean) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetClipContent: {
+                    id: artboardId,
+                    clip_content: clipContent,
+                },
+            },
+        });
+    }
+
+    setAutoLayout(artboardId: string, autoLayout: object | null) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetAutoLayout: {
+                    id: artboardId,
+                    auto_layout: autoLayout,
+                },
+            },
+        });
+    }
+
+    setLayoutSizing(nodeId: string, width: "Fixed" | "Hug" | "Fill", height: "Fixed" | "Hug" | "Fill") {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetLayoutSizing: {
+                    id: nodeId,
+                    sizing: [width, height],
+                },
+            },
+        });
+    }
+
+    setConstraints(nodeId: string, horizontal: "Start" | "End" | "StartAndEnd" | "Center" | "Scale", vertical: "Start" | "End" | "StartAndEnd" | "Center" | "Scale") {
+        this._controller.handleMessage({
+            SceneMessage: {
+                SetConstraints: {
+                    id: nodeId,
+                    constraints: [horizontal, vertical],
+                },
+            },
+        });
+    }
+
+    hoverNode(nodeId: string | null) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                HoverNode: {
+                    id: nodeId,
+                },
+            },
+        });
+    }
+
+    removeNode(nodeId: string) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                RemoveNode: {
+                    id: nodeId,
+                },
+            },
+        });
+    }
+
+    nudgeSelectedNode(delta: number[]) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                NudgeSelectedNode: {
+                    delta,
+                },
+            },
+        });
+    }
+
+    duplicateNode(nodeId: string, newNodeId: string) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                DuplicateNode: {
+                    id: nodeId,
+                    new_id: newNodeId,
+                },
+            },
+        });
+    }
+
+    editNode(nodeId: string) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                EditNode: {
+                    id: nodeId,
+                },
+            },
+        });
+    }
+
+    openContextMenu(nodeId: string | null, position: number[]) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                OpenContextMenu: {
+                    id: nodeId,
+                    position,
+                },
+            },
+        });
+    }
+
+    resizeArtboard(artboardId: string, position: number[], size: number[]) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                ResizeArtboard: {
+                    id: artboardId,
+                    position,
+                    size,
+                },
+            },
+        });
+    }
+
+    createSymbol(name: string, nodeIds: string[]) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                CreateSymbol: {
+                    id: null,
+                    name,
+                    node_ids: nodeIds,
+                },
+            },
+        });
+    }
+
+    addInstance(symbolId: string, position: number[]) {
+        this._controller.handleMessage({
+            AddInstance: {
+                id: null,
+                symbol_id: symbolId,
+                position,
+            },
+        });
+    }
+
+    setInstanceOverride(nodeId: string, masterNodeId: string, value: { text?: string, fill_color?: number[], stroke_color?: number[], visible?: boolean }) {
+        this._controller.handleMessage({
+            InstanceMessage: {
+                SetOverride: {
+                    id: nodeId,
+                    node_id: masterNodeId,
+                    value,
+                },
+            },
+        });
+    }
+
+    resetInstanceOverrides(nodeId: string) {
+        this._controller.handleMessage({
+            InstanceMessage: {
+                ResetOverrides: {
+                    id: nodeId,
+                },
+            },
+        });
+    }
+
+    createStyle(name: string, value: { Color: number[] } | { Text: { font_size?: number, font_family?: "Proportional" | "Monospace", color?: number[] } }) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                CreateStyle: {
+                    id: null,
+                    name,
+                    value,
+                },
+            },
+        });
+    }
+
+    updateStyle(styleId: string, value: { Color: number[] } | { Text: { font_size?: number, font_family?: "Proportional" | "Monospace", color?: number[] } }) {
+        this._controller.handleMessage({
+            SceneMessage: {
+                UpdateStyle: {
+                    id: styleId,
+                    value,
+                },
+            },
+        });
+    }
+
+    applyStyle(nodeId: string, styleId: string, target: "Fill" | "Stroke" | "Text") {
+        this._controller.handleMessage({
+            SceneMessage: {
+                ApplyStyle: {
+                    id: nodeId,
+                    style_id: styleId,
+                    target,
+                },
+            },
+        });
+    }
+
+    detachStyle(nodeId: string, target: "Fill" | "Stroke" | "Text") {
+        this._controller.handleMessage({
+            SceneMessage: {
+                DetachStyle: {
+                    id: nodeId,
+                    target,
+                },
+            },
+        });
+    }
+
     setText(nodeId: string, text: string) {
         this._controller.handleMessage({
             TextMessage: {